@@ -7,12 +7,52 @@ pub fn get_handler() -> impl Fn(Invoke<tauri::Wry>) -> bool + Send + Sync + 'sta
     tauri::generate_handler![
         // Todo Feature Commands
         crate::features::todo::api::commands::list_todos,
+        crate::features::todo::api::commands::query_todos,
+        crate::features::todo::api::commands::search_todos,
         crate::features::todo::api::commands::create_todo,
+        crate::features::todo::api::commands::quick_add_todo,
         crate::features::todo::api::commands::update_todo,
         crate::features::todo::api::commands::delete_todo,
         crate::features::todo::api::commands::update_todo_details,
+        crate::features::todo::api::commands::snooze_todo,
+        crate::features::todo::api::commands::set_todo_estimate,
+        crate::features::todo::api::commands::bulk_todos,
         crate::features::todo::api::commands::get_subtasks,
+        crate::features::todo::api::commands::get_todo_tree,
         crate::features::todo::api::commands::update_todo_parent,
+        crate::features::todo::api::commands::reorder_todo,
+        crate::features::todo::api::commands::get_todo_recurrence_mode,
+        crate::features::todo::api::commands::set_todo_recurrence_mode,
+        crate::features::todo::api::commands::get_todo_subtask_settings,
+        crate::features::todo::api::commands::set_todo_subtask_settings,
+        crate::features::todo::api::commands::list_trashed_todos,
+        crate::features::todo::api::commands::restore_todo,
+        crate::features::todo::api::commands::purge_todo,
+        crate::features::todo::api::commands::empty_trash,
+        crate::features::todo::api::commands::get_trash_retention_days,
+        crate::features::todo::api::commands::set_trash_retention_days,
+        crate::features::todo::api::commands::undo_todo_change,
+        crate::features::todo::api::commands::redo_todo_change,
+        crate::features::todo::api::commands::get_todo_history_status,
+        crate::features::todo::api::commands::list_tags,
+        crate::features::todo::api::commands::rename_tag,
+        crate::features::todo::api::commands::merge_tags,
+        crate::features::todo::api::commands::delete_tag,
+        crate::features::todo::api::commands::set_tag_color,
+        crate::features::todo::api::commands::list_todo_lists,
+        crate::features::todo::api::commands::create_todo_list,
+        crate::features::todo::api::commands::update_todo_list,
+        crate::features::todo::api::commands::delete_todo_list,
+        crate::features::todo::api::commands::move_todo_to_list,
+        crate::features::todo::api::commands::get_todo_dependencies,
+        crate::features::todo::api::commands::add_todo_dependency,
+        crate::features::todo::api::commands::remove_todo_dependency,
+        crate::features::todo::api::commands::get_todo_reminders,
+        crate::features::todo::api::commands::set_todo_reminders,
+        crate::features::todo::api::commands::add_todo_reminder,
+        crate::features::todo::api::commands::remove_todo_reminder,
+        crate::features::todo::api::commands::get_todo_reminder_settings,
+        crate::features::todo::api::commands::set_todo_reminder_settings,
         // CalDAV Commands
         crate::features::todo::sync::caldav_commands::get_caldav_status,
         crate::features::todo::sync::caldav_commands::save_caldav_config,
@@ -28,13 +68,33 @@ pub fn get_handler() -> impl Fn(Invoke<tauri::Wry>) -> bool + Send + Sync + 'sta
         crate::features::pomodoro::api::commands::pomodoro_pause,
         crate::features::pomodoro::api::commands::pomodoro_resume,
         crate::features::pomodoro::api::commands::pomodoro_skip,
+        crate::features::pomodoro::api::commands::pomodoro_finish,
         crate::features::pomodoro::api::commands::pomodoro_stop,
         crate::features::pomodoro::api::commands::pomodoro_status,
         crate::features::pomodoro::api::commands::pomodoro_get_config,
         crate::features::pomodoro::api::commands::pomodoro_set_config,
+        crate::features::pomodoro::api::commands::pomodoro_set_todo,
+        crate::features::pomodoro::api::commands::pomodoro_confirm,
+        crate::features::pomodoro::api::commands::pomodoro_adjust,
+        crate::features::pomodoro::api::commands::pomodoro_set_remaining,
+        crate::features::pomodoro::api::commands::pomodoro_todo_stats,
+        crate::features::pomodoro::api::commands::pomodoro_list_presets,
+        crate::features::pomodoro::api::commands::pomodoro_create_preset,
+        crate::features::pomodoro::api::commands::pomodoro_update_preset,
+        crate::features::pomodoro::api::commands::pomodoro_delete_preset,
+        crate::features::pomodoro::api::commands::pomodoro_get_active_preset,
+        crate::features::pomodoro::api::commands::pomodoro_set_active_preset,
         crate::features::pomodoro::api::commands::pomodoro_list_sessions,
         crate::features::pomodoro::api::commands::pomodoro_delete_session,
         crate::features::pomodoro::api::commands::pomodoro_stats,
+        crate::features::pomodoro::api::commands::pomodoro_stats_series,
+        crate::features::pomodoro::api::commands::pomodoro_stats_streaks,
+        crate::features::pomodoro::api::commands::pomodoro_stats_heatmap,
+        crate::features::pomodoro::api::commands::pomodoro_stats_breakdown,
+        crate::features::pomodoro::api::commands::pomodoro_get_goal,
+        crate::features::pomodoro::api::commands::pomodoro_set_goal,
+        crate::features::pomodoro::api::commands::pomodoro_goal_progress,
+        crate::features::pomodoro::api::commands::pomodoro_goal_history,
         // Pomodoro Session Management Commands
         crate::features::pomodoro::api::commands::pomodoro_create_session,
         crate::features::pomodoro::api::commands::pomodoro_get_session,
@@ -1,14 +1,29 @@
 use tauri::State;
 
 use crate::core::AppState;
-use crate::features::pomodoro::core::{models::PomodoroStatus, service, PomodoroConfig};
+use crate::features::pomodoro::core::{
+    analytics::{self, AnalyticsRange, StatsBucket},
+    goals::{self, GoalProgress, PomodoroGoal},
+    models::PomodoroStatus,
+    presets::{self, PomodoroPresetInput},
+    service, PomodoroConfig,
+};
 use crate::features::pomodoro::data::entities::{
-    pomodoro_records as record_entity, pomodoro_sessions as session_entity,
+    pomodoro_presets as preset_entity, pomodoro_records as record_entity,
+    pomodoro_sessions as session_entity,
 };
 
+/// 开始专注，可指定本次专注的待办和使用的预设（未指定时使用当前预设）
+///
+/// `flow` 为 true 时开始心流专注（正向计时）
 #[tauri::command]
-pub async fn pomodoro_start(state: State<'_, AppState>) -> Result<PomodoroStatus, String> {
-    let cfg = service::get_config(state.db())
+pub async fn pomodoro_start(
+    state: State<'_, AppState>,
+    todo_id: Option<i32>,
+    preset_id: Option<i32>,
+    flow: Option<bool>,
+) -> Result<PomodoroStatus, String> {
+    let (cfg, preset_id) = presets::start_config(state.db(), preset_id)
         .await
         .map_err(|e| e.to_string())?;
     let feature = state
@@ -21,7 +36,110 @@ pub async fn pomodoro_start(state: State<'_, AppState>) -> Result<PomodoroStatus
     let manager = feature
         .manager()
         .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
-    manager.start(cfg).await.map_err(|e| e.to_string())
+    let started = if flow.unwrap_or(false) {
+        manager.start_flow(cfg, preset_id, todo_id).await
+    } else {
+        manager.start(cfg, preset_id, todo_id).await
+    };
+    started.map_err(|e| e.to_string())
+}
+
+/// 结束心流专注并开始按专注时长计算的休息
+#[tauri::command]
+pub async fn pomodoro_finish(state: State<'_, AppState>) -> Result<PomodoroStatus, String> {
+    let feature = state
+        .get_feature("pomodoro")
+        .ok_or_else(|| "pomodoro feature not found".to_string())?;
+    let feature = feature
+        .as_any()
+        .downcast_ref::<crate::features::pomodoro::PomodoroFeature>()
+        .ok_or_else(|| "invalid pomodoro feature".to_string())?;
+    let manager = feature
+        .manager()
+        .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
+    let cfg = presets::resolve_config(state.db(), manager.status().await.preset_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    manager.finish(cfg).await.map_err(|e| e.to_string())
+}
+
+/// 切换当前专注的待办（为空表示取消关联）
+#[tauri::command]
+pub async fn pomodoro_set_todo(
+    state: State<'_, AppState>,
+    todo_id: Option<i32>,
+) -> Result<PomodoroStatus, String> {
+    let feature = state
+        .get_feature("pomodoro")
+        .ok_or_else(|| "pomodoro feature not found".to_string())?;
+    let feature = feature
+        .as_any()
+        .downcast_ref::<crate::features::pomodoro::PomodoroFeature>()
+        .ok_or_else(|| "invalid pomodoro feature".to_string())?;
+    let manager = feature
+        .manager()
+        .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
+    manager.set_todo(todo_id).await.map_err(|e| e.to_string())
+}
+
+/// 确认开始等待中的阶段（关闭自动开始时使用）
+#[tauri::command]
+pub async fn pomodoro_confirm(state: State<'_, AppState>) -> Result<PomodoroStatus, String> {
+    let feature = state
+        .get_feature("pomodoro")
+        .ok_or_else(|| "pomodoro feature not found".to_string())?;
+    let feature = feature
+        .as_any()
+        .downcast_ref::<crate::features::pomodoro::PomodoroFeature>()
+        .ok_or_else(|| "invalid pomodoro feature".to_string())?;
+    let manager = feature
+        .manager()
+        .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
+    manager.confirm().await.map_err(|e| e.to_string())
+}
+
+/// 延长（正数）或缩短（负数）当前阶段的剩余时间
+#[tauri::command]
+pub async fn pomodoro_adjust(
+    state: State<'_, AppState>,
+    delta_seconds: i64,
+) -> Result<PomodoroStatus, String> {
+    let feature = state
+        .get_feature("pomodoro")
+        .ok_or_else(|| "pomodoro feature not found".to_string())?;
+    let feature = feature
+        .as_any()
+        .downcast_ref::<crate::features::pomodoro::PomodoroFeature>()
+        .ok_or_else(|| "invalid pomodoro feature".to_string())?;
+    let manager = feature
+        .manager()
+        .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
+    manager
+        .adjust(delta_seconds)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 设置当前阶段的剩余秒数
+#[tauri::command]
+pub async fn pomodoro_set_remaining(
+    state: State<'_, AppState>,
+    seconds: u32,
+) -> Result<PomodoroStatus, String> {
+    let feature = state
+        .get_feature("pomodoro")
+        .ok_or_else(|| "pomodoro feature not found".to_string())?;
+    let feature = feature
+        .as_any()
+        .downcast_ref::<crate::features::pomodoro::PomodoroFeature>()
+        .ok_or_else(|| "invalid pomodoro feature".to_string())?;
+    let manager = feature
+        .manager()
+        .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
+    manager
+        .set_remaining(seconds)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -56,9 +174,6 @@ pub async fn pomodoro_resume(state: State<'_, AppState>) -> Result<PomodoroStatu
 
 #[tauri::command]
 pub async fn pomodoro_skip(state: State<'_, AppState>) -> Result<PomodoroStatus, String> {
-    let cfg = service::get_config(state.db())
-        .await
-        .map_err(|e| e.to_string())?;
     let feature = state
         .get_feature("pomodoro")
         .ok_or_else(|| "pomodoro feature not found".to_string())?;
@@ -69,6 +184,10 @@ pub async fn pomodoro_skip(state: State<'_, AppState>) -> Result<PomodoroStatus,
     let manager = feature
         .manager()
         .ok_or_else(|| "pomodoro manager not initialized".to_string())?;
+    // 按本次计时使用的预设切换到下一阶段
+    let cfg = presets::resolve_config(state.db(), manager.status().await.preset_id)
+        .await
+        .map_err(|e| e.to_string())?;
     Ok(manager.skip(cfg).await)
 }
 
@@ -119,6 +238,111 @@ pub async fn pomodoro_set_config(
         .map_err(|e| e.to_string())
 }
 
+// ==================== Preset Commands ====================
+
+/// 列出所有预设
+#[tauri::command]
+pub async fn pomodoro_list_presets(
+    state: State<'_, AppState>,
+) -> Result<Vec<preset_entity::Model>, String> {
+    presets::list_presets(state.db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 创建预设
+#[tauri::command]
+pub async fn pomodoro_create_preset(
+    state: State<'_, AppState>,
+    payload: PomodoroPresetInput,
+) -> Result<preset_entity::Model, String> {
+    presets::create_preset(state.db(), payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 修改预设
+#[tauri::command]
+pub async fn pomodoro_update_preset(
+    state: State<'_, AppState>,
+    id: i32,
+    payload: PomodoroPresetInput,
+) -> Result<preset_entity::Model, String> {
+    presets::update_preset(state.db(), id, payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 删除预设
+#[tauri::command]
+pub async fn pomodoro_delete_preset(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    presets::delete_preset(state.db(), id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取当前预设（为空表示使用默认配置）
+#[tauri::command]
+pub async fn pomodoro_get_active_preset(
+    state: State<'_, AppState>,
+) -> Result<Option<preset_entity::Model>, String> {
+    presets::get_active_preset(state.db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 设置当前预设（为空表示改回默认配置）
+#[tauri::command]
+pub async fn pomodoro_set_active_preset(
+    state: State<'_, AppState>,
+    id: Option<i32>,
+) -> Result<(), String> {
+    presets::set_active_preset(state.db(), id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Goal Commands ====================
+
+/// 获取每日专注目标（未设置时为空）
+#[tauri::command]
+pub async fn pomodoro_get_goal(state: State<'_, AppState>) -> Result<Option<PomodoroGoal>, String> {
+    goals::get_goal(state.db()).await.map_err(|e| e.to_string())
+}
+
+/// 设置每日专注目标（为空表示取消目标）
+#[tauri::command]
+pub async fn pomodoro_set_goal(
+    state: State<'_, AppState>,
+    goal: Option<PomodoroGoal>,
+) -> Result<(), String> {
+    goals::set_goal(state.db(), goal)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 今天的目标进度（没有目标时为空）
+#[tauri::command]
+pub async fn pomodoro_goal_progress(
+    state: State<'_, AppState>,
+) -> Result<Option<GoalProgress>, String> {
+    goals::today_progress(state.db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 目标历史（from/to 为本地日期，包含两端）
+#[tauri::command]
+pub async fn pomodoro_goal_history(
+    state: State<'_, AppState>,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+) -> Result<Vec<GoalProgress>, String> {
+    goals::goal_history(state.db(), from, to)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ==================== Record Commands (保留兼容性) ====================
 
 #[tauri::command]
@@ -136,6 +360,8 @@ pub async fn pomodoro_list_sessions(
 pub struct StatsRangePayload {
     pub from: String,
     pub to: String,
+    /// 只统计使用该预设的记录
+    pub preset_id: Option<i32>,
 }
 
 #[tauri::command]
@@ -149,7 +375,90 @@ pub async fn pomodoro_stats(
     let to = chrono::DateTime::parse_from_rfc3339(&payload.to)
         .map_err(|e| e.to_string())?
         .with_timezone(&chrono::Utc);
-    service::get_stats_range(state.db(), from, to)
+    service::get_stats_range(state.db(), from, to, payload.preset_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 专注分析的范围参数（日期边界按 `timezone` 计算，为空时使用系统时区）
+#[derive(serde::Deserialize)]
+pub struct AnalyticsPayload {
+    pub from: String,
+    pub to: String,
+    pub timezone: Option<String>,
+    /// 只统计使用该预设的记录
+    pub preset_id: Option<i32>,
+    /// day | week | month，仅用于时间序列，默认 day
+    pub bucket: Option<StatsBucket>,
+}
+
+impl AnalyticsPayload {
+    fn range(&self) -> Result<AnalyticsRange, String> {
+        let from = chrono::DateTime::parse_from_rfc3339(&self.from)
+            .map_err(|e| e.to_string())?
+            .with_timezone(&chrono::Utc);
+        let to = chrono::DateTime::parse_from_rfc3339(&self.to)
+            .map_err(|e| e.to_string())?
+            .with_timezone(&chrono::Utc);
+        AnalyticsRange::new(from, to, self.timezone.as_deref(), self.preset_id)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// 按天/周/月汇总专注
+#[tauri::command]
+pub async fn pomodoro_stats_series(
+    state: State<'_, AppState>,
+    payload: AnalyticsPayload,
+) -> Result<Vec<analytics::FocusBucket>, String> {
+    let range = payload.range()?;
+    analytics::focus_series(state.db(), &range, payload.bucket.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 当前与最长的连续专注天数
+#[tauri::command]
+pub async fn pomodoro_stats_streaks(
+    state: State<'_, AppState>,
+    timezone: Option<String>,
+) -> Result<analytics::FocusStreaks, String> {
+    analytics::focus_streaks(state.db(), timezone.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按星期与小时汇总专注
+#[tauri::command]
+pub async fn pomodoro_stats_heatmap(
+    state: State<'_, AppState>,
+    payload: AnalyticsPayload,
+) -> Result<analytics::FocusHeatmap, String> {
+    let range = payload.range()?;
+    analytics::focus_heatmap(state.db(), &range)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 完成/停止/跳过的比例，以及按待办和标签的拆分
+#[tauri::command]
+pub async fn pomodoro_stats_breakdown(
+    state: State<'_, AppState>,
+    payload: AnalyticsPayload,
+) -> Result<analytics::FocusBreakdown, String> {
+    let range = payload.range()?;
+    analytics::focus_breakdown(state.db(), &range)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按待办汇总专注时长与番茄数（为空时返回所有关联过专注的待办）
+#[tauri::command]
+pub async fn pomodoro_todo_stats(
+    state: State<'_, AppState>,
+    todo_ids: Option<Vec<i32>>,
+) -> Result<Vec<service::TodoFocusStats>, String> {
+    service::get_todo_focus_stats(state.db(), todo_ids)
         .await
         .map_err(|e| e.to_string())
 }
@@ -4,7 +4,8 @@ use serde_json::json;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 use crate::infrastructure::webserver;
 
-use crate::features::pomodoro::core::service;
+use crate::features::pomodoro::core::analytics::{self, AnalyticsRange, StatsBucket};
+use crate::features::pomodoro::core::{goals, presets, service};
 
 use crate::features::pomodoro::PomodoroFeature;
 
@@ -15,19 +16,225 @@ pub fn register_handlers(feature: &PomodoroFeature, registry: &mut webserver::Ha
     registry.register_event("pomodoro.status", "番茄钟状态变更事件");
     registry.register_event("pomodoro.tick", "番茄钟每秒心跳事件");
     registry.register_event("pomodoro.events", "番茄钟生命周期事件(start/finish/stop/skip)");
+    registry.register_event("pomodoro.goal", "每日专注目标进度达到 50%/100% 的事件");
 
-    // Start
-    registry.register_call("pomodoro.start", move |_method, _params, ctx| {
+    // Start（可选 todo_id：本次专注的待办；preset：预设 ID 或名称，默认为当前预设；flow：心流模式）
+    registry.register_call("pomodoro.start", move |_method, params, ctx| {
         Box::pin(async move {
-            let cfg = service::get_config(ctx.db())
+            let todo_id = params
+                .get("todo_id")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32);
+            let preset_id = match params.get("preset") {
+                Some(serde_json::Value::String(name)) => Some(
+                    presets::find_preset_by_name(ctx.db(), name)
+                        .await
+                        .context("Failed to find preset")?
+                        .id,
+                ),
+                Some(value) => value.as_i64().map(|v| v as i32),
+                None => None,
+            };
+            let (cfg, preset_id) = presets::start_config(ctx.db(), preset_id)
                 .await
                 .context("Failed to read config")?;
+            let flow = params
+                .get("flow")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
             let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
-            let status = mgr.start(cfg).await.context("Failed to start")?;
+            let status = if flow {
+                mgr.start_flow(cfg, preset_id, todo_id).await
+            } else {
+                mgr.start(cfg, preset_id, todo_id).await
+            }
+            .context("Failed to start")?;
             Ok(serde_json::to_value(status).unwrap_or(json!({})))
         })
     });
 
+    // 结束心流专注，开始按专注时长计算的休息
+    registry.register_call("pomodoro.finish", |_method, _params, ctx| {
+        Box::pin(async move {
+            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
+            let cfg = presets::resolve_config(ctx.db(), mgr.status().await.preset_id)
+                .await
+                .context("Failed to read config")?;
+            let status = mgr.finish(cfg).await.context("Failed to finish")?;
+            Ok(serde_json::to_value(status).unwrap_or(json!({})))
+        })
+    });
+
+    // 切换专注的待办（todo_id 为 null 表示取消关联）
+    registry.register_call("pomodoro.set_todo", |_method, params, ctx| {
+        Box::pin(async move {
+            let todo_id = params
+                .get("todo_id")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32);
+            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
+            let status = mgr.set_todo(todo_id).await.context("Failed to set todo")?;
+            Ok(serde_json::to_value(status).unwrap_or(json!({})))
+        })
+    });
+
+    // 确认开始等待中的阶段
+    registry.register_call("pomodoro.confirm", |_method, _params, ctx| {
+        Box::pin(async move {
+            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
+            let status = mgr.confirm().await.context("Failed to confirm")?;
+            Ok(serde_json::to_value(status).unwrap_or(json!({})))
+        })
+    });
+
+    // 延长/缩短当前阶段（delta_seconds 为负数表示缩短）
+    registry.register_call("pomodoro.adjust", |_method, params, ctx| {
+        Box::pin(async move {
+            let delta_seconds = params
+                .get("delta_seconds")
+                .and_then(|v| v.as_i64())
+                .context("Missing delta_seconds")?;
+            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
+            let status = mgr
+                .adjust(delta_seconds)
+                .await
+                .context("Failed to adjust")?;
+            Ok(serde_json::to_value(status).unwrap_or(json!({})))
+        })
+    });
+
+    // 设置当前阶段的剩余秒数
+    registry.register_call("pomodoro.set_remaining", |_method, params, ctx| {
+        Box::pin(async move {
+            let seconds = params
+                .get("seconds")
+                .and_then(|v| v.as_u64())
+                .context("Missing seconds")?;
+            let seconds = u32::try_from(seconds).context("Invalid seconds")?;
+            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
+            let status = mgr
+                .set_remaining(seconds)
+                .await
+                .context("Failed to set remaining")?;
+            Ok(serde_json::to_value(status).unwrap_or(json!({})))
+        })
+    });
+
+    // 按待办汇总专注（可选 todo_ids）
+    registry.register_call("pomodoro.todo_stats", |_method, params, ctx| {
+        Box::pin(async move {
+            let todo_ids = params
+                .get("todo_ids")
+                .and_then(|v| v.as_array())
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|v| v.as_i64())
+                        .map(|v| v as i32)
+                        .collect::<Vec<_>>()
+                });
+            let stats = service::get_todo_focus_stats(ctx.db(), todo_ids)
+                .await
+                .context("Failed to load todo stats")?;
+            Ok(json!(stats))
+        })
+    });
+
+    // 专注分析（from/to 为 RFC3339 时间，timezone 为空时使用系统时区，可选 preset_id）
+    registry.register_call("pomodoro.stats.series", |_method, params, ctx| {
+        Box::pin(async move {
+            let range = parse_range(&params)?;
+            let bucket = match params.get("bucket").and_then(|v| v.as_str()) {
+                Some(value) => StatsBucket::parse(value).context("Invalid bucket")?,
+                None => StatsBucket::Day,
+            };
+            let series = analytics::focus_series(ctx.db(), &range, bucket)
+                .await
+                .context("Failed to load focus series")?;
+            Ok(json!(series))
+        })
+    });
+
+    registry.register_call("pomodoro.stats.streaks", |_method, params, ctx| {
+        Box::pin(async move {
+            let timezone = params.get("timezone").and_then(|v| v.as_str());
+            let streaks = analytics::focus_streaks(ctx.db(), timezone)
+                .await
+                .context("Failed to load focus streaks")?;
+            Ok(json!(streaks))
+        })
+    });
+
+    registry.register_call("pomodoro.stats.heatmap", |_method, params, ctx| {
+        Box::pin(async move {
+            let range = parse_range(&params)?;
+            let heatmap = analytics::focus_heatmap(ctx.db(), &range)
+                .await
+                .context("Failed to load focus heatmap")?;
+            Ok(json!(heatmap))
+        })
+    });
+
+    registry.register_call("pomodoro.stats.breakdown", |_method, params, ctx| {
+        Box::pin(async move {
+            let range = parse_range(&params)?;
+            let breakdown = analytics::focus_breakdown(ctx.db(), &range)
+                .await
+                .context("Failed to load focus breakdown")?;
+            Ok(json!(breakdown))
+        })
+    });
+
+    // 每日目标
+    registry.register_call("pomodoro.goal.get", |_method, _params, ctx| {
+        Box::pin(async move {
+            let goal = goals::get_goal(ctx.db())
+                .await
+                .context("Failed to load goal")?;
+            Ok(json!(goal))
+        })
+    });
+
+    // goal 为 null 表示取消目标
+    registry.register_call("pomodoro.goal.set", |_method, params, ctx| {
+        Box::pin(async move {
+            let goal: Option<goals::PomodoroGoal> =
+                serde_json::from_value(params.get("goal").cloned().unwrap_or_default())
+                    .context("Invalid goal params")?;
+            goals::set_goal(ctx.db(), goal)
+                .await
+                .context("Failed to set goal")?;
+            Ok(json!({"success": true}))
+        })
+    });
+
+    registry.register_call("pomodoro.goal.progress", |_method, _params, ctx| {
+        Box::pin(async move {
+            let progress = goals::today_progress(ctx.db())
+                .await
+                .context("Failed to load goal progress")?;
+            Ok(json!(progress))
+        })
+    });
+
+    // from/to 为本地日期（YYYY-MM-DD），包含两端
+    registry.register_call("pomodoro.goal.history", |_method, params, ctx| {
+        Box::pin(async move {
+            let parse_date = |key: &str| -> anyhow::Result<chrono::NaiveDate> {
+                let value = params
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .with_context(|| format!("Missing {key}"))?;
+                value
+                    .parse::<chrono::NaiveDate>()
+                    .with_context(|| format!("Invalid {key}"))
+            };
+            let history = goals::goal_history(ctx.db(), parse_date("from")?, parse_date("to")?)
+                .await
+                .context("Failed to load goal history")?;
+            Ok(json!(history))
+        })
+    });
+
     // Pause
     registry.register_call("pomodoro.pause", |_method, _params, ctx| {
         Box::pin(async move {
@@ -49,10 +256,10 @@ pub fn register_handlers(feature: &PomodoroFeature, registry: &mut webserver::Ha
     // Skip
     registry.register_call("pomodoro.skip", |_method, _params, ctx| {
         Box::pin(async move {
-            let cfg = service::get_config(ctx.db())
+            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
+            let cfg = presets::resolve_config(ctx.db(), mgr.status().await.preset_id)
                 .await
                 .context("Failed to read config")?;
-            let mgr = get_manager(&ctx).context("Pomodoro manager not found")?;
             let status = mgr.skip(cfg).await;
             Ok(serde_json::to_value(status).unwrap_or(json!({})))
         })
@@ -67,6 +274,75 @@ pub fn register_handlers(feature: &PomodoroFeature, registry: &mut webserver::Ha
         })
     });
 
+    // 预设
+    registry.register_call("pomodoro.presets.list", |_method, _params, ctx| {
+        Box::pin(async move {
+            let presets = presets::list_presets(ctx.db())
+                .await
+                .context("Failed to list presets")?;
+            Ok(json!(presets))
+        })
+    });
+
+    registry.register_call("pomodoro.presets.create", |_method, params, ctx| {
+        Box::pin(async move {
+            let input: presets::PomodoroPresetInput =
+                serde_json::from_value(params.clone()).context("Invalid preset params")?;
+            let preset = presets::create_preset(ctx.db(), input)
+                .await
+                .context("Failed to create preset")?;
+            Ok(json!(preset))
+        })
+    });
+
+    registry.register_call("pomodoro.presets.update", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let input: presets::PomodoroPresetInput =
+                serde_json::from_value(params.clone()).context("Invalid preset params")?;
+            let preset = presets::update_preset(ctx.db(), id, input)
+                .await
+                .context("Failed to update preset")?;
+            Ok(json!(preset))
+        })
+    });
+
+    registry.register_call("pomodoro.presets.delete", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            presets::delete_preset(ctx.db(), id)
+                .await
+                .context("Failed to delete preset")?;
+            Ok(json!({"success": true}))
+        })
+    });
+
+    registry.register_call("pomodoro.presets.get_active", |_method, _params, ctx| {
+        Box::pin(async move {
+            let preset = presets::get_active_preset(ctx.db())
+                .await
+                .context("Failed to get active preset")?;
+            Ok(json!(preset))
+        })
+    });
+
+    // id 为 null 表示改回默认配置
+    registry.register_call("pomodoro.presets.set_active", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params.get("id").and_then(|v| v.as_i64()).map(|v| v as i32);
+            presets::set_active_preset(ctx.db(), id)
+                .await
+                .context("Failed to set active preset")?;
+            Ok(json!({"success": true}))
+        })
+    });
+
     // Status
     registry.register_call("pomodoro.status", |_method, _params, ctx| {
         Box::pin(async move {
@@ -77,6 +353,30 @@ pub fn register_handlers(feature: &PomodoroFeature, registry: &mut webserver::Ha
     });
 }
 
+/// 解析专注分析的范围参数
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn parse_range(params: &serde_json::Value) -> anyhow::Result<AnalyticsRange> {
+    let parse_time = |key: &str| -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+        let value = params
+            .get(key)
+            .and_then(|v| v.as_str())
+            .with_context(|| format!("Missing {key}"))?;
+        Ok(chrono::DateTime::parse_from_rfc3339(value)
+            .with_context(|| format!("Invalid {key}"))?
+            .with_timezone(&chrono::Utc))
+    };
+    let preset_id = params
+        .get("preset_id")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+    AnalyticsRange::new(
+        parse_time("from")?,
+        parse_time("to")?,
+        params.get("timezone").and_then(|v| v.as_str()),
+        preset_id,
+    )
+}
+
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 fn get_manager(
     ctx: &crate::infrastructure::webserver::core::ws::ApiContext,
@@ -0,0 +1,418 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Timelike, Utc};
+use chrono_tz::Tz;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::features::pomodoro::data::entities::pomodoro_records as record_entity;
+use crate::features::todo::core::recurrence::resolve_timezone;
+use crate::features::todo::core::service::get_local_timezone;
+use crate::features::todo::data::entities::{tag as tag_entity, todo_tag as todo_tag_entity};
+use crate::features::todo::data::entity as todo_entity;
+
+/// 最多返回的时间段数（按天统计约十年）
+const MAX_BUCKETS: usize = 3700;
+
+/// 时间序列的统计粒度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsBucket {
+    #[default]
+    Day,
+    /// 周一开始
+    Week,
+    Month,
+}
+
+impl StatsBucket {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(StatsBucket::Day),
+            "week" => Some(StatsBucket::Week),
+            "month" => Some(StatsBucket::Month),
+            _ => None,
+        }
+    }
+
+    /// `date` 所在时间段的第一天
+    fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            StatsBucket::Day => date,
+            StatsBucket::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            StatsBucket::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            StatsBucket::Day => start.succ_opt(),
+            StatsBucket::Week => start.checked_add_signed(Duration::days(7)),
+            StatsBucket::Month => start.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+/// 统计范围：按开始时间在 [from, to) 内的专注记录，日期边界按 `timezone` 计算
+#[derive(Debug, Clone)]
+pub struct AnalyticsRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub timezone: Tz,
+    /// 只统计使用该预设的记录
+    pub preset_id: Option<i32>,
+}
+
+impl AnalyticsRange {
+    /// `timezone` 为 IANA 时区名，为空时使用系统时区
+    pub fn new(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        timezone: Option<&str>,
+        preset_id: Option<i32>,
+    ) -> Result<Self> {
+        if to <= from {
+            bail!("stats range end must be after its start");
+        }
+        Ok(Self {
+            from,
+            to,
+            timezone: local_timezone(timezone)?,
+            preset_id,
+        })
+    }
+
+    fn local_date(&self, at: DateTime<Utc>) -> NaiveDate {
+        at.with_timezone(&self.timezone).date_naive()
+    }
+}
+
+/// 一个时间段的专注统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusBucket {
+    /// 时间段第一天（本地日期）
+    pub start: NaiveDate,
+    /// 专注总秒数（包括中途停止或跳过的专注）
+    pub focus_seconds: i64,
+    /// 完成的专注数
+    pub pomodoro_count: i64,
+}
+
+/// 连续专注天数（当天至少完成一个专注）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusStreaks {
+    /// 截至今天的连续天数；今天还没有完成专注时从昨天算起
+    pub current_days: i64,
+    pub longest_days: i64,
+    pub last_focus_date: Option<NaiveDate>,
+}
+
+/// 按星期与小时汇总的专注秒数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusHeatmap {
+    /// 7 行（周一到周日）× 24 列（0-23 时）
+    pub seconds: Vec<[i64; 24]>,
+}
+
+/// 专注记录的结束方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusOutcomes {
+    pub total: i64,
+    pub completed: i64,
+    pub stopped: i64,
+    pub skipped: i64,
+    pub completion_ratio: f64,
+    pub stop_ratio: f64,
+    pub skip_ratio: f64,
+}
+
+/// 单个待办在范围内的专注
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoFocusShare {
+    pub todo_id: i32,
+    /// 待办已被彻底删除时为空
+    pub title: Option<String>,
+    pub focus_seconds: i64,
+    pub pomodoro_count: i64,
+}
+
+/// 单个标签在范围内的专注（待办有多个标签时计入每个标签）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagFocusShare {
+    pub tag_id: i32,
+    pub name: String,
+    pub focus_seconds: i64,
+    pub pomodoro_count: i64,
+}
+
+/// 结束方式与按待办、标签的拆分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusBreakdown {
+    pub outcomes: FocusOutcomes,
+    /// 按专注时长降序
+    pub todos: Vec<TodoFocusShare>,
+    /// 按专注时长降序
+    pub tags: Vec<TagFocusShare>,
+}
+
+/// 按天/周/月汇总专注，范围内没有专注的时间段也会返回
+///
+/// 记录按开始时间所在的本地日期归入时间段
+pub async fn focus_series(
+    db: &DatabaseConnection,
+    range: &AnalyticsRange,
+    bucket: StatsBucket,
+) -> Result<Vec<FocusBucket>> {
+    let mut buckets = BTreeMap::new();
+    let last = bucket.start_of(range.local_date(range.to - Duration::seconds(1)));
+    let mut start = bucket.start_of(range.local_date(range.from));
+    while start <= last {
+        if buckets.len() >= MAX_BUCKETS {
+            bail!("stats range is too long for the selected bucket");
+        }
+        buckets.insert(start, (0i64, 0i64));
+        let Some(next) = bucket.next(start) else {
+            break;
+        };
+        start = next;
+    }
+
+    for record in load_focus_records(db, range).await? {
+        let key = bucket.start_of(range.local_date(record.start_at));
+        if let Some((seconds, count)) = buckets.get_mut(&key) {
+            *seconds += record.elapsed_seconds as i64;
+            if record.status == "completed" {
+                *count += 1;
+            }
+        }
+    }
+
+    Ok(buckets
+        .into_iter()
+        .map(|(start, (focus_seconds, pomodoro_count))| FocusBucket {
+            start,
+            focus_seconds,
+            pomodoro_count,
+        })
+        .collect())
+}
+
+/// 当前与最长的连续专注天数（统计全部记录）
+pub async fn focus_streaks(
+    db: &DatabaseConnection,
+    timezone: Option<&str>,
+) -> Result<FocusStreaks> {
+    let tz = local_timezone(timezone)?;
+    let starts: Vec<DateTime<Utc>> = record_entity::Entity::find()
+        .filter(record_entity::Column::Kind.eq("focus"))
+        .filter(record_entity::Column::Status.eq("completed"))
+        .select_only()
+        .column(record_entity::Column::StartAt)
+        .into_tuple()
+        .all(db)
+        .await
+        .context("failed to load focus records")?;
+    let days: BTreeSet<NaiveDate> = starts
+        .into_iter()
+        .map(|at| at.with_timezone(&tz).date_naive())
+        .collect();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in &days {
+        run = match previous {
+            Some(prev) if prev.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    while let Some(d) = day.filter(|d| days.contains(d)) {
+        current += 1;
+        day = d.pred_opt();
+    }
+
+    Ok(FocusStreaks {
+        current_days: current,
+        longest_days: longest,
+        last_focus_date: days.last().copied(),
+    })
+}
+
+/// 按本地时间的星期与小时汇总专注秒数（记录按开始时间归入）
+pub async fn focus_heatmap(
+    db: &DatabaseConnection,
+    range: &AnalyticsRange,
+) -> Result<FocusHeatmap> {
+    let mut seconds = vec![[0i64; 24]; 7];
+    for record in load_focus_records(db, range).await? {
+        let local = record.start_at.with_timezone(&range.timezone);
+        let weekday = local.weekday().num_days_from_monday() as usize;
+        seconds[weekday][local.hour() as usize] += record.elapsed_seconds as i64;
+    }
+    Ok(FocusHeatmap { seconds })
+}
+
+/// 完成/停止/跳过的比例，以及按待办和标签拆分的专注
+pub async fn focus_breakdown(
+    db: &DatabaseConnection,
+    range: &AnalyticsRange,
+) -> Result<FocusBreakdown> {
+    let records = load_focus_records(db, range).await?;
+
+    let (mut completed, mut stopped, mut skipped) = (0i64, 0i64, 0i64);
+    let mut by_todo: BTreeMap<i32, (i64, i64)> = BTreeMap::new();
+    for record in &records {
+        match record.status.as_str() {
+            "completed" => completed += 1,
+            "stopped" => stopped += 1,
+            "skipped" => skipped += 1,
+            _ => {}
+        }
+        if let Some(todo_id) = record.related_todo_id {
+            let entry = by_todo.entry(todo_id).or_default();
+            entry.0 += record.elapsed_seconds as i64;
+            if record.status == "completed" {
+                entry.1 += 1;
+            }
+        }
+    }
+    let total = records.len() as i64;
+    let ratio = |count: i64| {
+        if total == 0 {
+            0.0
+        } else {
+            count as f64 / total as f64
+        }
+    };
+    let outcomes = FocusOutcomes {
+        total,
+        completed,
+        stopped,
+        skipped,
+        completion_ratio: ratio(completed),
+        stop_ratio: ratio(stopped),
+        skip_ratio: ratio(skipped),
+    };
+
+    let todo_ids: Vec<i32> = by_todo.keys().copied().collect();
+    let titles: HashMap<i32, String> = if todo_ids.is_empty() {
+        HashMap::new()
+    } else {
+        todo_entity::Entity::find()
+            .filter(todo_entity::Column::Id.is_in(todo_ids.clone()))
+            .all(db)
+            .await
+            .context("failed to load todos")?
+            .into_iter()
+            .map(|todo| (todo.id, todo.title))
+            .collect()
+    };
+    let mut todos: Vec<TodoFocusShare> = by_todo
+        .iter()
+        .map(
+            |(&todo_id, &(focus_seconds, pomodoro_count))| TodoFocusShare {
+                todo_id,
+                title: titles.get(&todo_id).cloned(),
+                focus_seconds,
+                pomodoro_count,
+            },
+        )
+        .collect();
+    todos.sort_by_key(|todo| Reverse(todo.focus_seconds));
+
+    let mut by_tag: BTreeMap<i32, (i64, i64)> = BTreeMap::new();
+    if !todo_ids.is_empty() {
+        let links = todo_tag_entity::Entity::find()
+            .filter(todo_tag_entity::Column::TodoId.is_in(todo_ids))
+            .all(db)
+            .await
+            .context("failed to load todo tags")?;
+        for link in links {
+            if let Some(&(seconds, count)) = by_todo.get(&link.todo_id) {
+                let entry = by_tag.entry(link.tag_id).or_default();
+                entry.0 += seconds;
+                entry.1 += count;
+            }
+        }
+    }
+    let names: HashMap<i32, String> = if by_tag.is_empty() {
+        HashMap::new()
+    } else {
+        tag_entity::Entity::find()
+            .filter(tag_entity::Column::Id.is_in(by_tag.keys().copied().collect::<Vec<_>>()))
+            .all(db)
+            .await
+            .context("failed to load tags")?
+            .into_iter()
+            .map(|tag| (tag.id, tag.name))
+            .collect()
+    };
+    let mut tags: Vec<TagFocusShare> = by_tag
+        .into_iter()
+        .filter_map(|(tag_id, (focus_seconds, pomodoro_count))| {
+            Some(TagFocusShare {
+                tag_id,
+                name: names.get(&tag_id)?.clone(),
+                focus_seconds,
+                pomodoro_count,
+            })
+        })
+        .collect();
+    tags.sort_by_key(|tag| Reverse(tag.focus_seconds));
+
+    Ok(FocusBreakdown {
+        outcomes,
+        todos,
+        tags,
+    })
+}
+
+/// 解析统计使用的时区，为空时使用系统时区
+pub(crate) fn local_timezone(timezone: Option<&str>) -> Result<Tz> {
+    match timezone.map(str::trim).filter(|tz| !tz.is_empty()) {
+        Some(tz) => tz
+            .parse::<Tz>()
+            .map_err(|_| anyhow!("unknown timezone \"{tz}\"")),
+        None => Ok(resolve_timezone(Some(&get_local_timezone()))),
+    }
+}
+
+/// 范围内的专注记录（包括停止和跳过的）
+async fn load_focus_records(
+    db: &DatabaseConnection,
+    range: &AnalyticsRange,
+) -> Result<Vec<record_entity::Model>> {
+    let mut query = record_entity::Entity::find()
+        .filter(record_entity::Column::Kind.eq("focus"))
+        .filter(record_entity::Column::StartAt.gte(range.from))
+        .filter(record_entity::Column::StartAt.lt(range.to));
+    if let Some(preset_id) = range.preset_id {
+        query = query.filter(record_entity::Column::PresetId.eq(preset_id));
+    }
+    query
+        .order_by_asc(record_entity::Column::StartAt)
+        .all(db)
+        .await
+        .context("failed to load focus records")
+}
@@ -0,0 +1,339 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+use super::analytics::{self, AnalyticsRange, StatsBucket};
+use crate::features::pomodoro::data::entities::{
+    pomodoro_goal_days as goal_day_entity, pomodoro_records as record_entity,
+};
+use crate::features::settings::core::service::SettingService;
+
+const KEY_DAILY_GOAL: &str = "pomodoro.daily_goal";
+
+/// 达到后发送通知的进度百分比（从高到低）
+const MILESTONES: [u32; 2] = [100, 50];
+
+/// 每日目标的衡量方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalMetric {
+    /// 专注分钟数（包括中途停止或跳过的专注）
+    FocusMinutes,
+    /// 完成的专注数
+    Pomodoros,
+}
+
+impl GoalMetric {
+    fn as_str(self) -> &'static str {
+        match self {
+            GoalMetric::FocusMinutes => "focus_minutes",
+            GoalMetric::Pomodoros => "pomodoros",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "focus_minutes" => Some(GoalMetric::FocusMinutes),
+            "pomodoros" => Some(GoalMetric::Pomodoros),
+            _ => None,
+        }
+    }
+
+    fn progress(self, focus_seconds: i64, pomodoro_count: i64) -> u32 {
+        let value = match self {
+            GoalMetric::FocusMinutes => focus_seconds / 60,
+            GoalMetric::Pomodoros => pomodoro_count,
+        };
+        value.clamp(0, u32::MAX as i64) as u32
+    }
+}
+
+/// 每日专注目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PomodoroGoal {
+    pub metric: GoalMetric,
+    /// 每天的目标（分钟数或番茄数）
+    pub daily_target: u32,
+    /// 周一到周日单独的目标：为空时全部使用 `daily_target`，
+    /// 否则必须有 7 项，为 null 的项使用 `daily_target`，0 表示当天没有目标
+    #[serde(default)]
+    pub weekday_targets: Vec<Option<u32>>,
+}
+
+impl PomodoroGoal {
+    /// `date` 当天的目标，0 表示没有目标
+    pub fn target_for(&self, date: NaiveDate) -> u32 {
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        self.weekday_targets
+            .get(weekday)
+            .copied()
+            .flatten()
+            .unwrap_or(self.daily_target)
+    }
+}
+
+/// 某一天的目标进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    /// 本地日期
+    pub date: NaiveDate,
+    pub metric: GoalMetric,
+    pub target: u32,
+    /// 与目标同单位的进度（分钟数或番茄数）
+    pub progress: u32,
+    /// 进度百分比，超过目标时大于 100
+    pub percent: u32,
+    pub reached: bool,
+    pub reached_at: Option<DateTime<Utc>>,
+}
+
+/// 进度达到里程碑时发送的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalMilestone {
+    /// 50 | 100
+    pub milestone: u32,
+    #[serde(flatten)]
+    pub progress: GoalProgress,
+}
+
+/// 获取每日目标（未设置时为空）
+pub async fn get_goal(db: &DatabaseConnection) -> Result<Option<PomodoroGoal>> {
+    let Some(raw) = SettingService::get(db, KEY_DAILY_GOAL).await? else {
+        return Ok(None);
+    };
+    match serde_json::from_str(&raw) {
+        Ok(goal) => Ok(Some(goal)),
+        Err(e) => {
+            eprintln!("Failed to parse pomodoro daily goal: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// 设置每日目标，为空表示取消目标（已有的历史保留）
+pub async fn set_goal(db: &DatabaseConnection, goal: Option<PomodoroGoal>) -> Result<()> {
+    let Some(goal) = goal else {
+        SettingService::delete(db, KEY_DAILY_GOAL).await?;
+        return Ok(());
+    };
+
+    if !goal.weekday_targets.is_empty() && goal.weekday_targets.len() != 7 {
+        bail!("weekday targets must have 7 entries (Monday to Sunday)");
+    }
+    let max = match goal.metric {
+        GoalMetric::FocusMinutes => 24 * 60,
+        GoalMetric::Pomodoros => 100,
+    };
+    let targets =
+        std::iter::once(goal.daily_target).chain(goal.weekday_targets.iter().flatten().copied());
+    for target in targets {
+        if target > max {
+            bail!("daily goal cannot exceed {max} {}", goal.metric.as_str());
+        }
+    }
+
+    let payload = serde_json::to_string(&goal).context("failed to serialize daily goal")?;
+    SettingService::set(db, KEY_DAILY_GOAL, &payload).await?;
+    Ok(())
+}
+
+/// 今天的目标进度（按当前目标实时计算，没有目标时为空）
+pub async fn today_progress(db: &DatabaseConnection) -> Result<Option<GoalProgress>> {
+    let Some(goal) = get_goal(db).await? else {
+        return Ok(None);
+    };
+    let tz = analytics::local_timezone(None)?;
+    let date = Utc::now().with_timezone(&tz).date_naive();
+    let target = goal.target_for(date);
+    if target == 0 {
+        return Ok(None);
+    }
+
+    let (focus_seconds, pomodoro_count) = day_totals(db, tz, date).await?;
+    let progress = goal.metric.progress(focus_seconds, pomodoro_count);
+    // 目标改变后还没有写入新的专注记录时，达成时间以当前目标为准
+    let reached_at = find_day(db, date)
+        .await?
+        .filter(|day| day.metric == goal.metric.as_str() && day.target == target as i32)
+        .and_then(|day| day.reached_at)
+        .filter(|_| progress >= target);
+    Ok(Some(progress_of(
+        date,
+        goal.metric,
+        target,
+        progress,
+        reached_at,
+    )))
+}
+
+/// 目标历史（按日期升序，包含 `from` 和 `to`）
+///
+/// 只包含有目标且写入过专注记录的日期
+pub async fn goal_history(
+    db: &DatabaseConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<GoalProgress>> {
+    let days = goal_day_entity::Entity::find()
+        .filter(goal_day_entity::Column::Date.gte(from))
+        .filter(goal_day_entity::Column::Date.lte(to))
+        .order_by_asc(goal_day_entity::Column::Date)
+        .all(db)
+        .await
+        .context("failed to load goal history")?;
+
+    Ok(days
+        .into_iter()
+        .filter_map(|day| {
+            let metric = GoalMetric::parse(&day.metric)?;
+            Some(progress_of(
+                day.date,
+                metric,
+                day.target.max(0) as u32,
+                metric.progress(day.focus_seconds as i64, day.pomodoro_count as i64),
+                day.reached_at,
+            ))
+        })
+        .collect())
+}
+
+/// 写入专注记录后更新记录所在日期的目标进度
+///
+/// 进度首次达到 50% 或 100% 时返回达到的最高里程碑
+pub async fn record_progress(
+    db: &DatabaseConnection,
+    record: &record_entity::Model,
+) -> Result<Option<GoalMilestone>> {
+    if record.kind != "focus" {
+        return Ok(None);
+    }
+    let Some(goal) = get_goal(db).await? else {
+        return Ok(None);
+    };
+    let tz = analytics::local_timezone(None)?;
+    let date = record.start_at.with_timezone(&tz).date_naive();
+    let target = goal.target_for(date);
+    if target == 0 {
+        return Ok(None);
+    }
+
+    let (focus_seconds, pomodoro_count) = day_totals(db, tz, date).await?;
+    let progress = goal.metric.progress(focus_seconds, pomodoro_count);
+    let now = Utc::now();
+    let existing = find_day(db, date).await?;
+
+    // 当天的目标改变后重新计算里程碑与达成时间
+    let same_goal = existing
+        .as_ref()
+        .filter(|day| day.metric == goal.metric.as_str() && day.target == target as i32);
+    let previous_milestone = same_goal.map_or(0, |day| day.milestone.max(0) as u32);
+    let reached_at = if progress >= target {
+        Some(same_goal.and_then(|day| day.reached_at).unwrap_or(now))
+    } else {
+        None
+    };
+    let result = progress_of(date, goal.metric, target, progress, reached_at);
+    let milestone = MILESTONES
+        .into_iter()
+        .find(|&m| result.percent >= m && previous_milestone < m);
+    let stored_milestone = milestone.unwrap_or(previous_milestone);
+
+    let mut active: goal_day_entity::ActiveModel = match existing {
+        Some(day) => day.into(),
+        None => goal_day_entity::ActiveModel {
+            date: Set(date),
+            created_at: Set(now),
+            ..Default::default()
+        },
+    };
+    active.metric = Set(goal.metric.as_str().to_string());
+    active.target = Set(target as i32);
+    active.focus_seconds = Set(focus_seconds.clamp(0, i32::MAX as i64) as i32);
+    active.pomodoro_count = Set(pomodoro_count.clamp(0, i32::MAX as i64) as i32);
+    active.milestone = Set(stored_milestone as i32);
+    active.reached_at = Set(reached_at);
+    active.updated_at = Set(now);
+    active
+        .save(db)
+        .await
+        .with_context(|| format!("failed to save goal progress of {date}"))?;
+
+    Ok(milestone.map(|milestone| GoalMilestone {
+        milestone,
+        progress: result,
+    }))
+}
+
+fn progress_of(
+    date: NaiveDate,
+    metric: GoalMetric,
+    target: u32,
+    progress: u32,
+    reached_at: Option<DateTime<Utc>>,
+) -> GoalProgress {
+    let percent = if target == 0 {
+        0
+    } else {
+        (progress as u64 * 100 / target as u64).min(u32::MAX as u64) as u32
+    };
+    GoalProgress {
+        date,
+        metric,
+        target,
+        progress,
+        percent,
+        reached: target > 0 && progress >= target,
+        reached_at,
+    }
+}
+
+async fn find_day(
+    db: &DatabaseConnection,
+    date: NaiveDate,
+) -> Result<Option<goal_day_entity::Model>> {
+    goal_day_entity::Entity::find()
+        .filter(goal_day_entity::Column::Date.eq(date))
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load goal progress of {date}"))
+}
+
+/// 本地日期 `date` 当天的专注秒数与完成的专注数
+async fn day_totals(db: &DatabaseConnection, tz: Tz, date: NaiveDate) -> Result<(i64, i64)> {
+    let range = AnalyticsRange {
+        from: day_start(tz, date),
+        to: day_start(tz, date + Duration::days(1)),
+        timezone: tz,
+        preset_id: None,
+    };
+    let totals = analytics::focus_series(db, &range, StatsBucket::Day)
+        .await?
+        .into_iter()
+        .find(|bucket| bucket.start == date)
+        .map_or((0, 0), |bucket| {
+            (bucket.focus_seconds, bucket.pomodoro_count)
+        });
+    Ok(totals)
+}
+
+/// 本地日期开始的时间（夏令时跳过午夜时取当天最早的有效整点）
+fn day_start(tz: Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    (0..3)
+        .find_map(|hours| {
+            tz.from_local_datetime(&(midnight + Duration::hours(hours)))
+                .earliest()
+        })
+        .map_or_else(
+            || Utc.from_utc_datetime(&midnight),
+            |start| start.with_timezone(&Utc),
+        )
+}
@@ -1,4 +1,7 @@
+pub mod analytics;
+pub mod goals;
 pub mod models;
+pub mod presets;
 pub mod scheduler;
 pub mod service;
 
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -7,6 +8,8 @@ pub enum PomodoroMode {
     ShortBreak,
     LongBreak,
     Idle,
+    /// 心流专注：正向计时，手动结束后按专注时长计算休息时间
+    Flow,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +19,99 @@ pub struct PomodoroConfig {
     pub short_break_minutes: u32,
     pub long_break_minutes: u32,
     pub long_break_interval: u32,
+    /// 重启后继续未完成的阶段；关闭时把中断的阶段记录为 stopped
+    #[serde(default = "default_resume_on_restart")]
+    pub resume_on_restart: bool,
+    /// 检测到系统休眠后的处理方式
+    #[serde(default)]
+    pub suspend_policy: PomodoroSuspendPolicy,
+    /// 关联待办完成的专注数达到预估番茄数时自动完成该待办
+    #[serde(default)]
+    pub auto_complete_todo: bool,
+    /// 心流模式的休息时长为专注时长除以该值
+    #[serde(default = "default_flow_break_ratio")]
+    pub flow_break_ratio: u32,
+    /// 心流模式的最短休息分钟数
+    #[serde(default = "default_flow_min_break_minutes")]
+    pub flow_min_break_minutes: u32,
+    /// 心流模式的最长休息分钟数
+    #[serde(default = "default_flow_max_break_minutes")]
+    pub flow_max_break_minutes: u32,
+    /// 专注结束后自动开始休息；关闭时等待确认
+    #[serde(default = "default_auto_start")]
+    pub auto_start_breaks: bool,
+    /// 休息结束后自动开始专注；关闭时等待确认
+    #[serde(default = "default_auto_start")]
+    pub auto_start_focus: bool,
+    /// 暂停超过该分钟数后自动停止（0 表示不限制）
+    #[serde(default)]
+    pub pause_timeout_minutes: u32,
+}
+
+impl PomodoroConfig {
+    /// 心流专注 `focus_seconds` 秒后的休息秒数
+    pub fn flow_break_seconds(&self, focus_seconds: i64) -> u32 {
+        let min = self.flow_min_break_minutes as i64 * 60;
+        let max = (self.flow_max_break_minutes as i64 * 60).max(min);
+        (focus_seconds.max(0) / self.flow_break_ratio.max(1) as i64).clamp(min, max) as u32
+    }
+
+    /// 进入 `mode` 阶段时是否自动开始计时
+    pub fn auto_starts(&self, mode: PomodoroMode) -> bool {
+        match mode {
+            PomodoroMode::ShortBreak | PomodoroMode::LongBreak => self.auto_start_breaks,
+            PomodoroMode::Focus | PomodoroMode::Flow => self.auto_start_focus,
+            PomodoroMode::Idle => true,
+        }
+    }
+}
+
+/// 系统休眠/挂起期间的计时方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PomodoroSuspendPolicy {
+    /// 从休眠开始时暂停，唤醒后需要手动继续
+    #[default]
+    Pause,
+    /// 休眠期间照常计时，已经结束的阶段按原定结束时间记为完成
+    Finish,
+}
+
+impl PomodoroSuspendPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PomodoroSuspendPolicy::Pause => "pause",
+            PomodoroSuspendPolicy::Finish => "finish",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pause" => Some(PomodoroSuspendPolicy::Pause),
+            "finish" => Some(PomodoroSuspendPolicy::Finish),
+            _ => None,
+        }
+    }
+}
+
+fn default_resume_on_restart() -> bool {
+    true
+}
+
+fn default_flow_break_ratio() -> u32 {
+    5
+}
+
+fn default_flow_min_break_minutes() -> u32 {
+    1
+}
+
+fn default_flow_max_break_minutes() -> u32 {
+    30
+}
+
+fn default_auto_start() -> bool {
+    true
 }
 
 impl Default for PomodoroConfig {
@@ -25,6 +121,15 @@ impl Default for PomodoroConfig {
             short_break_minutes: 5,
             long_break_minutes: 15,
             long_break_interval: 4,
+            resume_on_restart: default_resume_on_restart(),
+            suspend_policy: PomodoroSuspendPolicy::default(),
+            auto_complete_todo: false,
+            flow_break_ratio: default_flow_break_ratio(),
+            flow_min_break_minutes: default_flow_min_break_minutes(),
+            flow_max_break_minutes: default_flow_max_break_minutes(),
+            auto_start_breaks: default_auto_start(),
+            auto_start_focus: default_auto_start(),
+            pause_timeout_minutes: 0,
         }
     }
 }
@@ -35,8 +140,63 @@ pub struct PomodoroStatus {
     pub running: bool,
     pub paused: bool,
     pub mode: PomodoroMode,
+    /// 倒计时阶段的剩余秒数（心流专注为 0）
     pub remaining_seconds: u32,
+    /// 本阶段已计时的秒数（不含暂停）
+    pub elapsed_seconds: u32,
+    pub round: u32,
+    /// 是否为心流模式（心流专注与计算出的休息交替）
+    pub flow: bool,
+    /// 当前专注的待办
+    pub todo_id: Option<i32>,
+    /// 本次计时使用的预设（使用默认配置时为空）
+    pub preset_id: Option<i32>,
+    /// 本阶段手动延长/缩短的累计秒数
+    pub adjusted_seconds: i64,
+    /// 上一阶段已结束，等待确认后开始 `mode` 阶段
+    pub awaiting_confirmation: bool,
+}
+
+/// 持久化的计时器状态，用于在崩溃或重启后恢复进行中的阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PomodoroTimerSnapshot {
+    pub mode: PomodoroMode,
     pub round: u32,
+    pub paused: bool,
+    pub remaining_seconds: u32,
+    pub phase_started_at: DateTime<Utc>,
+    /// 本阶段已结束的暂停累计秒数
+    #[serde(default)]
+    pub paused_seconds: i64,
+    /// 暂停中时为暂停开始的时间
+    #[serde(default)]
+    pub paused_at: Option<DateTime<Utc>>,
+    /// 当前专注的待办
+    #[serde(default)]
+    pub todo_id: Option<i32>,
+    /// 阶段中途切换待办后，当前待办开始计入的时间
+    #[serde(default)]
+    pub segment_started_at: Option<DateTime<Utc>>,
+    /// 本阶段已经记录给之前待办的计时秒数
+    #[serde(default)]
+    pub split_seconds: i64,
+    /// 本次计时使用的预设
+    #[serde(default)]
+    pub preset_id: Option<i32>,
+    /// 是否为心流模式
+    #[serde(default)]
+    pub flow: bool,
+    /// 阶段开始时的计划秒数
+    #[serde(default)]
+    pub planned_seconds: Option<u32>,
+    /// 本阶段手动调整的累计秒数
+    #[serde(default)]
+    pub adjusted_seconds: i64,
+    /// 等待确认开始本阶段（`phase_started_at` 为进入等待的时间）
+    #[serde(default)]
+    pub awaiting_confirmation: bool,
+    /// 保存快照的时间（计时期间定期刷新，视为应用最后一次正常运行的时间）
+    pub saved_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -0,0 +1,223 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, EntityTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use super::models::PomodoroConfig;
+use super::service;
+use crate::features::pomodoro::data::entities::pomodoro_presets as preset_entity;
+use crate::features::settings::core::service::SettingService;
+
+const KEY_ACTIVE_PRESET: &str = "pomodoro.active_preset_id";
+
+/// 创建/修改预设的参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PomodoroPresetInput {
+    pub name: String,
+    pub focus_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    pub long_break_interval: u32,
+}
+
+/// 列出所有预设（按名称排序）
+pub async fn list_presets(db: &DatabaseConnection) -> Result<Vec<preset_entity::Model>> {
+    preset_entity::Entity::find()
+        .order_by_asc(preset_entity::Column::Name)
+        .all(db)
+        .await
+        .context("failed to load pomodoro presets")
+}
+
+/// 获取指定预设
+pub async fn get_preset(db: &DatabaseConnection, id: i32) -> Result<preset_entity::Model> {
+    preset_entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load pomodoro preset {id}"))?
+        .ok_or_else(|| anyhow!("pomodoro preset {id} not found"))
+}
+
+/// 按名称查找预设（不区分大小写）
+pub async fn find_preset_by_name(
+    db: &DatabaseConnection,
+    name: &str,
+) -> Result<preset_entity::Model> {
+    let name = name.trim();
+    list_presets(db)
+        .await?
+        .into_iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow!("pomodoro preset \"{name}\" not found"))
+}
+
+/// 创建预设
+pub async fn create_preset(
+    db: &DatabaseConnection,
+    input: PomodoroPresetInput,
+) -> Result<preset_entity::Model> {
+    let input = validate(db, None, input).await?;
+    let now = Utc::now();
+
+    preset_entity::ActiveModel {
+        name: Set(input.name),
+        focus_minutes: Set(input.focus_minutes as i32),
+        short_break_minutes: Set(input.short_break_minutes as i32),
+        long_break_minutes: Set(input.long_break_minutes as i32),
+        long_break_interval: Set(input.long_break_interval as i32),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .context("failed to create pomodoro preset")
+}
+
+/// 修改预设（进行中的计时在下一阶段开始使用新的时长）
+pub async fn update_preset(
+    db: &DatabaseConnection,
+    id: i32,
+    input: PomodoroPresetInput,
+) -> Result<preset_entity::Model> {
+    let input = validate(db, Some(id), input).await?;
+    let preset = get_preset(db, id).await?;
+
+    let mut active: preset_entity::ActiveModel = preset.into();
+    active.name = Set(input.name);
+    active.focus_minutes = Set(input.focus_minutes as i32);
+    active.short_break_minutes = Set(input.short_break_minutes as i32);
+    active.long_break_minutes = Set(input.long_break_minutes as i32);
+    active.long_break_interval = Set(input.long_break_interval as i32);
+    active.updated_at = Set(Utc::now());
+    active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to update pomodoro preset {id}"))
+}
+
+/// 删除预设
+///
+/// 已有记录保留原来的 preset_id；删除当前使用的预设后改回默认配置
+pub async fn delete_preset(db: &DatabaseConnection, id: i32) -> Result<()> {
+    get_preset(db, id).await?;
+    preset_entity::Entity::delete_by_id(id)
+        .exec(db)
+        .await
+        .with_context(|| format!("failed to delete pomodoro preset {id}"))?;
+
+    if active_preset_id(db).await? == Some(id) {
+        SettingService::delete(db, KEY_ACTIVE_PRESET).await?;
+    }
+    Ok(())
+}
+
+/// 获取当前使用的预设（为空表示使用默认配置）
+pub async fn get_active_preset(db: &DatabaseConnection) -> Result<Option<preset_entity::Model>> {
+    let Some(id) = active_preset_id(db).await? else {
+        return Ok(None);
+    };
+    preset_entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load pomodoro preset {id}"))
+}
+
+/// 设置当前使用的预设，为空表示改回默认配置
+pub async fn set_active_preset(db: &DatabaseConnection, id: Option<i32>) -> Result<()> {
+    match id {
+        Some(id) => {
+            get_preset(db, id).await?;
+            SettingService::set(db, KEY_ACTIVE_PRESET, &id.to_string()).await?;
+        }
+        None => {
+            SettingService::delete(db, KEY_ACTIVE_PRESET).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 开始计时时使用的配置与预设
+///
+/// 指定的预设必须存在；未指定时使用当前预设，没有当前预设时使用默认配置。
+/// 预设的时长总是覆盖默认配置，计时器的调整时长对话框因此会先清除当前预设
+pub async fn start_config(
+    db: &DatabaseConnection,
+    preset_id: Option<i32>,
+) -> Result<(PomodoroConfig, Option<i32>)> {
+    let preset = match preset_id {
+        Some(id) => Some(get_preset(db, id).await?),
+        None => get_active_preset(db).await?,
+    };
+    let mut cfg = service::get_config(db).await?;
+    if let Some(preset) = &preset {
+        apply_preset(&mut cfg, preset);
+    }
+    Ok((cfg, preset.map(|preset| preset.id)))
+}
+
+/// 进行中的计时使用的配置：默认配置叠加预设的时长（预设已删除时使用默认配置）
+pub async fn resolve_config(
+    db: &DatabaseConnection,
+    preset_id: Option<i32>,
+) -> Result<PomodoroConfig> {
+    let mut cfg = service::get_config(db).await?;
+    if let Some(id) = preset_id {
+        if let Some(preset) = preset_entity::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .with_context(|| format!("failed to load pomodoro preset {id}"))?
+        {
+            apply_preset(&mut cfg, &preset);
+        }
+    }
+    Ok(cfg)
+}
+
+fn apply_preset(cfg: &mut PomodoroConfig, preset: &preset_entity::Model) {
+    cfg.focus_minutes = preset.focus_minutes as u32;
+    cfg.short_break_minutes = preset.short_break_minutes as u32;
+    cfg.long_break_minutes = preset.long_break_minutes as u32;
+    cfg.long_break_interval = preset.long_break_interval as u32;
+}
+
+async fn active_preset_id(db: &DatabaseConnection) -> Result<Option<i32>> {
+    Ok(SettingService::get(db, KEY_ACTIVE_PRESET)
+        .await?
+        .and_then(|value| value.parse::<i32>().ok()))
+}
+
+/// 校验并规范化预设参数
+async fn validate(
+    db: &DatabaseConnection,
+    id: Option<i32>,
+    input: PomodoroPresetInput,
+) -> Result<PomodoroPresetInput> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        bail!("preset name cannot be empty");
+    }
+    if input.focus_minutes == 0 {
+        bail!("focus minutes must be positive");
+    }
+    if input.long_break_interval == 0 || input.long_break_interval > 100 {
+        bail!("long break interval must be between 1 and 100");
+    }
+    for minutes in [
+        input.focus_minutes,
+        input.short_break_minutes,
+        input.long_break_minutes,
+    ] {
+        if minutes > 24 * 60 {
+            bail!("phase length cannot exceed 24 hours");
+        }
+    }
+
+    for other in list_presets(db).await? {
+        if Some(other.id) != id && other.name.eq_ignore_ascii_case(&name) {
+            bail!("preset \"{}\" already exists", other.name);
+        }
+    }
+
+    Ok(PomodoroPresetInput { name, ..input })
+}
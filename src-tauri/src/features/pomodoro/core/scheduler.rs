@@ -1,36 +1,358 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use sea_orm::DatabaseConnection;
 use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter, Manager, Wry};
 use tokio::{sync::Mutex, time::sleep};
 
-use crate::features::pomodoro::core::models::{PomodoroSessionKind, PomodoroSessionStatus};
-use crate::features::pomodoro::core::service as pomo_service;
+use crate::features::pomodoro::core::goals;
+use crate::features::pomodoro::core::models::{
+    PomodoroSessionKind, PomodoroSessionStatus, PomodoroTimerSnapshot,
+};
+use crate::features::pomodoro::core::presets;
+use crate::features::pomodoro::core::service::{self as pomo_service, NewPomodoroRecord};
+use crate::features::pomodoro::data::entities::pomodoro_records as record_entity;
+use crate::features::todo::core::service as todo_service;
 use crate::infrastructure::notification::NotificationManager;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
-use super::models::{PomodoroConfig, PomodoroMode, PomodoroStatus};
+use super::models::{PomodoroConfig, PomodoroMode, PomodoroStatus, PomodoroSuspendPolicy};
 
 pub const POMODORO_STATUS_EVENT: &str = "pomodoro-status";
 pub const POMODORO_TICK_EVENT: &str = "pomodoro-tick";
 pub const POMODORO_SESSION_RECORDED_EVENT: &str = "pomodoro-session-recorded";
+pub const POMODORO_GOAL_EVENT: &str = "pomodoro-goal";
 pub const WS_EVENT_STATUS: &str = "pomodoro.status";
 pub const WS_EVENT_TICK: &str = "pomodoro.tick";
 pub const WS_EVENT_EVENTS: &str = "pomodoro.events";
+pub const WS_EVENT_GOAL: &str = "pomodoro.goal";
 
+/// 计时期间每隔多少秒保存一次计时器状态（崩溃时据此计算中断阶段的结束时间）
+const SNAPSHOT_INTERVAL_TICKS: u32 = 30;
+
+/// 两次 tick 之间超过该秒数视为系统休眠/挂起
+const SUSPEND_GAP_SECONDS: i64 = 30;
+
+/// 手动调整后剩余时间的上限
+const MAX_REMAINING_SECONDS: i64 = 24 * 60 * 60;
+
+/// 计时器状态
+///
+/// 剩余时间由目标结束时间计算（不依赖 tick 次数），暂停时冻结剩余时间并单独累计暂停时长
 #[derive(Debug)]
 struct State {
     running: bool,
     paused: bool,
     mode: PomodoroMode,
+    /// 暂停时为冻结的剩余时间，计时中为最近一次计算的值
     remaining_seconds: u32,
     round: u32,
-    phase_started_at: Option<chrono::DateTime<chrono::Utc>>,
+    phase_started_at: Option<DateTime<Utc>>,
+    /// 计时中阶段的目标结束时间（暂停时为空）
+    phase_ends_at: Option<DateTime<Utc>>,
+    /// 当前暂停开始的时间
+    paused_at: Option<DateTime<Utc>>,
+    /// 本阶段已结束的暂停累计秒数
+    paused_seconds: i64,
+    /// 当前专注的待办（切换阶段时保留，停止时清除）
+    todo_id: Option<i32>,
+    /// 阶段中途切换待办后，当前待办开始计入的时间
+    segment_started_at: Option<DateTime<Utc>>,
+    /// 本阶段已经记录给之前待办的计时秒数
+    split_seconds: i64,
+    /// 本次计时使用的预设
+    preset_id: Option<i32>,
+    /// 心流模式：专注正向计时，休息结束后进入下一个心流专注
+    flow: bool,
+    /// 阶段开始时的计划秒数（心流专注为空）
+    planned_seconds: Option<u32>,
+    /// 本阶段手动延长/缩短的累计秒数
+    adjusted_seconds: i64,
+    /// 阶段已切换但尚未开始计时，等待确认
+    awaiting_confirmation: bool,
     generation: u64, // 用于标记 tick 任务的版本，每次启动时递增
 }
 
+impl State {
+    fn idle() -> Self {
+        Self {
+            running: false,
+            paused: false,
+            mode: PomodoroMode::Idle,
+            remaining_seconds: 0,
+            round: 0,
+            phase_started_at: None,
+            phase_ends_at: None,
+            paused_at: None,
+            paused_seconds: 0,
+            todo_id: None,
+            segment_started_at: None,
+            split_seconds: 0,
+            preset_id: None,
+            flow: false,
+            planned_seconds: None,
+            adjusted_seconds: 0,
+            awaiting_confirmation: false,
+            generation: 0,
+        }
+    }
+
+    /// 开始一个新阶段
+    fn begin_phase(&mut self, mode: PomodoroMode, seconds: u32, now: DateTime<Utc>) {
+        self.running = true;
+        self.paused = false;
+        self.mode = mode;
+        self.remaining_seconds = seconds;
+        self.phase_started_at = Some(now);
+        self.phase_ends_at = self.ends_at(now);
+        self.paused_at = None;
+        self.paused_seconds = 0;
+        self.segment_started_at = None;
+        self.split_seconds = 0;
+        self.planned_seconds = (mode != PomodoroMode::Flow).then_some(seconds);
+        self.adjusted_seconds = 0;
+        self.awaiting_confirmation = false;
+    }
+
+    /// 进入等待确认：保留新阶段的剩余时间，确认后才开始计时
+    fn await_confirmation(&mut self) {
+        self.awaiting_confirmation = true;
+        self.phase_started_at = None;
+        self.phase_ends_at = None;
+    }
+
+    /// 停止计时，回到空闲状态
+    fn reset(&mut self) {
+        self.running = false;
+        self.paused = false;
+        self.mode = PomodoroMode::Idle;
+        self.remaining_seconds = 0;
+        self.phase_started_at = None;
+        self.phase_ends_at = None;
+        self.paused_at = None;
+        self.paused_seconds = 0;
+        self.todo_id = None;
+        self.segment_started_at = None;
+        self.split_seconds = 0;
+        self.preset_id = None;
+        self.flow = false;
+        self.planned_seconds = None;
+        self.adjusted_seconds = 0;
+        self.awaiting_confirmation = false;
+    }
+
+    /// 是否正在计时（未暂停，也不在等待确认）
+    fn counting(&self) -> bool {
+        self.running && !self.paused && !self.awaiting_confirmation
+    }
+
+    /// 在指定时间点的剩余秒数（不足一秒按一秒计）
+    fn remaining_at(&self, now: DateTime<Utc>) -> u32 {
+        match self.phase_ends_at {
+            Some(ends_at) if !self.paused => {
+                let millis = (ends_at - now).num_milliseconds().max(0);
+                ((millis + 999) / 1000) as u32
+            }
+            _ => self.remaining_seconds,
+        }
+    }
+
+    fn pause_at(&mut self, at: DateTime<Utc>) {
+        if !self.counting() {
+            return;
+        }
+        self.remaining_seconds = self.remaining_at(at);
+        self.paused = true;
+        self.paused_at = Some(at);
+        self.phase_ends_at = None;
+    }
+
+    fn resume_at(&mut self, now: DateTime<Utc>) {
+        if !self.paused {
+            return;
+        }
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_seconds += (now - paused_at).num_seconds().max(0);
+        }
+        self.paused = false;
+        self.phase_ends_at = self.ends_at(now);
+    }
+
+    /// 两次 tick 间隔过长说明系统休眠过，按策略处理；返回是否因此暂停
+    fn detect_suspend(
+        &mut self,
+        last_tick: DateTime<Utc>,
+        now: DateTime<Utc>,
+        policy: PomodoroSuspendPolicy,
+    ) -> bool {
+        if !self.counting() || (now - last_tick).num_seconds() <= SUSPEND_GAP_SECONDS {
+            return false;
+        }
+        match policy {
+            // 从休眠开始时暂停，休眠时间计为暂停
+            PomodoroSuspendPolicy::Pause => {
+                self.pause_at(last_tick);
+                true
+            }
+            // 按墙钟时间继续，休眠期间已经结束的阶段按原定结束时间完成
+            PomodoroSuspendPolicy::Finish => false,
+        }
+    }
+
+    /// 从保存的状态恢复进行中的阶段
+    ///
+    /// 关闭期间计为暂停：已暂停的继续暂停，计时中的从关闭时开始暂停并在 `now` 继续
+    fn restore(&mut self, snapshot: &PomodoroTimerSnapshot, now: DateTime<Utc>) {
+        self.running = true;
+        self.mode = snapshot.mode;
+        self.remaining_seconds = snapshot.remaining_seconds;
+        self.round = snapshot.round;
+        self.phase_started_at = Some(snapshot.phase_started_at);
+        self.paused_seconds = snapshot.paused_seconds;
+        self.todo_id = snapshot.todo_id;
+        self.segment_started_at = snapshot.segment_started_at;
+        self.split_seconds = snapshot.split_seconds;
+        self.preset_id = snapshot.preset_id;
+        self.flow = snapshot.flow;
+        self.planned_seconds = snapshot.planned_seconds;
+        self.adjusted_seconds = snapshot.adjusted_seconds;
+        self.awaiting_confirmation = false;
+        if snapshot.awaiting_confirmation {
+            self.paused = false;
+            self.paused_at = None;
+            self.await_confirmation();
+        } else {
+            self.paused = true;
+            self.paused_at = Some(match snapshot.paused_at {
+                Some(paused_at) if snapshot.paused => paused_at,
+                _ => snapshot.saved_at,
+            });
+            self.phase_ends_at = None;
+            if !snapshot.paused {
+                self.resume_at(now);
+            }
+        }
+    }
+
+    /// 从 `now` 开始计时剩余时间的结束时间（心流专注没有结束时间）
+    fn ends_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.mode == PomodoroMode::Flow {
+            return None;
+        }
+        Some(now + chrono::Duration::seconds(self.remaining_seconds as i64))
+    }
+
+    /// 本阶段截至 `end` 的实际计时秒数（不含暂停）
+    fn active_seconds(&self, end: DateTime<Utc>) -> i64 {
+        let Some(start) = self.phase_started_at else {
+            return 0;
+        };
+        active_seconds(start, end, self.paused_seconds, self.paused_at)
+    }
+
+    /// 当前待办在本阶段截至 `end_at` 的记录，写入时提供 session_id
+    ///
+    /// 中途切换过待办时只包含切换之后的部分；没有开始计时的阶段返回 None
+    fn record_until(
+        &self,
+        status: PomodoroSessionStatus,
+        end_at: DateTime<Utc>,
+    ) -> Option<impl FnOnce(i32) -> NewPomodoroRecord> {
+        if !self.running {
+            return None;
+        }
+        let start_at = self.segment_started_at.or(self.phase_started_at)?;
+        let kind = session_kind(self.mode);
+        let record = NewPomodoroRecord {
+            session_id: 0,
+            kind,
+            status,
+            round: self.round,
+            start_at,
+            end_at: end_at.max(start_at),
+            elapsed_seconds: (self.active_seconds(end_at) - self.split_seconds).max(0),
+            related_todo_id: match kind {
+                PomodoroSessionKind::Focus => self.todo_id,
+                PomodoroSessionKind::Rest => None,
+            },
+            preset_id: self.preset_id,
+            planned_seconds: self.planned_seconds,
+            adjusted_seconds: self.adjusted_seconds,
+        };
+        Some(move |session_id| NewPomodoroRecord {
+            session_id,
+            ..record
+        })
+    }
+
+    /// 切换专注的待办
+    ///
+    /// 专注阶段已经计时的部分属于原待办，返回需要单独写入的 stopped 记录；
+    /// 计划与调整时长留在阶段最终的记录中
+    fn switch_todo(
+        &mut self,
+        todo_id: Option<i32>,
+        now: DateTime<Utc>,
+    ) -> Option<impl FnOnce(i32) -> NewPomodoroRecord> {
+        let previous = self.todo_id;
+        self.todo_id = todo_id;
+        if previous == todo_id
+            || self.awaiting_confirmation
+            || session_kind(self.mode) != PomodoroSessionKind::Focus
+        {
+            return None;
+        }
+
+        let start_at = self.segment_started_at.or(self.phase_started_at)?;
+        let elapsed_seconds = (self.active_seconds(now) - self.split_seconds).max(0);
+        if elapsed_seconds == 0 {
+            return None;
+        }
+        self.split_seconds += elapsed_seconds;
+        self.segment_started_at = Some(now);
+
+        let record = NewPomodoroRecord {
+            session_id: 0,
+            kind: PomodoroSessionKind::Focus,
+            status: PomodoroSessionStatus::Stopped,
+            round: self.round,
+            start_at,
+            end_at: now.max(start_at),
+            elapsed_seconds,
+            related_todo_id: previous,
+            preset_id: self.preset_id,
+            planned_seconds: None,
+            adjusted_seconds: 0,
+        };
+        Some(move |session_id| NewPomodoroRecord {
+            session_id,
+            ..record
+        })
+    }
+
+    fn status(&self, now: DateTime<Utc>) -> PomodoroStatus {
+        PomodoroStatus {
+            running: self.running,
+            paused: self.paused,
+            mode: self.mode,
+            remaining_seconds: self.remaining_at(now),
+            elapsed_seconds: if self.running {
+                self.active_seconds(now) as u32
+            } else {
+                0
+            },
+            round: self.round,
+            flow: self.flow,
+            todo_id: self.todo_id,
+            preset_id: self.preset_id,
+            adjusted_seconds: self.adjusted_seconds,
+            awaiting_confirmation: self.awaiting_confirmation,
+        }
+    }
+}
+
 pub struct PomodoroManager {
     app: AppHandle<Wry>,
     notifier: NotificationManager,
@@ -43,56 +365,299 @@ impl PomodoroManager {
         Self {
             app,
             notifier,
-            state: Arc::new(Mutex::new(State {
-                running: false,
-                paused: false,
-                mode: PomodoroMode::Idle,
-                remaining_seconds: 0,
-                round: 0,
-                phase_started_at: None,
-                generation: 0,
-            })),
+            state: Arc::new(Mutex::new(State::idle())),
             tick_task: Mutex::new(None),
         }
     }
 
-    pub async fn start(&self, cfg: PomodoroConfig) -> Result<PomodoroStatus> {
+    /// 开始专注
+    ///
+    /// `cfg` 为已叠加预设时长的配置，`preset_id` 记录到本次计时的所有记录中，`todo_id` 为专注的待办
+    pub async fn start(
+        &self,
+        cfg: PomodoroConfig,
+        preset_id: Option<i32>,
+        todo_id: Option<i32>,
+    ) -> Result<PomodoroStatus> {
+        self.start_run(cfg, preset_id, todo_id, false).await
+    }
+
+    /// 开始心流专注：正向计时，调用 `finish` 结束后按专注时长休息
+    pub async fn start_flow(
+        &self,
+        cfg: PomodoroConfig,
+        preset_id: Option<i32>,
+        todo_id: Option<i32>,
+    ) -> Result<PomodoroStatus> {
+        self.start_run(cfg, preset_id, todo_id, true).await
+    }
+
+    async fn start_run(
+        &self,
+        cfg: PomodoroConfig,
+        preset_id: Option<i32>,
+        todo_id: Option<i32>,
+        flow: bool,
+    ) -> Result<PomodoroStatus> {
+        if let Some(todo_id) = todo_id {
+            self.ensure_todo(todo_id).await?;
+        }
+        let mode = if flow {
+            PomodoroMode::Flow
+        } else {
+            PomodoroMode::Focus
+        };
         {
             let mut s = self.state.lock().await;
-            s.running = true;
-            s.paused = false;
-            s.mode = PomodoroMode::Focus;
-            s.remaining_seconds = cfg.focus_minutes * 60;
-            s.round = s.round.max(0);
-            s.phase_started_at = Some(Utc::now());
+            s.begin_phase(mode, first_phase_seconds(&cfg, mode), Utc::now());
+            s.todo_id = todo_id;
+            s.preset_id = preset_id;
+            s.flow = flow;
             s.generation = s.generation.wrapping_add(1); // 递增 generation 以终止旧任务
         }
 
         self.spawn_tick_loop(cfg).await;
-        self.notify_phase_start(PomodoroMode::Focus).await;
+        save_timer_state(&self.state, &self.app).await;
+        self.notify_phase_start(mode).await;
+        Ok(self.status().await)
+    }
+
+    /// 结束心流专注（记录为 completed）并开始按专注时长计算的休息
+    pub async fn finish(&self, cfg: PomodoroConfig) -> Result<PomodoroStatus> {
+        let (mode, awaiting) = {
+            let s = self.state.lock().await;
+            (s.mode, s.awaiting_confirmation)
+        };
+        if mode != PomodoroMode::Flow {
+            bail!("only a flow focus can be finished");
+        }
+        if awaiting {
+            bail!("pomodoro is waiting for confirmation");
+        }
+
+        self.notifier.send_websocket_event(
+            WS_EVENT_EVENTS.to_string(),
+            serde_json::json!({
+                "type": "finish",
+                "mode": format_mode(mode),
+            }),
+        );
+        let persisted = persist_phase(
+            &self.state,
+            &self.app,
+            PomodoroSessionStatus::Completed,
+            Utc::now(),
+        )
+        .await;
+        after_completed(&self.app, &cfg, persisted).await;
+
+        self.advance_phase(cfg).await;
+        Ok(self.status().await)
+    }
+
+    /// 启动时恢复上次未结束的阶段
+    ///
+    /// 开启 `resume_on_restart` 时从保存时的剩余时间继续（应用关闭期间计为暂停），
+    /// 否则把中断的阶段记录为 stopped，结束时间为最后一次保存状态的时间
+    pub async fn recover(&self, db: &DatabaseConnection) -> Result<()> {
+        let Some(snapshot) = pomo_service::load_timer_snapshot(db).await? else {
+            return Ok(());
+        };
+        if snapshot.mode == PomodoroMode::Idle {
+            pomo_service::clear_timer_snapshot(db).await?;
+            return Ok(());
+        }
+        let cfg = presets::resolve_config(db, snapshot.preset_id).await?;
+
+        if snapshot.awaiting_confirmation && !cfg.resume_on_restart {
+            // 等待确认的阶段还没有开始，不需要记录
+            pomo_service::clear_timer_snapshot(db).await?;
+            return Ok(());
+        }
+
+        if !cfg.resume_on_restart {
+            let active_session = pomo_service::get_or_create_active_session(db, None).await?;
+            // 中途切换过待办时，之前的部分已经单独记录
+            let start_at = snapshot
+                .segment_started_at
+                .unwrap_or(snapshot.phase_started_at);
+            let end_at = snapshot.saved_at.max(start_at);
+            let elapsed_seconds = active_seconds(
+                snapshot.phase_started_at,
+                end_at,
+                snapshot.paused_seconds,
+                snapshot.paused_at,
+            ) - snapshot.split_seconds;
+            let record = pomo_service::create_record_with_session(
+                db,
+                NewPomodoroRecord {
+                    session_id: active_session.id,
+                    kind: session_kind(snapshot.mode),
+                    status: PomodoroSessionStatus::Stopped,
+                    round: snapshot.round,
+                    start_at,
+                    end_at,
+                    elapsed_seconds: elapsed_seconds.max(0),
+                    related_todo_id: match session_kind(snapshot.mode) {
+                        PomodoroSessionKind::Focus => snapshot.todo_id,
+                        PomodoroSessionKind::Rest => None,
+                    },
+                    preset_id: snapshot.preset_id,
+                    planned_seconds: snapshot.planned_seconds,
+                    adjusted_seconds: snapshot.adjusted_seconds,
+                },
+            )
+            .await?;
+            pomo_service::clear_timer_snapshot(db).await?;
+            update_goal_progress(&self.app, db, &record).await;
+            println!(
+                "[Pomodoro] 已将中断的{}阶段记录为 stopped",
+                format_mode(snapshot.mode)
+            );
+            let _ = self.app.emit(POMODORO_SESSION_RECORDED_EVENT, ());
+            return Ok(());
+        }
+
+        {
+            let mut s = self.state.lock().await;
+            s.restore(&snapshot, Utc::now());
+            s.generation = s.generation.wrapping_add(1);
+        }
+
+        self.spawn_tick_loop(cfg).await;
+        if let Some(snapshot) = snapshot_of(&*self.state.lock().await) {
+            pomo_service::save_timer_snapshot(db, &snapshot).await?;
+        }
+
+        println!(
+            "[Pomodoro] 已恢复{}阶段，剩余 {} 秒",
+            format_mode(snapshot.mode),
+            snapshot.remaining_seconds
+        );
+        Ok(())
+    }
+
+    /// 切换专注的待办（为空表示取消关联）
+    ///
+    /// 专注阶段中途切换时，已计时的部分记录为原待办的 stopped 记录，其余部分计入新的待办
+    pub async fn set_todo(&self, todo_id: Option<i32>) -> Result<PomodoroStatus> {
+        if let Some(todo_id) = todo_id {
+            self.ensure_todo(todo_id).await?;
+        }
+        let split = {
+            let mut s = self.state.lock().await;
+            if !s.running {
+                bail!("pomodoro is not running");
+            }
+            s.switch_todo(todo_id, Utc::now())
+        };
+        if let Some(split) = split {
+            if let Err(e) = write_record(&self.app, split).await {
+                eprintln!("persist split record error: {}", e);
+            }
+        }
+        save_timer_state(&self.state, &self.app).await;
+        self.broadcast_status().await;
+        Ok(self.status().await)
+    }
+
+    /// 确认开始等待中的阶段
+    pub async fn confirm(&self) -> Result<PomodoroStatus> {
+        let mode = {
+            let mut s = self.state.lock().await;
+            if !s.running || !s.awaiting_confirmation {
+                bail!("pomodoro is not waiting for confirmation");
+            }
+            let now = Utc::now();
+            s.awaiting_confirmation = false;
+            s.phase_started_at = Some(now);
+            s.phase_ends_at = s.ends_at(now);
+            s.mode
+        };
+        save_timer_state(&self.state, &self.app).await;
+        self.notify_phase_start(mode).await;
+        Ok(self.status().await)
+    }
+
+    /// 延长（正数）或缩短（负数）当前阶段的剩余时间
+    ///
+    /// 剩余时间缩短到 0 时本阶段立即记为完成；调整量累计到本阶段的记录中
+    pub async fn adjust(&self, delta_seconds: i64) -> Result<PomodoroStatus> {
+        self.adjust_remaining(|remaining| (remaining as i64).saturating_add(delta_seconds))
+            .await
+    }
+
+    /// 把当前阶段的剩余时间设为 `seconds`
+    pub async fn set_remaining(&self, seconds: u32) -> Result<PomodoroStatus> {
+        self.adjust_remaining(|_| seconds as i64).await
+    }
+
+    async fn adjust_remaining(&self, target: impl FnOnce(u32) -> i64) -> Result<PomodoroStatus> {
+        let (mode, delta, remaining) = {
+            let mut s = self.state.lock().await;
+            if !s.running {
+                bail!("pomodoro is not running");
+            }
+            if s.mode == PomodoroMode::Flow {
+                bail!("a flow focus has no remaining time to adjust");
+            }
+            let now = Utc::now();
+            let before = s.remaining_at(now);
+            let after = target(before).max(0);
+            if after > MAX_REMAINING_SECONDS {
+                bail!("remaining time cannot exceed 24 hours");
+            }
+            let after = after as u32;
+            s.remaining_seconds = after;
+            if s.counting() {
+                s.phase_ends_at = s.ends_at(now);
+            }
+            let delta = after as i64 - before as i64;
+            s.adjusted_seconds += delta;
+            (s.mode, delta, after)
+        };
+
+        self.notifier.send_websocket_event(
+            WS_EVENT_EVENTS.to_string(),
+            serde_json::json!({
+                "type": "adjust",
+                "mode": format_mode(mode),
+                "deltaSeconds": delta,
+                "remainingSeconds": remaining,
+            }),
+        );
+        save_timer_state(&self.state, &self.app).await;
+        self.broadcast_status().await;
         Ok(self.status().await)
     }
 
     pub async fn pause(&self) -> PomodoroStatus {
         let mut s = self.state.lock().await;
-        s.paused = true;
+        s.pause_at(Utc::now());
         drop(s);
+        save_timer_state(&self.state, &self.app).await;
         self.broadcast_status().await;
         self.status().await
     }
 
     pub async fn resume(&self) -> PomodoroStatus {
         let mut s = self.state.lock().await;
-        s.paused = false;
+        s.resume_at(Utc::now());
         drop(s);
+        save_timer_state(&self.state, &self.app).await;
         self.broadcast_status().await;
         self.status().await
     }
 
     pub async fn skip(&self, cfg: PomodoroConfig) -> PomodoroStatus {
         // 持久化当前阶段为 skipped
-        if let Err(e) =
-            persist_with_status(&self.state, &self.app, PomodoroSessionStatus::Skipped).await
+        if let Err(e) = persist_phase(
+            &self.state,
+            &self.app,
+            PomodoroSessionStatus::Skipped,
+            Utc::now(),
+        )
+        .await
         {
             eprintln!("persist skipped error: {}", e);
         }
@@ -112,21 +677,23 @@ impl PomodoroManager {
 
     pub async fn stop(&self) -> PomodoroStatus {
         let previous_mode = { self.state.lock().await.mode };
+        // 持久化当前阶段为 stopped（如果有进行中的阶段，需在重置状态之前）
+        if let Err(e) = persist_phase(
+            &self.state,
+            &self.app,
+            PomodoroSessionStatus::Stopped,
+            Utc::now(),
+        )
+        .await
         {
-            let mut s = self.state.lock().await;
-            s.running = false;
-            s.paused = false;
-            s.mode = PomodoroMode::Idle;
-            s.remaining_seconds = 0;
-            s.round = s.round;
-            s.generation = s.generation.wrapping_add(1); // 递增 generation 以终止旧任务
+            eprintln!("persist stopped error: {}", e);
         }
-        // 持久化当前阶段为 stopped（如果有进行中的阶段）
-        if let Err(e) =
-            persist_with_status(&self.state, &self.app, PomodoroSessionStatus::Stopped).await
         {
-            eprintln!("persist stopped error: {}", e);
+            let mut s = self.state.lock().await;
+            s.reset();
+            s.generation = s.generation.wrapping_add(1); // 递增 generation 以终止旧任务
         }
+        save_timer_state(&self.state, &self.app).await;
 
         self.notifier.send_websocket_event(
             WS_EVENT_EVENTS.to_string(),
@@ -142,14 +709,19 @@ impl PomodoroManager {
     }
 
     pub async fn status(&self) -> PomodoroStatus {
-        let s = self.state.lock().await;
-        PomodoroStatus {
-            running: s.running,
-            paused: s.paused,
-            mode: s.mode,
-            remaining_seconds: s.remaining_seconds,
-            round: s.round,
+        self.state.lock().await.status(Utc::now())
+    }
+
+    /// 检查待办存在且不在回收站中
+    async fn ensure_todo(&self, todo_id: i32) -> Result<()> {
+        let Some(state) = self.app.try_state::<crate::core::AppState>() else {
+            return Ok(());
+        };
+        let todo = todo_service::get_todo_by_id(state.db(), todo_id).await?;
+        if todo.deleted_at.is_some() {
+            bail!("todo {todo_id} not found");
         }
+        Ok(())
     }
 
     async fn spawn_tick_loop(&self, cfg: PomodoroConfig) {
@@ -165,11 +737,17 @@ impl PomodoroManager {
         };
 
         let handle = tauri::async_runtime::spawn(async move {
+            let mut ticks = 0u32;
+            let mut last_tick = Utc::now();
             loop {
                 sleep(Duration::from_secs(1)).await;
-                let mut finished = false;
-                let mut mode = PomodoroMode::Idle;
-                let mut remaining = 0u32;
+                let now = Utc::now();
+                let mut finished_at = None;
+                let mut pause_timed_out = false;
+                let suspended;
+                let mode;
+                let remaining;
+                let elapsed;
 
                 {
                     let mut s = state_ptr.lock().await;
@@ -180,41 +758,80 @@ impl PomodoroManager {
                     if !s.running {
                         break;
                     }
-                    if s.paused {
-                        // 仅广播 tick，不减少时间
-                        remaining = s.remaining_seconds;
-                        mode = s.mode;
-                    } else {
-                        if s.remaining_seconds > 0 {
-                            s.remaining_seconds -= 1;
-                        }
-                        remaining = s.remaining_seconds;
-                        mode = s.mode;
-                        if s.remaining_seconds == 0 {
-                            finished = true;
+                    suspended = s.detect_suspend(last_tick, now, cfg.suspend_policy);
+                    remaining = s.remaining_at(now);
+                    s.remaining_seconds = remaining;
+                    elapsed = s.active_seconds(now) as u32;
+                    mode = s.mode;
+                    // 心流专注正向计时，只能手动结束
+                    if s.counting() && mode != PomodoroMode::Flow && remaining == 0 {
+                        finished_at = Some(s.phase_ends_at.map_or(now, |ends_at| ends_at.min(now)));
+                    }
+                    // 暂停时间过长时自动停止
+                    if cfg.pause_timeout_minutes > 0 {
+                        if let Some(paused_at) = s.paused_at.filter(|_| s.paused) {
+                            pause_timed_out = (now - paused_at).num_seconds()
+                                >= cfg.pause_timeout_minutes as i64 * 60;
                         }
                     }
                 }
+                last_tick = now;
+
+                if pause_timed_out {
+                    stop_after_pause_timeout(&state_ptr, &notifier, &manager_app, &cfg, mode).await;
+                    break;
+                }
+
+                if suspended {
+                    println!("[Pomodoro] 检测到系统休眠，已暂停计时");
+                    let _ = notifier.send_toast(
+                        "检测到系统休眠，番茄钟已暂停".to_string(),
+                        crate::infrastructure::notification::ToastLevel::Info,
+                    );
+                    notifier.send_websocket_event(
+                        WS_EVENT_EVENTS.to_string(),
+                        serde_json::json!({
+                            "type": "suspend",
+                            "mode": format_mode(mode),
+                        }),
+                    );
+                    let status = state_ptr.lock().await.status(now);
+                    emit_status(&manager_app, &notifier, &status);
+                    save_timer_state(&state_ptr, &manager_app).await;
+                }
 
                 // 广播 tick
                 let _ = manager_app.emit(
                     POMODORO_TICK_EVENT,
                     serde_json::json!({
                         "remainingSeconds": remaining,
+                        "elapsedSeconds": elapsed,
                     }),
                 );
                 notifier.send_websocket_event(
                     WS_EVENT_TICK.to_string(),
                     serde_json::json!({
                         "remainingSeconds": remaining,
+                        "elapsedSeconds": elapsed,
                         "mode": format_mode(mode),
                     }),
                 );
 
                 // 更新托盘 tooltip（桌面）
-                update_tray_tooltip(&manager_app, mode, remaining).await;
+                let shown = if mode == PomodoroMode::Flow {
+                    elapsed
+                } else {
+                    remaining
+                };
+                update_tray_tooltip(&manager_app, mode, shown).await;
+
+                // 定期保存计时器状态，阶段结束时由 advance_phase_internal 保存
+                ticks = ticks.wrapping_add(1);
+                if finished_at.is_none() && ticks.is_multiple_of(SNAPSHOT_INTERVAL_TICKS) {
+                    save_timer_state(&state_ptr, &manager_app).await;
+                }
 
-                if finished {
+                if let Some(finished_at) = finished_at {
                     // 阶段结束，切换下一阶段
                     notifier.send_websocket_event(
                         WS_EVENT_EVENTS.to_string(),
@@ -225,9 +842,14 @@ impl PomodoroManager {
                     );
 
                     // 持久化本阶段
-                    if let Err(e) = persist_finished_phase(&state_ptr, &manager_app).await {
-                        eprintln!("Pomodoro persist error: {}", e);
-                    }
+                    let persisted = persist_phase(
+                        &state_ptr,
+                        &manager_app,
+                        PomodoroSessionStatus::Completed,
+                        finished_at,
+                    )
+                    .await;
+                    after_completed(&manager_app, &cfg, persisted).await;
                     if let Err(e) =
                         advance_phase_internal(&state_ptr, &notifier, &manager_app, cfg.clone())
                             .await
@@ -256,6 +878,7 @@ impl PomodoroManager {
     async fn notify_phase_start(&self, mode: PomodoroMode) {
         let (title, body) = match mode {
             PomodoroMode::Focus => ("开始专注", "进入专注阶段"),
+            PomodoroMode::Flow => ("开始心流专注", "正向计时，完成后按专注时长休息"),
             PomodoroMode::ShortBreak => ("短休开始", "放松一下"),
             PomodoroMode::LongBreak => ("长休开始", "好好休息"),
             PomodoroMode::Idle => ("空闲", ""),
@@ -267,7 +890,7 @@ impl PomodoroManager {
         let _ = self
             .notifier
             .send_native(title.to_string(), body.to_string());
-        
+
         self.notifier.send_websocket_event(
             WS_EVENT_EVENTS.to_string(),
             serde_json::json!({
@@ -281,11 +904,7 @@ impl PomodoroManager {
 
     async fn broadcast_status(&self) {
         let status = self.status().await;
-        let _ = self.app.emit(POMODORO_STATUS_EVENT, &status);
-        self.notifier.send_websocket_event(
-            WS_EVENT_STATUS.to_string(),
-            serde_json::to_value(&status).unwrap_or_default(),
-        );
+        emit_status(&self.app, &self.notifier, &status);
     }
 }
 
@@ -296,8 +915,7 @@ async fn advance_phase_internal(
     cfg: PomodoroConfig,
 ) -> Result<()> {
     let next_mode;
-    let mut next_seconds = 0u32;
-    let mut _next_round;
+    let next_seconds;
 
     {
         let mut s = state_ptr.lock().await;
@@ -317,23 +935,63 @@ async fn advance_phase_internal(
                     cfg.short_break_minutes
                 }) * 60;
             }
+            PomodoroMode::Flow => {
+                // 休息时长按本次心流专注的时长计算
+                s.round += 1;
+                next_mode = PomodoroMode::ShortBreak;
+                next_seconds = cfg.flow_break_seconds(s.active_seconds(Utc::now()));
+            }
             PomodoroMode::ShortBreak | PomodoroMode::LongBreak | PomodoroMode::Idle => {
-                next_mode = PomodoroMode::Focus;
-                next_seconds = cfg.focus_minutes * 60;
+                next_mode = if s.flow {
+                    PomodoroMode::Flow
+                } else {
+                    PomodoroMode::Focus
+                };
+                next_seconds = first_phase_seconds(&cfg, next_mode);
             }
         }
 
-        s.mode = next_mode;
-        s.remaining_seconds = next_seconds;
-        s.paused = false;
-        s.running = true;
-        _next_round = s.round;
-        s.phase_started_at = Some(Utc::now());
+        s.begin_phase(next_mode, next_seconds, Utc::now());
+        if !cfg.auto_starts(next_mode) {
+            s.await_confirmation();
+        }
+    }
+
+    let awaiting = { state_ptr.lock().await.awaiting_confirmation };
+    if awaiting {
+        // 等待确认，确认后再发送阶段开始通知
+        let title = match next_mode {
+            PomodoroMode::Focus | PomodoroMode::Flow => "休息结束",
+            _ => "专注结束",
+        };
+        let body = match next_mode {
+            PomodoroMode::Focus | PomodoroMode::Flow => "确认后开始专注",
+            _ => "确认后开始休息",
+        };
+        let _ = notifier.send_toast(
+            format!("{title}，{body}"),
+            crate::infrastructure::notification::ToastLevel::Info,
+        );
+        let _ = notifier.send_native(title.to_string(), body.to_string());
+        notifier.send_websocket_event(
+            WS_EVENT_EVENTS.to_string(),
+            serde_json::json!({
+                "type": "awaiting_confirmation",
+                "mode": format_mode(next_mode),
+            }),
+        );
+
+        let status = state_ptr.lock().await.status(Utc::now());
+        emit_status(app, notifier, &status);
+        update_tray_tooltip(app, next_mode, status.remaining_seconds).await;
+        save_timer_state(state_ptr, app).await;
+        return Ok(());
     }
 
     // 阶段开始通知与广播
     let (title, body) = match next_mode {
         PomodoroMode::Focus => ("开始专注", "进入专注阶段"),
+        PomodoroMode::Flow => ("开始心流专注", "正向计时，完成后按专注时长休息"),
         PomodoroMode::ShortBreak => ("短休开始", "放松一下"),
         PomodoroMode::LongBreak => ("长休开始", "好好休息"),
         PomodoroMode::Idle => ("空闲", ""),
@@ -353,41 +1011,74 @@ async fn advance_phase_internal(
     );
 
     // 立即广播最新状态 & 更新托盘
-    let status = {
-        let s = state_ptr.lock().await;
-        PomodoroStatus {
-            running: s.running,
-            paused: s.paused,
-            mode: s.mode,
-            remaining_seconds: s.remaining_seconds,
-            round: s.round,
-        }
-    };
-    let _ = app.emit(POMODORO_STATUS_EVENT, &status);
+    let status = state_ptr.lock().await.status(Utc::now());
+    emit_status(app, notifier, &status);
+    update_tray_tooltip(app, next_mode, status.remaining_seconds).await;
+    save_timer_state(state_ptr, app).await;
+
+    Ok(())
+}
+
+/// 暂停超时：把当前阶段记录为 stopped 并停止计时
+async fn stop_after_pause_timeout(
+    state_ptr: &Mutex<State>,
+    notifier: &NotificationManager,
+    app: &AppHandle<Wry>,
+    cfg: &PomodoroConfig,
+    mode: PomodoroMode,
+) {
+    if let Err(e) = persist_phase(state_ptr, app, PomodoroSessionStatus::Stopped, Utc::now()).await
+    {
+        eprintln!("persist stopped error: {}", e);
+    }
+    {
+        let mut s = state_ptr.lock().await;
+        s.reset();
+        s.generation = s.generation.wrapping_add(1);
+    }
+    save_timer_state(state_ptr, app).await;
+
+    println!(
+        "[Pomodoro] 暂停超过 {} 分钟，已自动停止",
+        cfg.pause_timeout_minutes
+    );
+    let _ = notifier.send_toast(
+        format!("暂停超过 {} 分钟，番茄钟已停止", cfg.pause_timeout_minutes),
+        crate::infrastructure::notification::ToastLevel::Info,
+    );
     notifier.send_websocket_event(
-        WS_EVENT_STATUS.to_string(),
-        serde_json::to_value(&status).unwrap_or_default(),
+        WS_EVENT_EVENTS.to_string(),
+        serde_json::json!({
+            "type": "pause_timeout",
+            "mode": format_mode(mode),
+        }),
     );
-    update_tray_tooltip(app, next_mode, next_seconds).await;
 
-    Ok(())
+    let status = state_ptr.lock().await.status(Utc::now());
+    emit_status(app, notifier, &status);
+    update_tray_tooltip(app, PomodoroMode::Idle, 0).await;
 }
 
-async fn persist_finished_phase(state_ptr: &Arc<Mutex<State>>, app: &AppHandle<Wry>) -> Result<()> {
-    use tauri::Manager;
-    let (mode, started_at, round) = {
-        let s = state_ptr.lock().await;
-        (s.mode, s.phase_started_at, s.round)
-    };
-    let Some(start_at) = started_at else {
-        return Ok(());
-    };
-    let end_at = Utc::now();
-    let kind = match mode {
-        PomodoroMode::Focus => PomodoroSessionKind::Focus,
-        _ => PomodoroSessionKind::Rest,
+/// 把当前阶段写入记录，结束时间为 `end_at`，记录的时长不含暂停
+///
+/// 专注阶段关联写入时的待办；没有进行中的阶段时返回 None
+async fn persist_phase(
+    state_ptr: &Mutex<State>,
+    app: &AppHandle<Wry>,
+    status: PomodoroSessionStatus,
+    end_at: DateTime<Utc>,
+) -> Result<Option<record_entity::Model>> {
+    let Some(record) = state_ptr.lock().await.record_until(status, end_at) else {
+        return Ok(None);
     };
+    write_record(app, record).await
+}
 
+/// 把记录写入当前活动 session 并更新目标进度
+async fn write_record(
+    app: &AppHandle<Wry>,
+    record: impl FnOnce(i32) -> NewPomodoroRecord,
+) -> Result<Option<record_entity::Model>> {
     if let Some(state) = app.try_state::<crate::core::AppState>() {
         let db = state.db().clone();
 
@@ -395,93 +1086,228 @@ async fn persist_finished_phase(state_ptr: &Arc<Mutex<State>>, app: &AppHandle<W
         let active_session = pomo_service::get_or_create_active_session(&db, None).await?;
 
         // 创建 record 并关联到 session
-        pomo_service::create_record_with_session(
-            &db,
-            active_session.id,
-            kind,
-            PomodoroSessionStatus::Completed,
-            round,
-            start_at,
-            end_at,
-            None,
-        )
-        .await?;
+        let record =
+            pomo_service::create_record_with_session(&db, record(active_session.id)).await?;
+        update_goal_progress(app, &db, &record).await;
 
         // 发送会话记录更新事件
         println!("发送会话记录事件: {}", POMODORO_SESSION_RECORDED_EVENT);
         let _ = app.emit(POMODORO_SESSION_RECORDED_EVENT, ());
+        return Ok(Some(record));
     }
-    Ok(())
+    Ok(None)
 }
 
-async fn persist_with_status(
-    state_ptr: &Arc<Mutex<State>>,
+/// 写入专注记录后更新每日目标进度，达到里程碑时发送事件
+async fn update_goal_progress(
     app: &AppHandle<Wry>,
-    status: PomodoroSessionStatus,
-) -> Result<()> {
-    use tauri::Manager;
-    let (mode, started_at, round, running) = {
-        let s = state_ptr.lock().await;
-        (s.mode, s.phase_started_at, s.round, s.running)
+    db: &DatabaseConnection,
+    record: &record_entity::Model,
+) {
+    let milestone = match goals::record_progress(db, record).await {
+        Ok(Some(milestone)) => milestone,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("Pomodoro goal progress error: {}", e);
+            return;
+        }
     };
-    if !running {
-        return Ok(());
+    println!(
+        "[Pomodoro] {} 的专注目标已完成 {}%",
+        milestone.progress.date, milestone.milestone
+    );
+
+    let _ = app.emit(POMODORO_GOAL_EVENT, &milestone);
+    if let Some(state) = app.try_state::<crate::core::AppState>() {
+        let notifier = state.notification();
+        let message = if milestone.progress.reached {
+            "专注目标已达成".to_string()
+        } else {
+            format!("专注目标已完成 {}%", milestone.milestone)
+        };
+        let _ = notifier.send_toast(
+            message,
+            crate::infrastructure::notification::ToastLevel::Success,
+        );
+        notifier.send_websocket_event(
+            WS_EVENT_GOAL.to_string(),
+            serde_json::to_value(&milestone).unwrap_or_default(),
+        );
     }
-    let Some(start_at) = started_at else {
+}
+
+/// 阶段记为完成之后的处理（达到预估番茄数时自动完成待办）
+async fn after_completed(
+    app: &AppHandle<Wry>,
+    cfg: &PomodoroConfig,
+    persisted: Result<Option<record_entity::Model>>,
+) {
+    match persisted {
+        Ok(Some(record)) if cfg.auto_complete_todo => {
+            if let Some(todo_id) = record.related_todo_id {
+                if let Err(e) = complete_todo_if_estimated(app, todo_id).await {
+                    eprintln!("Pomodoro auto complete todo error: {}", e);
+                }
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Pomodoro persist error: {}", e),
+    }
+}
+
+/// 待办完成的番茄数达到预估时自动完成它
+///
+/// 已完成、已删除、没有预估的待办不处理；重复待办需要推进到下一次发生，也不自动完成
+async fn complete_todo_if_estimated(app: &AppHandle<Wry>, todo_id: i32) -> Result<()> {
+    let Some(state) = app.try_state::<crate::core::AppState>() else {
         return Ok(());
     };
-    let end_at = Utc::now();
-    let kind = match mode {
-        PomodoroMode::Focus => PomodoroSessionKind::Focus,
-        _ => PomodoroSessionKind::Rest,
+    let db = state.db();
+
+    let todo = todo_service::get_todo_by_id(db, todo_id).await?;
+    let Some(estimate) = todo.estimated_pomodoros else {
+        return Ok(());
     };
-    if let Some(state) = app.try_state::<crate::core::AppState>() {
-        let db = state.db().clone();
+    if todo.completed
+        || todo.deleted_at.is_some()
+        || todo
+            .recurrence_rule
+            .as_deref()
+            .is_some_and(|rule| !rule.trim().is_empty())
+    {
+        return Ok(());
+    }
 
-        // 获取或创建活动 session（自动创建时不带备注）
-        let active_session = pomo_service::get_or_create_active_session(&db, None).await?;
+    let count = pomo_service::get_todo_focus_stats(db, Some(vec![todo_id]))
+        .await?
+        .first()
+        .map_or(0, |stats| stats.pomodoro_count);
+    if count < estimate as i64 {
+        return Ok(());
+    }
 
-        // 创建 record 并关联到 session
-        pomo_service::create_record_with_session(
-            &db,
-            active_session.id,
-            kind,
-            status,
-            round,
-            start_at,
-            end_at,
-            None,
-        )
-        .await?;
+    todo_service::update_todo(db, todo_id, None, Some(true)).await?;
+    println!(
+        "[Pomodoro] 待办 {} 已完成 {}/{} 个番茄，自动标记为完成",
+        todo_id, count, estimate
+    );
 
-        // 发送会话记录更新事件
-        println!("发送会话记录事件: {}", POMODORO_SESSION_RECORDED_EVENT);
-        let _ = app.emit(POMODORO_SESSION_RECORDED_EVENT, ());
+    let _ = app.emit(
+        "todo-data-updated",
+        serde_json::json!({
+            "action": "updated",
+            "todoId": todo_id,
+            "source": "pomodoro"
+        }),
+    );
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
     }
     Ok(())
 }
 
+fn emit_status(app: &AppHandle<Wry>, notifier: &NotificationManager, status: &PomodoroStatus) {
+    let _ = app.emit(POMODORO_STATUS_EVENT, status);
+    notifier.send_websocket_event(
+        WS_EVENT_STATUS.to_string(),
+        serde_json::to_value(status).unwrap_or_default(),
+    );
+}
+
+/// 从 `start` 到 `end` 扣除暂停后的秒数（`paused_at` 为仍在进行的暂停）
+fn active_seconds(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    paused_seconds: i64,
+    paused_at: Option<DateTime<Utc>>,
+) -> i64 {
+    let ongoing = paused_at.map_or(0, |paused_at| (end - paused_at).num_seconds().max(0));
+    ((end - start).num_seconds() - paused_seconds - ongoing).max(0)
+}
+
+/// 保存当前计时器状态；计时器停止时清除
+async fn save_timer_state(state_ptr: &Mutex<State>, app: &AppHandle<Wry>) {
+    let snapshot = snapshot_of(&*state_ptr.lock().await);
+    let Some(state) = app.try_state::<crate::core::AppState>() else {
+        return;
+    };
+    let result = match snapshot {
+        Some(snapshot) => pomo_service::save_timer_snapshot(state.db(), &snapshot).await,
+        None => pomo_service::clear_timer_snapshot(state.db()).await,
+    };
+    if let Err(e) = result {
+        eprintln!("Pomodoro save timer state error: {}", e);
+    }
+}
+
+fn snapshot_of(s: &State) -> Option<PomodoroTimerSnapshot> {
+    let now = Utc::now();
+    if !s.running || s.mode == PomodoroMode::Idle {
+        return None;
+    }
+    Some(PomodoroTimerSnapshot {
+        mode: s.mode,
+        round: s.round,
+        paused: s.paused,
+        remaining_seconds: s.remaining_at(now),
+        phase_started_at: s
+            .phase_started_at
+            .or(s.awaiting_confirmation.then_some(now))?,
+        paused_seconds: s.paused_seconds,
+        paused_at: s.paused_at,
+        todo_id: s.todo_id,
+        segment_started_at: s.segment_started_at,
+        split_seconds: s.split_seconds,
+        preset_id: s.preset_id,
+        flow: s.flow,
+        planned_seconds: s.planned_seconds,
+        adjusted_seconds: s.adjusted_seconds,
+        awaiting_confirmation: s.awaiting_confirmation,
+        saved_at: now,
+    })
+}
+
+/// 阶段开始时的倒计时秒数（心流专注为 0）
+fn first_phase_seconds(cfg: &PomodoroConfig, mode: PomodoroMode) -> u32 {
+    match mode {
+        PomodoroMode::Focus => cfg.focus_minutes * 60,
+        PomodoroMode::ShortBreak => cfg.short_break_minutes * 60,
+        PomodoroMode::LongBreak => cfg.long_break_minutes * 60,
+        PomodoroMode::Flow | PomodoroMode::Idle => 0,
+    }
+}
+
+fn session_kind(mode: PomodoroMode) -> PomodoroSessionKind {
+    match mode {
+        PomodoroMode::Focus | PomodoroMode::Flow => PomodoroSessionKind::Focus,
+        _ => PomodoroSessionKind::Rest,
+    }
+}
+
 fn format_mode(mode: PomodoroMode) -> &'static str {
     match mode {
         PomodoroMode::Focus => "focus",
         PomodoroMode::ShortBreak => "short_break",
         PomodoroMode::LongBreak => "long_break",
         PomodoroMode::Idle => "idle",
+        PomodoroMode::Flow => "flow",
     }
 }
 
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
-async fn update_tray_tooltip(app: &AppHandle<Wry>, mode: PomodoroMode, remaining_seconds: u32) {
+/// 心流专注显示已计时的时间，其他阶段显示剩余时间
+async fn update_tray_tooltip(app: &AppHandle<Wry>, mode: PomodoroMode, seconds: u32) {
     let text = if matches!(mode, PomodoroMode::Idle) {
         "番茄钟 - 空闲".to_string()
     } else {
-        let m = remaining_seconds / 60;
-        let s = remaining_seconds % 60;
+        let m = seconds / 60;
+        let s = seconds % 60;
         let label = match mode {
             PomodoroMode::Focus => "专注",
             PomodoroMode::ShortBreak => "短休",
             PomodoroMode::LongBreak => "长休",
             PomodoroMode::Idle => "空闲",
+            PomodoroMode::Flow => "心流",
         };
         format!("番茄钟 - {} {:02}:{:02}", label, m, s)
     };
@@ -492,4 +1318,195 @@ async fn update_tray_tooltip(app: &AppHandle<Wry>, mode: PomodoroMode, remaining
 }
 
 #[cfg(any(target_os = "android", target_os = "ios"))]
-async fn update_tray_tooltip(_app: &AppHandle<Wry>, _mode: PomodoroMode, _remaining_seconds: u32) {}
+async fn update_tray_tooltip(_app: &AppHandle<Wry>, _mode: PomodoroMode, _seconds: u32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 5, 9, 0, 0).unwrap()
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        t0() + chrono::Duration::seconds(seconds)
+    }
+
+    /// 在 t0 开始一个 25 分钟的专注
+    fn focusing() -> State {
+        let mut s = State::idle();
+        s.begin_phase(PomodoroMode::Focus, 1500, t0());
+        s
+    }
+
+    /// t0 开始的专注，其中暂停过 30 秒，在 200 秒时保存
+    fn snapshot(
+        paused: bool,
+        paused_at: Option<DateTime<Utc>>,
+        remaining_seconds: u32,
+    ) -> PomodoroTimerSnapshot {
+        PomodoroTimerSnapshot {
+            mode: PomodoroMode::Focus,
+            round: 0,
+            paused,
+            remaining_seconds,
+            phase_started_at: t0(),
+            paused_seconds: 30,
+            paused_at,
+            todo_id: Some(7),
+            segment_started_at: None,
+            split_seconds: 0,
+            preset_id: None,
+            flow: false,
+            planned_seconds: Some(1500),
+            adjusted_seconds: 0,
+            awaiting_confirmation: false,
+            saved_at: at(200),
+        }
+    }
+
+    #[test]
+    fn pause_freezes_remaining_and_elapsed() {
+        let mut s = focusing();
+        s.pause_at(at(100));
+
+        assert_eq!(s.remaining_at(at(400)), 1400);
+        assert_eq!(s.status(at(400)).elapsed_seconds, 100);
+
+        s.resume_at(at(400));
+        assert_eq!(s.paused_seconds, 300);
+        assert_eq!(s.remaining_at(at(500)), 1300);
+        assert_eq!(s.status(at(500)).elapsed_seconds, 200);
+        assert_eq!(s.phase_ends_at, Some(at(1800)));
+    }
+
+    #[test]
+    fn short_tick_gap_is_not_a_suspend() {
+        let mut s = focusing();
+        assert!(!s.detect_suspend(
+            at(60),
+            at(60 + SUSPEND_GAP_SECONDS),
+            PomodoroSuspendPolicy::Pause
+        ));
+        assert!(!s.paused);
+    }
+
+    #[test]
+    fn suspend_with_pause_policy_pauses_from_last_tick() {
+        let mut s = focusing();
+        assert!(s.detect_suspend(at(60), at(660), PomodoroSuspendPolicy::Pause));
+
+        assert!(s.paused);
+        assert_eq!(s.paused_at, Some(at(60)));
+        assert_eq!(s.remaining_at(at(660)), 1440);
+        assert_eq!(s.status(at(660)).elapsed_seconds, 60);
+
+        // 唤醒后手动继续，休眠时间计为暂停
+        s.resume_at(at(700));
+        assert_eq!(s.remaining_at(at(710)), 1430);
+        assert_eq!(s.status(at(710)).elapsed_seconds, 70);
+    }
+
+    #[test]
+    fn suspend_with_finish_policy_keeps_wall_clock() {
+        let mut s = focusing();
+        assert!(!s.detect_suspend(at(60), at(660), PomodoroSuspendPolicy::Finish));
+
+        assert!(s.counting());
+        assert_eq!(s.remaining_at(at(660)), 840);
+        assert_eq!(s.status(at(660)).elapsed_seconds, 660);
+
+        // 休眠期间已经结束：剩余为 0，按原定结束时间计时长
+        assert_eq!(s.remaining_at(at(2000)), 0);
+        assert_eq!(s.phase_ends_at, Some(at(1500)));
+        assert_eq!(s.active_seconds(at(1500)), 1500);
+    }
+
+    #[test]
+    fn paused_phase_stays_paused_after_restart() {
+        let mut s = State::idle();
+        s.restore(&snapshot(true, Some(at(100)), 1430), at(1000));
+
+        assert!(s.running && s.paused);
+        assert_eq!(s.todo_id, Some(7));
+        assert_eq!(s.remaining_at(at(1000)), 1430);
+        // 100 秒计时中有 30 秒暂停，之后一直暂停到重启
+        assert_eq!(s.status(at(1000)).elapsed_seconds, 70);
+
+        s.resume_at(at(1000));
+        assert_eq!(s.remaining_at(at(1010)), 1420);
+        assert_eq!(s.status(at(1010)).elapsed_seconds, 80);
+    }
+
+    #[test]
+    fn restart_gap_counts_as_pause() {
+        let mut s = State::idle();
+        s.restore(&snapshot(false, None, 1330), at(1000));
+
+        // 从最后一次保存到重启之间不计时
+        assert!(s.counting());
+        assert_eq!(s.paused_seconds, 30 + 800);
+        assert_eq!(s.remaining_at(at(1000)), 1330);
+        assert_eq!(s.status(at(1000)).elapsed_seconds, 170);
+        assert_eq!(s.remaining_at(at(1100)), 1230);
+        assert_eq!(s.status(at(1100)).elapsed_seconds, 270);
+    }
+
+    #[test]
+    fn switching_todo_splits_the_focus() {
+        let mut s = focusing();
+        s.todo_id = Some(1);
+        s.pause_at(at(300));
+        s.resume_at(at(400));
+
+        let split = s.switch_todo(Some(2), at(600)).unwrap()(9);
+        assert_eq!(split.session_id, 9);
+        assert_eq!(split.related_todo_id, Some(1));
+        assert_eq!(split.status, PomodoroSessionStatus::Stopped);
+        assert_eq!((split.start_at, split.end_at), (t0(), at(600)));
+        assert_eq!(split.elapsed_seconds, 500);
+        assert_eq!(s.todo_id, Some(2));
+
+        // 阶段本身不受影响，结束时的记录只包含切换之后的部分
+        assert_eq!(s.remaining_at(at(1600)), 0);
+        assert_eq!(s.status(at(1000)).elapsed_seconds, 900);
+        let record = s
+            .record_until(PomodoroSessionStatus::Completed, at(1600))
+            .unwrap()(9);
+        assert_eq!(record.related_todo_id, Some(2));
+        assert_eq!(record.start_at, at(600));
+        assert_eq!(record.elapsed_seconds, 1000);
+        assert_eq!(record.planned_seconds, Some(1500));
+    }
+
+    #[test]
+    fn switching_todo_twice_only_records_each_segment_once() {
+        let mut s = focusing();
+        assert!(s.switch_todo(Some(1), at(100)).is_some());
+        assert!(s.switch_todo(Some(1), at(200)).is_none());
+        let second = s.switch_todo(None, at(700)).unwrap()(0);
+        assert_eq!(second.related_todo_id, Some(1));
+        assert_eq!((second.start_at, second.elapsed_seconds), (at(100), 600));
+
+        let record = s
+            .record_until(PomodoroSessionStatus::Stopped, at(800))
+            .unwrap()(0);
+        assert_eq!(
+            (record.related_todo_id, record.elapsed_seconds),
+            (None, 100)
+        );
+    }
+
+    #[test]
+    fn switching_todo_outside_focus_does_not_split() {
+        let mut s = State::idle();
+        s.begin_phase(PomodoroMode::ShortBreak, 300, t0());
+        assert!(s.switch_todo(Some(1), at(100)).is_none());
+        assert_eq!(s.todo_id, Some(1));
+
+        s.begin_phase(PomodoroMode::Focus, 1500, at(300));
+        s.await_confirmation();
+        assert!(s.switch_todo(Some(2), at(400)).is_none());
+    }
+}
@@ -1,10 +1,15 @@
-use anyhow::Result;
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
 
 use crate::features::settings::core::service::SettingService;
 
-use super::models::{PomodoroConfig, PomodoroSessionKind, PomodoroSessionStatus};
+use super::models::{
+    PomodoroConfig, PomodoroSessionKind, PomodoroSessionStatus, PomodoroSuspendPolicy,
+    PomodoroTimerSnapshot,
+};
 use crate::features::pomodoro::data::entities::{
     pomodoro_records as record_entity, pomodoro_sessions as session_entity,
 };
@@ -19,6 +24,16 @@ const KEY_FOCUS: &str = "pomodoro.focus_minutes";
 const KEY_SHORT: &str = "pomodoro.short_break_minutes";
 const KEY_LONG: &str = "pomodoro.long_break_minutes";
 const KEY_INTERVAL: &str = "pomodoro.long_break_interval";
+const KEY_RESUME_ON_RESTART: &str = "pomodoro.resume_on_restart";
+const KEY_SUSPEND_POLICY: &str = "pomodoro.suspend_policy";
+const KEY_AUTO_COMPLETE_TODO: &str = "pomodoro.auto_complete_todo";
+const KEY_FLOW_BREAK_RATIO: &str = "pomodoro.flow_break_ratio";
+const KEY_FLOW_MIN_BREAK: &str = "pomodoro.flow_min_break_minutes";
+const KEY_FLOW_MAX_BREAK: &str = "pomodoro.flow_max_break_minutes";
+const KEY_AUTO_START_BREAKS: &str = "pomodoro.auto_start_breaks";
+const KEY_AUTO_START_FOCUS: &str = "pomodoro.auto_start_focus";
+const KEY_PAUSE_TIMEOUT: &str = "pomodoro.pause_timeout_minutes";
+const KEY_TIMER_STATE: &str = "pomodoro.timer_state";
 
 pub async fn get_config(db: &DatabaseConnection) -> Result<PomodoroConfig> {
     let mut cfg = PomodoroConfig::default();
@@ -40,14 +55,130 @@ pub async fn get_config(db: &DatabaseConnection) -> Result<PomodoroConfig> {
             .await?;
     cfg.long_break_interval = interval.parse::<u32>().unwrap_or(cfg.long_break_interval);
 
+    cfg.resume_on_restart =
+        SettingService::get_bool(db, KEY_RESUME_ON_RESTART, cfg.resume_on_restart).await?;
+
+    if let Some(policy) = SettingService::get(db, KEY_SUSPEND_POLICY).await? {
+        cfg.suspend_policy = PomodoroSuspendPolicy::parse(&policy).unwrap_or(cfg.suspend_policy);
+    }
+
+    cfg.auto_complete_todo =
+        SettingService::get_bool(db, KEY_AUTO_COMPLETE_TODO, cfg.auto_complete_todo).await?;
+
+    let ratio =
+        SettingService::get_or_default(db, KEY_FLOW_BREAK_RATIO, &cfg.flow_break_ratio.to_string())
+            .await?;
+    cfg.flow_break_ratio = ratio.parse::<u32>().unwrap_or(cfg.flow_break_ratio);
+
+    let min_break = SettingService::get_or_default(
+        db,
+        KEY_FLOW_MIN_BREAK,
+        &cfg.flow_min_break_minutes.to_string(),
+    )
+    .await?;
+    cfg.flow_min_break_minutes = min_break
+        .parse::<u32>()
+        .unwrap_or(cfg.flow_min_break_minutes);
+
+    let max_break = SettingService::get_or_default(
+        db,
+        KEY_FLOW_MAX_BREAK,
+        &cfg.flow_max_break_minutes.to_string(),
+    )
+    .await?;
+    cfg.flow_max_break_minutes = max_break
+        .parse::<u32>()
+        .unwrap_or(cfg.flow_max_break_minutes);
+
+    cfg.auto_start_breaks =
+        SettingService::get_bool(db, KEY_AUTO_START_BREAKS, cfg.auto_start_breaks).await?;
+    cfg.auto_start_focus =
+        SettingService::get_bool(db, KEY_AUTO_START_FOCUS, cfg.auto_start_focus).await?;
+
+    let pause_timeout = SettingService::get_or_default(
+        db,
+        KEY_PAUSE_TIMEOUT,
+        &cfg.pause_timeout_minutes.to_string(),
+    )
+    .await?;
+    cfg.pause_timeout_minutes = pause_timeout
+        .parse::<u32>()
+        .unwrap_or(cfg.pause_timeout_minutes);
+
     Ok(cfg)
 }
 
 pub async fn set_config(db: &DatabaseConnection, cfg: PomodoroConfig) -> Result<()> {
+    if cfg.flow_break_ratio == 0 {
+        bail!("flow break ratio must be positive");
+    }
+    if cfg.flow_min_break_minutes > cfg.flow_max_break_minutes {
+        bail!("flow minimum break cannot exceed the maximum break");
+    }
+    if cfg.pause_timeout_minutes > 24 * 60 {
+        bail!("pause timeout cannot exceed 24 hours");
+    }
+
     SettingService::set(db, KEY_FOCUS, &cfg.focus_minutes.to_string()).await?;
     SettingService::set(db, KEY_SHORT, &cfg.short_break_minutes.to_string()).await?;
     SettingService::set(db, KEY_LONG, &cfg.long_break_minutes.to_string()).await?;
     SettingService::set(db, KEY_INTERVAL, &cfg.long_break_interval.to_string()).await?;
+    SettingService::set_bool(db, KEY_RESUME_ON_RESTART, cfg.resume_on_restart).await?;
+    SettingService::set(db, KEY_SUSPEND_POLICY, cfg.suspend_policy.as_str()).await?;
+    SettingService::set_bool(db, KEY_AUTO_COMPLETE_TODO, cfg.auto_complete_todo).await?;
+    SettingService::set(db, KEY_FLOW_BREAK_RATIO, &cfg.flow_break_ratio.to_string()).await?;
+    SettingService::set(
+        db,
+        KEY_FLOW_MIN_BREAK,
+        &cfg.flow_min_break_minutes.to_string(),
+    )
+    .await?;
+    SettingService::set(
+        db,
+        KEY_FLOW_MAX_BREAK,
+        &cfg.flow_max_break_minutes.to_string(),
+    )
+    .await?;
+    SettingService::set_bool(db, KEY_AUTO_START_BREAKS, cfg.auto_start_breaks).await?;
+    SettingService::set_bool(db, KEY_AUTO_START_FOCUS, cfg.auto_start_focus).await?;
+    SettingService::set(
+        db,
+        KEY_PAUSE_TIMEOUT,
+        &cfg.pause_timeout_minutes.to_string(),
+    )
+    .await?;
+    Ok(())
+}
+
+// ==================== Timer State ====================
+
+/// 保存进行中的计时器状态
+pub async fn save_timer_snapshot(
+    db: &DatabaseConnection,
+    snapshot: &PomodoroTimerSnapshot,
+) -> Result<()> {
+    let payload = serde_json::to_string(snapshot).context("failed to serialize timer state")?;
+    SettingService::set(db, KEY_TIMER_STATE, &payload).await?;
+    Ok(())
+}
+
+/// 读取上次保存的计时器状态（无法解析时视为没有）
+pub async fn load_timer_snapshot(db: &DatabaseConnection) -> Result<Option<PomodoroTimerSnapshot>> {
+    let Some(raw) = SettingService::get(db, KEY_TIMER_STATE).await? else {
+        return Ok(None);
+    };
+    match serde_json::from_str(&raw) {
+        Ok(snapshot) => Ok(Some(snapshot)),
+        Err(e) => {
+            eprintln!("Failed to parse pomodoro timer state: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// 计时器停止后清除保存的状态
+pub async fn clear_timer_snapshot(db: &DatabaseConnection) -> Result<()> {
+    SettingService::delete(db, KEY_TIMER_STATE).await?;
     Ok(())
 }
 
@@ -60,18 +191,25 @@ pub struct PomodoroStats {
     pub session_count: i64,
 }
 
-/// 获取指定时间范围内的统计数据
+/// 获取指定时间范围内的统计数据（`preset_id` 不为空时只统计使用该预设的记录）
 pub async fn get_stats_range(
     db: &DatabaseConnection,
     from: DateTime<Utc>,
     to: DateTime<Utc>,
+    preset_id: Option<i32>,
 ) -> Result<PomodoroStats> {
-    // 查询所有专注时间的秒数
-    let rows: Vec<i32> = record_entity::Entity::find()
+    let mut query = record_entity::Entity::find()
         .filter(record_entity::Column::Kind.eq("focus"))
         .filter(record_entity::Column::Status.eq("completed"))
         .filter(record_entity::Column::StartAt.gte(from))
-        .filter(record_entity::Column::EndAt.lte(to))
+        .filter(record_entity::Column::EndAt.lte(to));
+    if let Some(preset_id) = preset_id {
+        query = query.filter(record_entity::Column::PresetId.eq(preset_id));
+    }
+
+    // 查询所有专注时间的秒数
+    let rows: Vec<i32> = query
+        .clone()
         .select_only()
         .column(record_entity::Column::ElapsedSeconds)
         .into_tuple()
@@ -80,14 +218,7 @@ pub async fn get_stats_range(
     let total_focus: i64 = rows.into_iter().map(|v| v as i64).sum();
 
     // 计数专注记录
-    let session_count: i64 = record_entity::Entity::find()
-        .filter(record_entity::Column::Kind.eq("focus"))
-        .filter(record_entity::Column::Status.eq("completed"))
-        .filter(record_entity::Column::StartAt.gte(from))
-        .filter(record_entity::Column::EndAt.lte(to))
-        .count(db)
-        .await
-        .map(|c| c as i64)?;
+    let session_count: i64 = query.count(db).await.map(|c| c as i64)?;
 
     Ok(PomodoroStats {
         total_focus_seconds: total_focus,
@@ -95,6 +226,70 @@ pub async fn get_stats_range(
     })
 }
 
+/// 单个待办的专注统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoFocusStats {
+    pub todo_id: i32,
+    /// 专注总秒数（包括中途停止或跳过的专注）
+    pub focus_seconds: i64,
+    /// 完成的番茄数
+    pub pomodoro_count: i64,
+    pub last_focused_at: Option<DateTime<Utc>>,
+}
+
+/// 按待办汇总专注记录
+///
+/// `todo_ids` 为空时返回所有关联过专注的待办，否则只返回指定的待办（没有记录的计为 0）
+pub async fn get_todo_focus_stats(
+    db: &DatabaseConnection,
+    todo_ids: Option<Vec<i32>>,
+) -> Result<Vec<TodoFocusStats>> {
+    let mut query = record_entity::Entity::find()
+        .filter(record_entity::Column::Kind.eq("focus"))
+        .filter(record_entity::Column::RelatedTodoId.is_not_null());
+    if let Some(ids) = &todo_ids {
+        query = query.filter(record_entity::Column::RelatedTodoId.is_in(ids.clone()));
+    }
+    let records = query
+        .all(db)
+        .await
+        .context("failed to load focus records")?;
+
+    let mut stats: BTreeMap<i32, TodoFocusStats> = BTreeMap::new();
+    for todo_id in todo_ids.into_iter().flatten() {
+        stats.entry(todo_id).or_insert_with(|| TodoFocusStats {
+            todo_id,
+            focus_seconds: 0,
+            pomodoro_count: 0,
+            last_focused_at: None,
+        });
+    }
+    for record in records {
+        let Some(todo_id) = record.related_todo_id else {
+            continue;
+        };
+        let entry = stats.entry(todo_id).or_insert_with(|| TodoFocusStats {
+            todo_id,
+            focus_seconds: 0,
+            pomodoro_count: 0,
+            last_focused_at: None,
+        });
+        entry.focus_seconds += record.elapsed_seconds as i64;
+        if record.status == "completed" {
+            entry.pomodoro_count += 1;
+        }
+        if entry
+            .last_focused_at
+            .is_none_or(|last| last < record.end_at)
+        {
+            entry.last_focused_at = Some(record.end_at);
+        }
+    }
+
+    Ok(stats.into_values().collect())
+}
+
 /// 列出最近的 records（按时间倒序）
 pub async fn list_recent_records(
     db: &DatabaseConnection,
@@ -246,18 +441,44 @@ pub async fn list_session_records(
     Ok(records)
 }
 
+/// 新建 Record 的内容
+#[derive(Debug, Clone)]
+pub struct NewPomodoroRecord {
+    pub session_id: i32,
+    pub kind: PomodoroSessionKind,
+    pub status: PomodoroSessionStatus,
+    pub round: u32,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    /// 实际计时的秒数（不含暂停）
+    pub elapsed_seconds: i64,
+    pub related_todo_id: Option<i32>,
+    pub preset_id: Option<i32>,
+    /// 阶段开始时的计划秒数（心流专注为空）
+    pub planned_seconds: Option<u32>,
+    /// 手动调整的累计秒数
+    pub adjusted_seconds: i64,
+}
+
 /// 创建 Record 并关联到 Session
 pub async fn create_record_with_session(
     db: &DatabaseConnection,
-    session_id: i32,
-    kind: PomodoroSessionKind,
-    status: PomodoroSessionStatus,
-    round: u32,
-    start_at: DateTime<Utc>,
-    end_at: DateTime<Utc>,
-    related_todo_id: Option<i32>,
+    record: NewPomodoroRecord,
 ) -> Result<record_entity::Model> {
-    let elapsed = (end_at - start_at).num_seconds().max(0) as i32;
+    let NewPomodoroRecord {
+        session_id,
+        kind,
+        status,
+        round,
+        start_at,
+        end_at,
+        elapsed_seconds,
+        related_todo_id,
+        preset_id,
+        planned_seconds,
+        adjusted_seconds,
+    } = record;
+    let elapsed = elapsed_seconds.clamp(0, i32::MAX as i64) as i32;
     let now = Utc::now();
 
     let active = record_entity::ActiveModel {
@@ -277,6 +498,9 @@ pub async fn create_record_with_session(
         end_at: Set(end_at),
         elapsed_seconds: Set(elapsed),
         related_todo_id: Set(related_todo_id),
+        preset_id: Set(preset_id),
+        planned_seconds: Set(planned_seconds.map(|seconds| seconds.min(i32::MAX as u32) as i32)),
+        adjusted_seconds: Set(adjusted_seconds.clamp(i32::MIN as i64, i32::MAX as i64) as i32),
         created_at: Set(now),
         updated_at: Set(now),
     };
@@ -0,0 +1,82 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+/// 为 pomodoro_records 添加计划时长 planned_seconds 与调整时长 adjusted_seconds
+#[derive(Debug, Clone, Copy)]
+pub struct PomodoroAdjustmentMigration;
+
+impl MigrationName for PomodoroAdjustmentMigration {
+    fn name(&self) -> &str {
+        "m20260115_000001_add_pomodoro_record_adjustment"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for PomodoroAdjustmentMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+
+        println!("Adding planned/adjusted time to pomodoro records...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        for (column, definition) in [
+            ("planned_seconds", "INTEGER"),
+            ("adjusted_seconds", "INTEGER NOT NULL DEFAULT 0"),
+        ] {
+            // 检查列是否已存在
+            let check_column = db
+                .query_one(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    format!(
+                        "SELECT COUNT(*) as count FROM pragma_table_info('pomodoro_records') WHERE name='{column}';"
+                    ),
+                ))
+                .await;
+
+            let column_exists = if let Ok(Some(row)) = check_column {
+                let count: i32 = row.try_get("", "count").unwrap_or(0);
+                count > 0
+            } else {
+                false
+            };
+
+            if column_exists {
+                println!("  -> Column {column} already exists, skipping...");
+                continue;
+            }
+
+            println!("  -> Adding {column} column...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                format!("ALTER TABLE pomodoro_records ADD COLUMN {column} {definition};"),
+            ))
+            .await
+            .with_context(|| format!("failed to add {column} column"))
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        }
+
+        println!("Pomodoro adjustment migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            ALTER TABLE pomodoro_records DROP COLUMN adjusted_seconds;
+            ALTER TABLE pomodoro_records DROP COLUMN planned_seconds;
+            "#
+            .to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,2 +1,4 @@
+pub mod pomodoro_goal_days;
+pub mod pomodoro_presets;
 pub mod pomodoro_records;
 pub mod pomodoro_sessions;
@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 每日专注目标的完成情况（每个本地日期一行，写入专注记录时更新）
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "pomodoro_goal_days")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// 本地日期
+    pub date: Date,
+    /// focus_minutes | pomodoros
+    pub metric: String,
+    /// 当天的目标（分钟数或番茄数）
+    pub target: i32,
+    /// 当天的专注秒数（包括中途停止或跳过的专注）
+    pub focus_seconds: i32,
+    /// 当天完成的专注数
+    pub pomodoro_count: i32,
+    /// 已通知的进度里程碑（0 | 50 | 100）
+    pub milestone: i32,
+    /// 达成目标的时间
+    pub reached_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
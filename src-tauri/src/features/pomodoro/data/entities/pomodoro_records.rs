@@ -19,6 +19,15 @@ pub struct Model {
     /// seconds
     pub elapsed_seconds: i32,
     pub related_todo_id: Option<i32>,
+    /// 使用的预设 ID（使用默认配置时为空）
+    #[serde(default)]
+    pub preset_id: Option<i32>,
+    /// 阶段开始时的计划秒数（心流专注为空）
+    #[serde(default)]
+    pub planned_seconds: Option<i32>,
+    /// 计时中手动延长/缩短的累计秒数（缩短为负数）
+    #[serde(default)]
+    pub adjusted_seconds: i32,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
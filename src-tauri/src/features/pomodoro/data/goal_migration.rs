@@ -0,0 +1,55 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::pomodoro_goal_days;
+
+/// 创建每日专注目标的历史表
+#[derive(Debug, Clone, Copy)]
+pub struct PomodoroGoalMigration;
+
+impl MigrationName for PomodoroGoalMigration {
+    fn name(&self) -> &str {
+        "m20260120_000001_create_pomodoro_goal_days"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for PomodoroGoalMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Adding pomodoro goal history...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        let mut create_goal_days = schema.create_table_from_entity(pomodoro_goal_days::Entity);
+        create_goal_days.if_not_exists();
+        db.execute(backend.build(&create_goal_days))
+            .await
+            .context("failed to create pomodoro_goal_days table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_pomodoro_goal_days_date ON pomodoro_goal_days(date);"
+                .to_string(),
+        ))
+        .await
+        .context("failed to create pomodoro goal index")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Pomodoro goal migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(pomodoro_goal_days::Entity).to_owned())
+            .await
+    }
+}
@@ -1,4 +1,7 @@
+pub mod adjustment_migration;
 pub mod entities; // 新的实体结构
 pub mod entity; // 保留旧的 entity.rs 用于兼容
+pub mod goal_migration;
 pub mod migration;
+pub mod preset_migration;
 pub mod restructure_migration;
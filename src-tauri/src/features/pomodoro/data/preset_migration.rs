@@ -0,0 +1,100 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::pomodoro_presets;
+
+/// 创建番茄钟预设表，并为 pomodoro_records 添加 preset_id 字段
+#[derive(Debug, Clone, Copy)]
+pub struct PomodoroPresetMigration;
+
+impl MigrationName for PomodoroPresetMigration {
+    fn name(&self) -> &str {
+        "m20260110_000001_create_pomodoro_presets"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for PomodoroPresetMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Adding pomodoro presets...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        let mut create_presets = schema.create_table_from_entity(pomodoro_presets::Entity);
+        create_presets.if_not_exists();
+        db.execute(backend.build(&create_presets))
+            .await
+            .context("failed to create pomodoro_presets table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        // 检查 preset_id 列是否已存在
+        let check_column = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM pragma_table_info('pomodoro_records') WHERE name='preset_id';"
+                    .to_string(),
+            ))
+            .await;
+
+        let column_exists = if let Ok(Some(row)) = check_column {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        if !column_exists {
+            // 不设外键：删除预设后记录仍保留原来的 preset_id，便于统计
+            println!("  -> Adding preset_id column...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "ALTER TABLE pomodoro_records ADD COLUMN preset_id INTEGER;".to_string(),
+            ))
+            .await
+            .context("failed to add preset_id column")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        } else {
+            println!("  -> Column already exists, skipping...");
+        }
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_pomodoro_presets_name ON pomodoro_presets(name COLLATE NOCASE);
+            CREATE INDEX IF NOT EXISTS idx_pomodoro_records_preset ON pomodoro_records(preset_id);
+            "#
+            .to_string(),
+        ))
+        .await
+        .context("failed to create pomodoro preset indexes")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Pomodoro preset migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            DROP INDEX IF EXISTS idx_pomodoro_records_preset;
+            ALTER TABLE pomodoro_records DROP COLUMN preset_id;
+            "#
+            .to_string(),
+        ))
+        .await?;
+
+        manager
+            .drop_table(Table::drop().table(pomodoro_presets::Entity).to_owned())
+            .await
+    }
+}
@@ -45,10 +45,30 @@ impl Feature for PomodoroFeature {
             "pomodoro_pause",
             "pomodoro_resume",
             "pomodoro_skip",
+            "pomodoro_finish",
             "pomodoro_stop",
             "pomodoro_status",
             "pomodoro_get_config",
             "pomodoro_set_config",
+            "pomodoro_set_todo",
+            "pomodoro_confirm",
+            "pomodoro_adjust",
+            "pomodoro_set_remaining",
+            "pomodoro_todo_stats",
+            "pomodoro_stats_series",
+            "pomodoro_stats_streaks",
+            "pomodoro_stats_heatmap",
+            "pomodoro_stats_breakdown",
+            "pomodoro_get_goal",
+            "pomodoro_set_goal",
+            "pomodoro_goal_progress",
+            "pomodoro_goal_history",
+            "pomodoro_list_presets",
+            "pomodoro_create_preset",
+            "pomodoro_update_preset",
+            "pomodoro_delete_preset",
+            "pomodoro_get_active_preset",
+            "pomodoro_set_active_preset",
         ]
     }
 
@@ -59,9 +79,14 @@ impl Feature for PomodoroFeature {
             _app_state.notification().clone(),
         ));
         self.manager
-            .set(manager)
+            .set(manager.clone())
             .map_err(|_| anyhow::anyhow!("PomodoroManager already initialized"))?;
 
+        // 恢复上次关闭时未结束的阶段（失败不影响启动）
+        if let Err(e) = manager.recover(_app_state.db()).await {
+            eprintln!("[PomodoroFeature] Failed to recover timer state: {}", e);
+        }
+
         println!("[PomodoroFeature] Initialized");
         Ok(())
     }
@@ -83,6 +108,24 @@ impl Feature for PomodoroFeature {
             let migration = super::data::restructure_migration::PomodoroRestructureMigration;
             Box::pin(async move { migration.up(manager).await })
         });
+
+        // 创建预设表
+        registry.register_migration("pomodoro_preset_migration", |manager| {
+            let migration = super::data::preset_migration::PomodoroPresetMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 记录计划时长与调整时长
+        registry.register_migration("pomodoro_adjustment_migration", |manager| {
+            let migration = super::data::adjustment_migration::PomodoroAdjustmentMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 创建每日目标历史表
+        registry.register_migration("pomodoro_goal_migration", |manager| {
+            let migration = super::data::goal_migration::PomodoroGoalMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
     }
 }
 
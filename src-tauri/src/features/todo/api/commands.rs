@@ -1,8 +1,26 @@
 use serde::Deserialize;
 use tauri::State;
 
+use super::notifications;
 use crate::core::AppState;
-use crate::features::todo::core::{models::Todo, service};
+use crate::features::todo::core::{
+    bulk::{self, BulkOperation, BulkResult},
+    dependencies::{self, TodoDependencies},
+    journal::{self, HistoryChange, HistoryStatus},
+    lists::{self, TodoList, TodoListInput},
+    models::Todo,
+    ordering::{self, ReorderPosition},
+    query::{self, TodoPage, TodoQuery},
+    quick_add::{self, QuickAddResult},
+    recurrence::{self, RecurrenceMode},
+    reminders::{self, ReminderInput, ReminderSettings, TodoReminder},
+    search::{self, TodoSearchHit},
+    service,
+    subtasks::{self, SubtaskSettings},
+    tags::{self, TagChange, TagSummary},
+    trash,
+    tree::{self, TodoTreeNode},
+};
 
 #[derive(Debug, Default, Deserialize)]
 pub struct CreateTodoPayload {
@@ -40,6 +58,30 @@ pub async fn list_todos(state: State<'_, AppState>) -> Result<Vec<Todo>, String>
         .map_err(|err| err.to_string())
 }
 
+/// 按条件查询 todo（过滤、排序、游标分页）
+#[tauri::command]
+pub async fn query_todos(
+    state: State<'_, AppState>,
+    query: Option<TodoQuery>,
+) -> Result<TodoPage, String> {
+    let query = query.unwrap_or_default();
+    query::query_todos(state.db(), &query)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 全文搜索 todo
+#[tauri::command]
+pub async fn search_todos(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<u64>,
+) -> Result<Vec<TodoSearchHit>, String> {
+    search::search_todos(state.db(), &query, limit)
+        .await
+        .map_err(|err| err.to_string())
+}
+
 /// 创建新 todo
 #[tauri::command]
 pub async fn create_todo(
@@ -63,6 +105,30 @@ pub async fn create_todo(
     Ok(result)
 }
 
+/// 用一句话快速创建 todo（解析日期、标签、优先级、提醒和重复规则）
+///
+/// dry_run 为 true 时只返回解析结果，不保存
+#[tauri::command]
+pub async fn quick_add_todo(
+    state: State<'_, AppState>,
+    text: String,
+    dry_run: Option<bool>,
+) -> Result<QuickAddResult, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let result = quick_add::quick_add(state.db(), &text, dry_run)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 触发调度器重新规划提醒
+    if !dry_run {
+        if let Some(scheduler) = state.todo_scheduler() {
+            scheduler.reschedule().await;
+        }
+    }
+
+    Ok(result)
+}
+
 /// 更新 todo
 #[tauri::command]
 pub async fn update_todo(
@@ -136,6 +202,58 @@ pub async fn update_todo_details(
     Ok(result)
 }
 
+/// 稍后提醒：在 minutes 分钟之后，或在 until 指定的时间再次提醒（二选一）
+#[tauri::command]
+pub async fn snooze_todo(
+    state: State<'_, AppState>,
+    id: i32,
+    minutes: Option<i64>,
+    until: Option<String>,
+) -> Result<Todo, String> {
+    let result = service::snooze_todo(state.db(), id, minutes, until)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 触发调度器重新规划提醒
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 设置预估番茄数（为空表示清除）
+#[tauri::command]
+pub async fn set_todo_estimate(
+    state: State<'_, AppState>,
+    id: i32,
+    estimated_pomodoros: Option<i32>,
+) -> Result<Todo, String> {
+    service::set_estimated_pomodoros(state.db(), id, estimated_pomodoros)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 在一个事务中批量执行 todo 操作（全部成功或全部回滚）
+#[tauri::command]
+pub async fn bulk_todos(
+    state: State<'_, AppState>,
+    operations: Vec<BulkOperation>,
+) -> Result<BulkResult, String> {
+    let result = bulk::apply(state.db(), operations)
+        .await
+        .map_err(|err| format!("{err:#}"))?;
+
+    notifications::notify_todos_bulk_changed(state.notification(), &result);
+
+    // 整批只重新规划一次提醒
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
 /// 获取指定任务的所有子任务
 #[tauri::command]
 pub async fn get_subtasks(state: State<'_, AppState>, parent_id: i32) -> Result<Vec<Todo>, String> {
@@ -144,6 +262,17 @@ pub async fn get_subtasks(state: State<'_, AppState>, parent_id: i32) -> Result<
         .map_err(|err| err.to_string())
 }
 
+/// 获取待办树（root_id 为空时返回全部顶层待办及其后代）
+#[tauri::command]
+pub async fn get_todo_tree(
+    state: State<'_, AppState>,
+    root_id: Option<i32>,
+) -> Result<Vec<TodoTreeNode>, String> {
+    tree::load_tree(state.db(), root_id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
 /// 更新任务的父任务关系
 #[tauri::command]
 pub async fn update_todo_parent(
@@ -155,3 +284,420 @@ pub async fn update_todo_parent(
         .await
         .map_err(|err| err.to_string())
 }
+
+/// 移动 todo 到同级 todo 之前/之后，必要时一起调整父任务
+///
+/// before_id 与 after_id 都为空时移到 parent_id（为空表示顶层）的最后
+#[tauri::command]
+pub async fn reorder_todo(
+    state: State<'_, AppState>,
+    id: i32,
+    before_id: Option<i32>,
+    after_id: Option<i32>,
+    parent_id: Option<i32>,
+) -> Result<Todo, String> {
+    let position = match (before_id, after_id) {
+        (Some(before_id), None) => ReorderPosition::Before(before_id),
+        (None, Some(after_id)) => ReorderPosition::After(after_id),
+        (None, None) => ReorderPosition::End { parent_id },
+        (Some(_), Some(_)) => return Err("only one of before_id and after_id".to_string()),
+    };
+
+    ordering::reorder(state.db(), id, position)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 获取重复待办的推进方式
+#[tauri::command]
+pub async fn get_todo_recurrence_mode(
+    state: State<'_, AppState>,
+) -> Result<RecurrenceMode, String> {
+    recurrence::get_mode(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 设置重复待办的推进方式
+#[tauri::command]
+pub async fn set_todo_recurrence_mode(
+    state: State<'_, AppState>,
+    mode: RecurrenceMode,
+) -> Result<(), String> {
+    recurrence::set_mode(state.db(), mode)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 获取子任务的进度汇总与级联完成设置
+#[tauri::command]
+pub async fn get_todo_subtask_settings(
+    state: State<'_, AppState>,
+) -> Result<SubtaskSettings, String> {
+    subtasks::get_settings(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 设置子任务的进度汇总与级联完成规则
+#[tauri::command]
+pub async fn set_todo_subtask_settings(
+    state: State<'_, AppState>,
+    settings: SubtaskSettings,
+) -> Result<(), String> {
+    subtasks::set_settings(state.db(), settings)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 列出回收站中的 todo
+#[tauri::command]
+pub async fn list_trashed_todos(state: State<'_, AppState>) -> Result<Vec<Todo>, String> {
+    trash::list_trash(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 从回收站恢复 todo
+#[tauri::command]
+pub async fn restore_todo(state: State<'_, AppState>, id: i32) -> Result<Todo, String> {
+    let result = trash::restore_todo(state.db(), id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 触发调度器重新规划提醒
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 彻底删除回收站中的 todo
+#[tauri::command]
+pub async fn purge_todo(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    trash::purge_todo(state.db(), id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 清空回收站
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> Result<(), String> {
+    trash::empty_trash(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 获取回收站保留天数
+#[tauri::command]
+pub async fn get_trash_retention_days(state: State<'_, AppState>) -> Result<u32, String> {
+    trash::get_retention_days(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 设置回收站保留天数（0 表示不自动清理）
+#[tauri::command]
+pub async fn set_trash_retention_days(state: State<'_, AppState>, days: u32) -> Result<(), String> {
+    trash::set_retention_days(state.db(), days)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 立即按新的保留期清理一次
+    trash::purge_expired(state.db())
+        .await
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// 撤销最近一次 todo 修改（没有可撤销的操作时返回 null）
+#[tauri::command]
+pub async fn undo_todo_change(state: State<'_, AppState>) -> Result<Option<HistoryChange>, String> {
+    let result = journal::undo(state.db())
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 触发调度器重新规划提醒
+    if result.is_some() {
+        if let Some(scheduler) = state.todo_scheduler() {
+            scheduler.reschedule().await;
+        }
+    }
+
+    Ok(result)
+}
+
+/// 重做最近一次被撤销的 todo 修改（没有可重做的操作时返回 null）
+#[tauri::command]
+pub async fn redo_todo_change(state: State<'_, AppState>) -> Result<Option<HistoryChange>, String> {
+    let result = journal::redo(state.db())
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 触发调度器重新规划提醒
+    if result.is_some() {
+        if let Some(scheduler) = state.todo_scheduler() {
+            scheduler.reschedule().await;
+        }
+    }
+
+    Ok(result)
+}
+
+/// 查询当前是否可以撤销/重做
+#[tauri::command]
+pub async fn get_todo_history_status(state: State<'_, AppState>) -> Result<HistoryStatus, String> {
+    journal::status(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 列出所有标签及使用次数
+#[tauri::command]
+pub async fn list_tags(state: State<'_, AppState>) -> Result<Vec<TagSummary>, String> {
+    tags::list_tags(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 重命名标签（同步修改所有使用该标签的 todo）
+#[tauri::command]
+pub async fn rename_tag(
+    state: State<'_, AppState>,
+    id: i32,
+    name: String,
+) -> Result<TagChange, String> {
+    let result = tags::rename_tag(state.db(), id, &name)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    notifications::notify_tags_changed(state.notification(), "renamed", &result.todo_ids);
+
+    Ok(result)
+}
+
+/// 把若干标签合并到目标标签
+#[tauri::command]
+pub async fn merge_tags(
+    state: State<'_, AppState>,
+    source_ids: Vec<i32>,
+    target_id: i32,
+) -> Result<TagChange, String> {
+    let result = tags::merge_tags(state.db(), &source_ids, target_id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    notifications::notify_tags_changed(state.notification(), "merged", &result.todo_ids);
+
+    Ok(result)
+}
+
+/// 删除标签并从所有 todo 中移除
+#[tauri::command]
+pub async fn delete_tag(state: State<'_, AppState>, id: i32) -> Result<TagChange, String> {
+    let result = tags::delete_tag(state.db(), id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    notifications::notify_tags_changed(state.notification(), "deleted", &result.todo_ids);
+
+    Ok(result)
+}
+
+/// 设置标签颜色（null 表示清除）
+#[tauri::command]
+pub async fn set_tag_color(
+    state: State<'_, AppState>,
+    id: i32,
+    color: Option<String>,
+) -> Result<TagSummary, String> {
+    tags::set_tag_color(state.db(), id, color)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 列出所有清单
+#[tauri::command]
+pub async fn list_todo_lists(state: State<'_, AppState>) -> Result<Vec<TodoList>, String> {
+    lists::list_lists(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 创建清单
+#[tauri::command]
+pub async fn create_todo_list(
+    state: State<'_, AppState>,
+    payload: TodoListInput,
+) -> Result<TodoList, String> {
+    lists::create_list(state.db(), payload)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 修改清单（名称、颜色、映射的 CalDAV 日历）
+#[tauri::command]
+pub async fn update_todo_list(
+    state: State<'_, AppState>,
+    id: i32,
+    payload: TodoListInput,
+) -> Result<TodoList, String> {
+    lists::update_list(state.db(), id, payload)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 删除清单（其中的 todo 移出清单）
+#[tauri::command]
+pub async fn delete_todo_list(state: State<'_, AppState>, id: i32) -> Result<(), String> {
+    lists::delete_list(state.db(), id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 把 todo（连同子任务）移到清单，list_id 为空表示移出清单
+#[tauri::command]
+pub async fn move_todo_to_list(
+    state: State<'_, AppState>,
+    id: i32,
+    list_id: Option<i32>,
+) -> Result<Todo, String> {
+    lists::move_todo(state.db(), id, list_id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 查询 todo 的阻塞关系
+#[tauri::command]
+pub async fn get_todo_dependencies(
+    state: State<'_, AppState>,
+    id: i32,
+) -> Result<TodoDependencies, String> {
+    dependencies::list_dependencies(state.db(), id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 添加阻塞关系：id 需要等待 depends_on_id 完成
+#[tauri::command]
+pub async fn add_todo_dependency(
+    state: State<'_, AppState>,
+    id: i32,
+    depends_on_id: i32,
+) -> Result<TodoDependencies, String> {
+    let result = dependencies::add_dependency(state.db(), id, depends_on_id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // 被阻塞的 todo 推迟提醒
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 移除阻塞关系
+#[tauri::command]
+pub async fn remove_todo_dependency(
+    state: State<'_, AppState>,
+    id: i32,
+    depends_on_id: i32,
+) -> Result<TodoDependencies, String> {
+    let result = dependencies::remove_dependency(state.db(), id, depends_on_id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 获取待办的所有提醒
+#[tauri::command]
+pub async fn get_todo_reminders(
+    state: State<'_, AppState>,
+    id: i32,
+) -> Result<Vec<TodoReminder>, String> {
+    reminders::list_reminders(state.db(), id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 替换待办的全部提醒
+#[tauri::command]
+pub async fn set_todo_reminders(
+    state: State<'_, AppState>,
+    id: i32,
+    reminders: Vec<ReminderInput>,
+) -> Result<Vec<TodoReminder>, String> {
+    let result = reminders::set_reminders(state.db(), id, reminders)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 为待办添加一个提醒
+#[tauri::command]
+pub async fn add_todo_reminder(
+    state: State<'_, AppState>,
+    id: i32,
+    reminder: ReminderInput,
+) -> Result<Vec<TodoReminder>, String> {
+    let result = reminders::add_reminder(state.db(), id, reminder)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 移除待办的一个提醒
+#[tauri::command]
+pub async fn remove_todo_reminder(
+    state: State<'_, AppState>,
+    id: i32,
+    reminder_id: i32,
+) -> Result<Vec<TodoReminder>, String> {
+    let result = reminders::remove_reminder(state.db(), id, reminder_id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if let Some(scheduler) = state.todo_scheduler() {
+        scheduler.reschedule().await;
+    }
+
+    Ok(result)
+}
+
+/// 获取提醒设置
+#[tauri::command]
+pub async fn get_todo_reminder_settings(
+    state: State<'_, AppState>,
+) -> Result<ReminderSettings, String> {
+    reminders::get_settings(state.db())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 设置提醒（启动时补发错过提醒的时间范围）
+#[tauri::command]
+pub async fn set_todo_reminder_settings(
+    state: State<'_, AppState>,
+    settings: ReminderSettings,
+) -> Result<(), String> {
+    reminders::set_settings(state.db(), settings)
+        .await
+        .map_err(|err| err.to_string())
+}
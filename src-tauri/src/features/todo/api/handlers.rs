@@ -6,7 +6,13 @@ use tauri::Manager;
 use crate::infrastructure::webserver;
 
 use super::notifications;
-use crate::features::todo::core::service;
+use crate::features::todo::core::{
+    bulk, dependencies, journal, lists,
+    ordering::{self, ReorderPosition},
+    query, quick_add,
+    reminders::{self, ReminderInput},
+    search, service, tags, trash, tree,
+};
 
 /// 注册 Todo Feature 的所有 WebSocket handlers
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -31,6 +37,39 @@ pub fn register_handlers(registry: &mut webserver::HandlerRegistry) {
         })
     });
 
+    // 按条件查询待办
+    registry.register_call("todo.query", |_method, params, ctx| {
+        Box::pin(async move {
+            let query: query::TodoQuery = if params.is_null() {
+                query::TodoQuery::default()
+            } else {
+                serde_json::from_value(params).context("Invalid query params")?
+            };
+
+            let page = query::query_todos(ctx.db(), &query)
+                .await
+                .context("Failed to query todos")?;
+            Ok(json!(page))
+        })
+    });
+
+    // 全文搜索待办
+    registry.register_call("todo.search", |_method, params, ctx| {
+        Box::pin(async move {
+            let query = params
+                .get("query")
+                .and_then(|v| v.as_str())
+                .context("Missing or invalid query")?
+                .to_string();
+            let limit = params.get("limit").and_then(|v| v.as_u64());
+
+            let hits = search::search_todos(ctx.db(), &query, limit)
+                .await
+                .context("Failed to search todos")?;
+            Ok(json!(hits))
+        })
+    });
+
     // 获取单个待办
     registry.register_call("todo.get", |_method, params, ctx| {
         Box::pin(async move {
@@ -47,6 +86,22 @@ pub fn register_handlers(registry: &mut webserver::HandlerRegistry) {
         })
     });
 
+    // 获取待办树（全部顶层待办或以 root_id 为根的子树）
+    registry.register_call("todo.tree", |_method, params, ctx| {
+        Box::pin(async move {
+            let root_id = params
+                .get("root_id")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32);
+
+            let nodes = tree::load_tree(ctx.db(), root_id)
+                .await
+                .context("Failed to load todo tree")?;
+
+            Ok(json!(nodes))
+        })
+    });
+
     // 创建待办
     registry.register_call("todo.create", |_method, params, ctx| {
         Box::pin(async move {
@@ -84,6 +139,50 @@ pub fn register_handlers(registry: &mut webserver::HandlerRegistry) {
         })
     });
 
+    // 用一句话快速创建待办
+    registry.register_call("todo.quick_add", |_method, params, ctx| {
+        Box::pin(async move {
+            let text = params
+                .get("text")
+                .and_then(|v| v.as_str())
+                .context("Missing or invalid text")?
+                .to_string();
+            let dry_run = params
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let result = quick_add::quick_add(ctx.db(), &text, dry_run)
+                .await
+                .context("Failed to quick add todo")?;
+
+            if let Some(todo) = &result.todo {
+                // 同时通过 Tauri Event 通知前端（给内置前端）
+                use tauri::Emitter;
+                let _ = ctx.app_handle().emit(
+                    "todo-data-updated",
+                    json!({
+                        "action": "created",
+                        "todoId": todo.id,
+                        "source": "webserver"
+                    }),
+                );
+
+                // 发送 Toast + WebSocket 通知 & 触发调度器重新规划
+                if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                    notifications::notify_todo_created(state.notification(), todo.id, &todo.title);
+
+                    // 触发调度器重新规划提醒
+                    if let Some(scheduler) = state.todo_scheduler() {
+                        scheduler.reschedule().await;
+                    }
+                }
+            }
+
+            Ok(json!(result))
+        })
+    });
+
     // 更新待办
     registry.register_call("todo.update", |_method, params, ctx| {
         Box::pin(async move {
@@ -274,4 +373,757 @@ pub fn register_handlers(registry: &mut webserver::HandlerRegistry) {
             Ok(json!(todo))
         })
     });
+
+    // 稍后提醒：在 minutes 分钟之后，或在 until 指定的时间再次提醒
+    registry.register_call("todo.snooze", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let minutes = params.get("minutes").and_then(|v| v.as_i64());
+            let until = params
+                .get("until")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+
+            let todo = service::snooze_todo(ctx.db(), id, minutes, until)
+                .await
+                .context("Failed to snooze todo")?;
+
+            // 同时通过 Tauri Event 通知前端（给内置前端）
+            use tauri::Emitter;
+            let _ = ctx.app_handle().emit(
+                "todo-data-updated",
+                json!({
+                    "action": "updated",
+                    "todoId": id,
+                    "source": "webserver"
+                }),
+            );
+
+            // 触发调度器重新规划提醒
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                if let Some(scheduler) = state.todo_scheduler() {
+                    scheduler.reschedule().await;
+                }
+            }
+
+            Ok(json!(todo))
+        })
+    });
+
+    // 设置预估番茄数（null 表示清除）
+    registry.register_call("todo.set_estimate", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let estimated_pomodoros = params
+                .get("estimated_pomodoros")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32);
+
+            let todo = service::set_estimated_pomodoros(ctx.db(), id, estimated_pomodoros)
+                .await
+                .context("Failed to set estimate")?;
+
+            use tauri::Emitter;
+            let _ = ctx.app_handle().emit(
+                "todo-data-updated",
+                json!({
+                    "action": "updated",
+                    "todoId": id,
+                    "source": "webserver"
+                }),
+            );
+
+            Ok(json!(todo))
+        })
+    });
+
+    // 手动排序：移到同级待办之前/之后，或移到某个父任务的最后
+    registry.register_call("todo.reorder", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let before_id = params.get("before_id").and_then(|v| v.as_i64());
+            let after_id = params.get("after_id").and_then(|v| v.as_i64());
+
+            let position = match (before_id, after_id) {
+                (Some(before_id), None) => ReorderPosition::Before(before_id as i32),
+                (None, Some(after_id)) => ReorderPosition::After(after_id as i32),
+                (None, None) => {
+                    let parent_id = params
+                        .get("parent_id")
+                        .context("One of before_id, after_id or parent_id is required")?;
+                    ReorderPosition::End {
+                        parent_id: parent_id.as_i64().map(|v| v as i32),
+                    }
+                }
+                (Some(_), Some(_)) => {
+                    anyhow::bail!("Only one of before_id and after_id is allowed")
+                }
+            };
+
+            let todo = ordering::reorder(ctx.db(), id, position)
+                .await
+                .context("Failed to reorder todo")?;
+
+            // 同时通过 Tauri Event 通知前端（给内置前端）
+            use tauri::Emitter;
+            let _ = ctx.app_handle().emit(
+                "todo-data-updated",
+                json!({
+                    "action": "updated",
+                    "todoId": id,
+                    "source": "webserver"
+                }),
+            );
+
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                notifications::notify_todo_updated(state.notification(), id, &todo.title);
+            }
+
+            Ok(json!(todo))
+        })
+    });
+
+    // 批量操作（单个事务，全部成功或全部回滚）
+    registry.register_call("todo.bulk", |_method, params, ctx| {
+        Box::pin(async move {
+            let operations: Vec<bulk::BulkOperation> = serde_json::from_value(
+                params
+                    .get("operations")
+                    .cloned()
+                    .context("Missing operations")?,
+            )
+            .context("Invalid operations")?;
+
+            let result = bulk::apply(ctx.db(), operations)
+                .await
+                .context("Failed to apply bulk operations")?;
+
+            // 同时通过 Tauri Event 通知前端（给内置前端）
+            use tauri::Emitter;
+            let _ = ctx.app_handle().emit(
+                "todo-data-updated",
+                json!({
+                    "action": "bulk",
+                    "todoIds": result
+                        .updated
+                        .iter()
+                        .map(|todo| todo.id)
+                        .chain(result.deleted.iter().copied())
+                        .collect::<Vec<_>>(),
+                    "source": "webserver"
+                }),
+            );
+
+            // 整批只发送一次通知、重新规划一次提醒
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                notifications::notify_todos_bulk_changed(state.notification(), &result);
+
+                if let Some(scheduler) = state.todo_scheduler() {
+                    scheduler.reschedule().await;
+                }
+            }
+
+            Ok(json!(result))
+        })
+    });
+
+    // 列出回收站
+    registry.register_call("todo.trash.list", |_method, _params, ctx| {
+        Box::pin(async move {
+            let todos = trash::list_trash(ctx.db())
+                .await
+                .context("Failed to list trashed todos")?;
+            Ok(json!(todos))
+        })
+    });
+
+    // 从回收站恢复待办
+    registry.register_call("todo.trash.restore", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+
+            let todo = trash::restore_todo(ctx.db(), id)
+                .await
+                .context("Failed to restore todo")?;
+
+            // 同时通过 Tauri Event 通知前端（给内置前端）
+            use tauri::Emitter;
+            let _ = ctx.app_handle().emit(
+                "todo-data-updated",
+                json!({
+                    "action": "restored",
+                    "todoId": id,
+                    "source": "webserver"
+                }),
+            );
+
+            // 发送 Toast + WebSocket 通知 & 触发调度器重新规划
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                notifications::notify_todo_restored(state.notification(), id, &todo.title);
+
+                // 触发调度器重新规划提醒
+                if let Some(scheduler) = state.todo_scheduler() {
+                    scheduler.reschedule().await;
+                }
+            }
+
+            Ok(json!(todo))
+        })
+    });
+
+    // 彻底删除回收站中的待办
+    registry.register_call("todo.trash.purge", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+
+            trash::purge_todo(ctx.db(), id)
+                .await
+                .context("Failed to purge todo")?;
+
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                notifications::notify_todo_purged(state.notification(), Some(id));
+            }
+
+            Ok(json!({"success": true}))
+        })
+    });
+
+    // 清空回收站
+    registry.register_call("todo.trash.empty", |_method, _params, ctx| {
+        Box::pin(async move {
+            trash::empty_trash(ctx.db())
+                .await
+                .context("Failed to empty trash")?;
+
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                notifications::notify_todo_purged(state.notification(), None);
+            }
+
+            Ok(json!({"success": true}))
+        })
+    });
+
+    // 撤销最近一次修改
+    registry.register_call("todo.undo", |_method, _params, ctx| {
+        Box::pin(async move {
+            let change = journal::undo(ctx.db())
+                .await
+                .context("Failed to undo todo change")?;
+
+            if let Some(change) = &change {
+                notify_history(&ctx, "undone", change).await;
+            }
+
+            Ok(json!(change))
+        })
+    });
+
+    // 重做最近一次被撤销的修改
+    registry.register_call("todo.redo", |_method, _params, ctx| {
+        Box::pin(async move {
+            let change = journal::redo(ctx.db())
+                .await
+                .context("Failed to redo todo change")?;
+
+            if let Some(change) = &change {
+                notify_history(&ctx, "redone", change).await;
+            }
+
+            Ok(json!(change))
+        })
+    });
+
+    // 查询是否可以撤销/重做
+    registry.register_call("todo.history", |_method, _params, ctx| {
+        Box::pin(async move {
+            let status = journal::status(ctx.db())
+                .await
+                .context("Failed to load todo history")?;
+            Ok(json!(status))
+        })
+    });
+
+    // 列出所有清单
+    registry.register_call("todo.lists.list", |_method, _params, ctx| {
+        Box::pin(async move {
+            let lists = lists::list_lists(ctx.db())
+                .await
+                .context("Failed to list todo lists")?;
+            Ok(json!(lists))
+        })
+    });
+
+    // 创建清单
+    registry.register_call("todo.lists.create", |_method, params, ctx| {
+        Box::pin(async move {
+            let input: lists::TodoListInput =
+                serde_json::from_value(params.clone()).context("Invalid list params")?;
+
+            let list = lists::create_list(ctx.db(), input)
+                .await
+                .context("Failed to create todo list")?;
+
+            notify_lists_changed(&ctx, "created", list.id);
+
+            Ok(json!(list))
+        })
+    });
+
+    // 修改清单
+    registry.register_call("todo.lists.update", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let input: lists::TodoListInput =
+                serde_json::from_value(params.clone()).context("Invalid list params")?;
+
+            let list = lists::update_list(ctx.db(), id, input)
+                .await
+                .context("Failed to update todo list")?;
+
+            notify_lists_changed(&ctx, "updated", id);
+
+            Ok(json!(list))
+        })
+    });
+
+    // 删除清单
+    registry.register_call("todo.lists.delete", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+
+            lists::delete_list(ctx.db(), id)
+                .await
+                .context("Failed to delete todo list")?;
+
+            notify_lists_changed(&ctx, "deleted", id);
+
+            Ok(json!({"success": true}))
+        })
+    });
+
+    // 把待办移到清单
+    registry.register_call("todo.lists.move", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let list_id = params
+                .get("list_id")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32);
+
+            let todo = lists::move_todo(ctx.db(), id, list_id)
+                .await
+                .context("Failed to move todo to list")?;
+
+            // 同时通过 Tauri Event 通知前端（给内置前端）
+            use tauri::Emitter;
+            let _ = ctx.app_handle().emit(
+                "todo-data-updated",
+                json!({
+                    "action": "updated",
+                    "todoId": id,
+                    "source": "webserver"
+                }),
+            );
+
+            if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+                notifications::notify_todo_updated(state.notification(), id, &todo.title);
+            }
+
+            Ok(json!(todo))
+        })
+    });
+
+    // 列出所有标签及使用次数
+    registry.register_call("todo.tags.list", |_method, _params, ctx| {
+        Box::pin(async move {
+            let tags = tags::list_tags(ctx.db())
+                .await
+                .context("Failed to list tags")?;
+            Ok(json!(tags))
+        })
+    });
+
+    // 重命名标签
+    registry.register_call("todo.tags.rename", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let name = params
+                .get("name")
+                .and_then(|v| v.as_str())
+                .context("Missing or invalid name")?;
+
+            let change = tags::rename_tag(ctx.db(), id, name)
+                .await
+                .context("Failed to rename tag")?;
+
+            notify_tags_changed(&ctx, "renamed", &change);
+
+            Ok(json!(change))
+        })
+    });
+
+    // 合并标签
+    registry.register_call("todo.tags.merge", |_method, params, ctx| {
+        Box::pin(async move {
+            let source_ids: Vec<i32> = serde_json::from_value(
+                params
+                    .get("source_ids")
+                    .cloned()
+                    .context("Missing source_ids")?,
+            )
+            .context("Invalid source_ids")?;
+            let target_id = params
+                .get("target_id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid target_id")? as i32;
+
+            let change = tags::merge_tags(ctx.db(), &source_ids, target_id)
+                .await
+                .context("Failed to merge tags")?;
+
+            notify_tags_changed(&ctx, "merged", &change);
+
+            Ok(json!(change))
+        })
+    });
+
+    // 删除标签
+    registry.register_call("todo.tags.delete", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+
+            let change = tags::delete_tag(ctx.db(), id)
+                .await
+                .context("Failed to delete tag")?;
+
+            notify_tags_changed(&ctx, "deleted", &change);
+
+            Ok(json!(change))
+        })
+    });
+
+    // 设置标签颜色
+    registry.register_call("todo.tags.set_color", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let color = params
+                .get("color")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let tag = tags::set_tag_color(ctx.db(), id, color)
+                .await
+                .context("Failed to set tag colour")?;
+
+            Ok(json!(tag))
+        })
+    });
+
+    // 查询待办的阻塞关系
+    registry.register_call("todo.dependencies.list", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+
+            let result = dependencies::list_dependencies(ctx.db(), id)
+                .await
+                .context("Failed to load todo dependencies")?;
+
+            Ok(json!(result))
+        })
+    });
+
+    // 添加阻塞关系
+    registry.register_call("todo.dependencies.add", |_method, params, ctx| {
+        Box::pin(async move {
+            let (id, depends_on_id) = parse_dependency_params(&params)?;
+
+            let result = dependencies::add_dependency(ctx.db(), id, depends_on_id)
+                .await
+                .context("Failed to add todo dependency")?;
+
+            notify_dependencies_changed(&ctx, id).await;
+
+            Ok(json!(result))
+        })
+    });
+
+    // 移除阻塞关系
+    registry.register_call("todo.dependencies.remove", |_method, params, ctx| {
+        Box::pin(async move {
+            let (id, depends_on_id) = parse_dependency_params(&params)?;
+
+            let result = dependencies::remove_dependency(ctx.db(), id, depends_on_id)
+                .await
+                .context("Failed to remove todo dependency")?;
+
+            notify_dependencies_changed(&ctx, id).await;
+
+            Ok(json!(result))
+        })
+    });
+
+    // 列出待办的所有提醒
+    registry.register_call("todo.reminders.list", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+
+            let result = reminders::list_reminders(ctx.db(), id)
+                .await
+                .context("Failed to load todo reminders")?;
+
+            Ok(json!(result))
+        })
+    });
+
+    // 替换待办的全部提醒
+    registry.register_call("todo.reminders.set", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let inputs: Vec<ReminderInput> = serde_json::from_value(
+                params
+                    .get("reminders")
+                    .cloned()
+                    .context("Missing reminders")?,
+            )
+            .context("Invalid reminders")?;
+
+            let result = reminders::set_reminders(ctx.db(), id, inputs)
+                .await
+                .context("Failed to set todo reminders")?;
+
+            notify_reminders_changed(&ctx, id).await;
+
+            Ok(json!(result))
+        })
+    });
+
+    // 添加一个提醒
+    registry.register_call("todo.reminders.add", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let input: ReminderInput = serde_json::from_value(
+                params
+                    .get("reminder")
+                    .cloned()
+                    .context("Missing reminder")?,
+            )
+            .context("Invalid reminder")?;
+
+            let result = reminders::add_reminder(ctx.db(), id, input)
+                .await
+                .context("Failed to add todo reminder")?;
+
+            notify_reminders_changed(&ctx, id).await;
+
+            Ok(json!(result))
+        })
+    });
+
+    // 移除一个提醒
+    registry.register_call("todo.reminders.remove", |_method, params, ctx| {
+        Box::pin(async move {
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid id")? as i32;
+            let reminder_id = params
+                .get("reminder_id")
+                .and_then(|v| v.as_i64())
+                .context("Missing or invalid reminder_id")? as i32;
+
+            let result = reminders::remove_reminder(ctx.db(), id, reminder_id)
+                .await
+                .context("Failed to remove todo reminder")?;
+
+            notify_reminders_changed(&ctx, id).await;
+
+            Ok(json!(result))
+        })
+    });
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn parse_dependency_params(params: &Value) -> anyhow::Result<(i32, i32)> {
+    let id = params
+        .get("id")
+        .and_then(|v| v.as_i64())
+        .context("Missing or invalid id")? as i32;
+    let depends_on_id = params
+        .get("depends_on_id")
+        .and_then(|v| v.as_i64())
+        .context("Missing or invalid depends_on_id")? as i32;
+    Ok((id, depends_on_id))
+}
+
+/// 阻塞关系变化后通知前端并重新规划提醒
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+async fn notify_dependencies_changed(
+    ctx: &crate::infrastructure::webserver::core::ws::ApiContext,
+    id: i32,
+) {
+    // 同时通过 Tauri Event 通知前端（给内置前端）
+    use tauri::Emitter;
+    let _ = ctx.app_handle().emit(
+        "todo-data-updated",
+        json!({
+            "action": "updated",
+            "todoId": id,
+            "source": "webserver"
+        }),
+    );
+
+    if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+        // 被阻塞的待办推迟提醒
+        if let Some(scheduler) = state.todo_scheduler() {
+            scheduler.reschedule().await;
+        }
+    }
+}
+
+/// 提醒变化后通知前端并重新规划提醒
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+async fn notify_reminders_changed(
+    ctx: &crate::infrastructure::webserver::core::ws::ApiContext,
+    id: i32,
+) {
+    // 同时通过 Tauri Event 通知前端（给内置前端）
+    use tauri::Emitter;
+    let _ = ctx.app_handle().emit(
+        "todo-data-updated",
+        json!({
+            "action": "updated",
+            "todoId": id,
+            "source": "webserver"
+        }),
+    );
+
+    if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+        if let Some(scheduler) = state.todo_scheduler() {
+            scheduler.reschedule().await;
+        }
+    }
+}
+
+/// 撤销/重做后通知前端、广播变更并重新规划提醒
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+async fn notify_history(
+    ctx: &crate::infrastructure::webserver::core::ws::ApiContext,
+    action: &str,
+    change: &journal::HistoryChange,
+) {
+    // 同时通过 Tauri Event 通知前端（给内置前端）
+    use tauri::Emitter;
+    let _ = ctx.app_handle().emit(
+        "todo-data-updated",
+        json!({
+            "action": action,
+            "todoIds": change.todo_ids,
+            "source": "webserver"
+        }),
+    );
+
+    if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+        notifications::notify_todo_history(
+            state.notification(),
+            action,
+            &change.operation,
+            &change.todo_ids,
+        );
+
+        // 触发调度器重新规划提醒
+        if let Some(scheduler) = state.todo_scheduler() {
+            scheduler.reschedule().await;
+        }
+    }
+}
+
+/// 清单修改后通知前端并广播变更
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn notify_lists_changed(
+    ctx: &crate::infrastructure::webserver::core::ws::ApiContext,
+    action: &str,
+    list_id: i32,
+) {
+    // 同时通过 Tauri Event 通知前端（给内置前端）
+    use tauri::Emitter;
+    let _ = ctx.app_handle().emit(
+        "todo-data-updated",
+        json!({
+            "action": "lists_changed",
+            "listId": list_id,
+            "source": "webserver"
+        }),
+    );
+
+    if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+        notifications::notify_lists_changed(state.notification(), action, list_id);
+    }
+}
+
+/// 标签修改后通知前端并广播变更
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn notify_tags_changed(
+    ctx: &crate::infrastructure::webserver::core::ws::ApiContext,
+    action: &str,
+    change: &tags::TagChange,
+) {
+    // 同时通过 Tauri Event 通知前端（给内置前端）
+    use tauri::Emitter;
+    let _ = ctx.app_handle().emit(
+        "todo-data-updated",
+        json!({
+            "action": "tags_changed",
+            "todoIds": change.todo_ids,
+            "source": "webserver"
+        }),
+    );
+
+    if let Some(state) = ctx.app_handle().try_state::<crate::core::AppState>() {
+        notifications::notify_tags_changed(state.notification(), action, &change.todo_ids);
+    }
 }
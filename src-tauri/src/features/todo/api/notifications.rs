@@ -1,5 +1,6 @@
 use serde_json::json;
 
+use crate::features::todo::core::{bulk::BulkResult, reminders::MissedReminder};
 use crate::infrastructure::notification::{NotificationManager, ToastLevel};
 
 /// Todo Feature 的所有通知定义
@@ -13,6 +14,7 @@ use crate::infrastructure::notification::{NotificationManager, ToastLevel};
 /// WebSocket Event 名称
 pub const TODO_CHANGES_EVENT: &str = "todo.changes";
 pub const TODO_DUE_EVENT: &str = "todo.due";
+pub const TODO_MISSED_EVENT: &str = "todo.missed";
 
 /// 创建 Todo 成功通知（Toast + WebSocket）
 pub fn notify_todo_created(notification_manager: &NotificationManager, todo_id: i32, title: &str) {
@@ -43,7 +45,7 @@ pub fn notify_todo_updated(notification_manager: &NotificationManager, todo_id:
 /// 删除 Todo 成功通知（Toast + WebSocket）
 pub fn notify_todo_deleted(notification_manager: &NotificationManager, todo_id: i32) {
     let _ = notification_manager.notify(
-        "待办删除成功".to_string(),
+        "待办已移至回收站".to_string(),
         ToastLevel::Success,
         TODO_CHANGES_EVENT.to_string(),
         json!({
@@ -53,6 +55,130 @@ pub fn notify_todo_deleted(notification_manager: &NotificationManager, todo_id:
     );
 }
 
+/// 批量操作成功通知（整批只发送一次 Toast + WebSocket）
+pub fn notify_todos_bulk_changed(notification_manager: &NotificationManager, result: &BulkResult) {
+    let updated_ids: Vec<i32> = result.updated.iter().map(|todo| todo.id).collect();
+    let _ = notification_manager.notify(
+        format!(
+            "已批量处理 {} 个待办",
+            updated_ids.len() + result.deleted.len()
+        ),
+        ToastLevel::Success,
+        TODO_CHANGES_EVENT.to_string(),
+        json!({
+            "action": "bulk",
+            "batch_id": result.batch_id,
+            "updated_ids": updated_ids,
+            "deleted_ids": result.deleted,
+        }),
+    );
+}
+
+/// 从回收站恢复 Todo 成功通知（Toast + WebSocket）
+pub fn notify_todo_restored(notification_manager: &NotificationManager, todo_id: i32, title: &str) {
+    let _ = notification_manager.notify(
+        format!("待办 \"{}\" 已恢复", title),
+        ToastLevel::Success,
+        TODO_CHANGES_EVENT.to_string(),
+        json!({
+            "action": "restored",
+            "todo_id": todo_id,
+        }),
+    );
+}
+
+/// 彻底删除 Todo 成功通知（Toast + WebSocket）
+///
+/// todo_id 为空表示清空了整个回收站
+pub fn notify_todo_purged(notification_manager: &NotificationManager, todo_id: Option<i32>) {
+    let _ = notification_manager.notify(
+        if todo_id.is_some() {
+            "待办已彻底删除".to_string()
+        } else {
+            "回收站已清空".to_string()
+        },
+        ToastLevel::Success,
+        TODO_CHANGES_EVENT.to_string(),
+        json!({
+            "action": "purged",
+            "todo_id": todo_id,
+        }),
+    );
+}
+
+/// 撤销/重做 Todo 修改成功通知（Toast + WebSocket）
+///
+/// action 为 "undone" 或 "redone"
+pub fn notify_todo_history(
+    notification_manager: &NotificationManager,
+    action: &str,
+    operation: &str,
+    todo_ids: &[i32],
+) {
+    let _ = notification_manager.notify(
+        if action == "undone" {
+            "已撤销".to_string()
+        } else {
+            "已重做".to_string()
+        },
+        ToastLevel::Success,
+        TODO_CHANGES_EVENT.to_string(),
+        json!({
+            "action": action,
+            "operation": operation,
+            "todo_ids": todo_ids,
+        }),
+    );
+}
+
+/// 清单创建/修改/删除成功通知（Toast + WebSocket）
+///
+/// action 为 "created"、"updated" 或 "deleted"
+pub fn notify_lists_changed(
+    notification_manager: &NotificationManager,
+    action: &str,
+    list_id: i32,
+) {
+    let _ = notification_manager.notify(
+        match action {
+            "created" => "清单已创建".to_string(),
+            "updated" => "清单已更新".to_string(),
+            _ => "清单已删除".to_string(),
+        },
+        ToastLevel::Success,
+        TODO_CHANGES_EVENT.to_string(),
+        json!({
+            "action": "lists_changed",
+            "list_action": action,
+            "list_id": list_id,
+        }),
+    );
+}
+
+/// 标签重命名/合并/删除成功通知（Toast + WebSocket）
+///
+/// action 为 "renamed"、"merged" 或 "deleted"
+pub fn notify_tags_changed(
+    notification_manager: &NotificationManager,
+    action: &str,
+    todo_ids: &[i32],
+) {
+    let _ = notification_manager.notify(
+        match action {
+            "renamed" => "标签已重命名".to_string(),
+            "merged" => "标签已合并".to_string(),
+            _ => "标签已删除".to_string(),
+        },
+        ToastLevel::Success,
+        TODO_CHANGES_EVENT.to_string(),
+        json!({
+            "action": "tags_changed",
+            "tag_action": action,
+            "todo_ids": todo_ids,
+        }),
+    );
+}
+
 /// Todo 到期提醒通知（Toast + WebSocket）
 pub fn notify_todo_due(notification_manager: &NotificationManager, todo_id: i32, title: &str) {
     let _ = notification_manager.notify(
@@ -69,6 +195,37 @@ pub fn notify_todo_due(notification_manager: &NotificationManager, todo_id: i32,
     let _ = notification_manager.send_native("待办到期".to_string(), format!("{} 已到期", title));
 }
 
+/// 应用关闭期间错过的提醒（启动时合并成一条 Toast + WebSocket 通知）
+pub fn notify_todos_missed(notification_manager: &NotificationManager, missed: &[MissedReminder]) {
+    let _ = notification_manager.notify(
+        format!("⏰ 应用关闭期间错过了 {} 个提醒", missed.len()),
+        ToastLevel::Warning,
+        TODO_MISSED_EVENT.to_string(),
+        json!({
+            "count": missed.len(),
+            "reminders": missed,
+        }),
+    );
+
+    // 系统通知只列出前几个待办
+    let mut titles: Vec<&str> = Vec::new();
+    for reminder in missed {
+        if !titles.contains(&reminder.title.as_str()) {
+            titles.push(&reminder.title);
+        }
+    }
+    let mut body = titles
+        .iter()
+        .take(3)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("、");
+    if titles.len() > 3 {
+        body.push_str(&format!(" 等 {} 个待办", titles.len()));
+    }
+    let _ = notification_manager.send_native(format!("错过了 {} 个提醒", missed.len()), body);
+}
+
 /// CalDAV 同步成功通知
 pub fn notify_sync_success(
     notification_manager: &NotificationManager,
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ConnectionTrait, DatabaseConnection, EntityTrait,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::journal::{self, Operation};
+use super::lists;
+use super::models::Todo;
+use super::recurrence;
+use super::service;
+use super::tags::{self, contains_tag, normalize_tags};
+use crate::features::todo::data::entity;
+
+/// 单次批量操作允许的最大操作数
+pub const MAX_BULK_OPERATIONS: usize = 500;
+
+/// iCalendar PRIORITY 的取值范围（0 表示未定义）
+const PRIORITY_RANGE: std::ops::RangeInclusive<i32> = 0..=9;
+
+/// 批量操作中的一项
+///
+/// JSON 形式：`{"op": "complete", "id": 1}`、`{"op": "set_priority", "id": 2, "priority": 5}` 等
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// 标记完成（重复待办推进到下一次发生）
+    Complete { id: i32 },
+    /// 取消完成
+    Uncomplete { id: i32 },
+    /// 修改标题
+    Rename { id: i32, title: String },
+    /// 替换全部标签
+    SetTags { id: i32, tags: Vec<String> },
+    /// 追加标签（已存在的忽略，不区分大小写）
+    AddTags { id: i32, tags: Vec<String> },
+    /// 移除标签（不区分大小写）
+    RemoveTags { id: i32, tags: Vec<String> },
+    /// 修改优先级（0-9），null 表示清除
+    SetPriority { id: i32, priority: Option<i32> },
+    /// 移动到新的父任务下，null 表示移为顶层任务
+    Move { id: i32, parent_id: Option<i32> },
+    /// 移到清单（连同子任务），null 表示移出清单
+    SetList { id: i32, list_id: Option<i32> },
+    /// 移入回收站
+    Delete { id: i32 },
+}
+
+impl BulkOperation {
+    fn id(&self) -> i32 {
+        match self {
+            BulkOperation::Complete { id }
+            | BulkOperation::Uncomplete { id }
+            | BulkOperation::Rename { id, .. }
+            | BulkOperation::SetTags { id, .. }
+            | BulkOperation::AddTags { id, .. }
+            | BulkOperation::RemoveTags { id, .. }
+            | BulkOperation::SetPriority { id, .. }
+            | BulkOperation::Move { id, .. }
+            | BulkOperation::SetList { id, .. }
+            | BulkOperation::Delete { id } => *id,
+        }
+    }
+}
+
+/// 批量操作结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkResult {
+    /// 操作日志中的批次 ID，整批操作可以一次撤销
+    pub batch_id: String,
+    /// 修改后的待办（每个 ID 只出现一次，按首次出现的顺序）
+    pub updated: Vec<Todo>,
+    /// 移入回收站的待办 ID
+    pub deleted: Vec<i32>,
+}
+
+/// 在一个事务中依次执行批量操作
+///
+/// 任意一项失败时整批回滚，错误信息中带有失败项的序号。
+/// 所有修改记录在同一个操作日志批次中，撤销时一起恢复。
+pub async fn apply(db: &DatabaseConnection, operations: Vec<BulkOperation>) -> Result<BulkResult> {
+    if operations.is_empty() {
+        return Err(anyhow!("no operations given"));
+    }
+    if operations.len() > MAX_BULK_OPERATIONS {
+        return Err(anyhow!(
+            "too many operations: {} (max {MAX_BULK_OPERATIONS})",
+            operations.len()
+        ));
+    }
+
+    let mode = recurrence::get_mode(db).await?;
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start bulk transaction")?;
+    let batch_id = journal::new_batch_id();
+
+    let mut touched: Vec<i32> = Vec::new();
+    let mut deleted: Vec<i32> = Vec::new();
+
+    for (index, operation) in operations.into_iter().enumerate() {
+        let id = operation.id();
+        let result = match operation {
+            BulkOperation::Complete { id } => {
+                service::update_todo_in(&txn, &batch_id, mode, id, None, Some(true))
+                    .await
+                    .map(|_| ())
+            }
+            BulkOperation::Uncomplete { id } => {
+                service::update_todo_in(&txn, &batch_id, mode, id, None, Some(false))
+                    .await
+                    .map(|_| ())
+            }
+            BulkOperation::Rename { id, title } => {
+                service::update_todo_in(&txn, &batch_id, mode, id, Some(title), None)
+                    .await
+                    .map(|_| ())
+            }
+            BulkOperation::SetTags { id, tags } => {
+                update_tags(&txn, &batch_id, id, |_| normalize_tags(tags)).await
+            }
+            BulkOperation::AddTags { id, tags } => {
+                update_tags(&txn, &batch_id, id, |mut current| {
+                    for tag in normalize_tags(tags) {
+                        if !contains_tag(&current, &tag) {
+                            current.push(tag);
+                        }
+                    }
+                    current
+                })
+                .await
+            }
+            BulkOperation::RemoveTags { id, tags } => {
+                let removed = normalize_tags(tags);
+                update_tags(&txn, &batch_id, id, |current| {
+                    current
+                        .into_iter()
+                        .filter(|tag| !contains_tag(&removed, tag))
+                        .collect()
+                })
+                .await
+            }
+            BulkOperation::SetPriority { id, priority } => {
+                set_priority(&txn, &batch_id, id, priority).await
+            }
+            BulkOperation::Move { id, parent_id } => {
+                service::update_parent_in(&txn, &batch_id, id, parent_id)
+                    .await
+                    .map(|_| ())
+            }
+            BulkOperation::SetList { id, list_id } => {
+                lists::move_todo_in(&txn, &batch_id, id, list_id)
+                    .await
+                    .map(|_| ())
+            }
+            BulkOperation::Delete { id } => {
+                service::delete_todo_in(&txn, &batch_id, id).await.map(|_| {
+                    if !deleted.contains(&id) {
+                        deleted.push(id);
+                    }
+                })
+            }
+        };
+
+        result.with_context(|| format!("bulk operation #{index} on todo {id} failed"))?;
+
+        if !touched.contains(&id) {
+            touched.push(id);
+        }
+    }
+
+    // 返回每个待办的最终状态（已删除的除外）
+    let mut updated = Vec::new();
+    for id in touched {
+        if deleted.contains(&id) {
+            continue;
+        }
+        let model = entity::Entity::find_by_id(id)
+            .one(&txn)
+            .await
+            .with_context(|| format!("failed to load todo {id}"))?
+            .ok_or_else(|| anyhow!("todo {id} not found"))?;
+        if model.deleted_at.is_none() {
+            updated.push(model.into());
+        }
+    }
+
+    journal::commit(txn).await?;
+
+    Ok(BulkResult {
+        batch_id,
+        updated,
+        deleted,
+    })
+}
+
+/// 修改标签并记录操作日志
+async fn update_tags<C, F>(db: &C, batch_id: &str, id: i32, change: F) -> Result<()>
+where
+    C: ConnectionTrait,
+    F: FnOnce(Vec<String>) -> Vec<String>,
+{
+    let model = load_active_todo(db, id).await?;
+    let tags = change(tags::parse_tags(model.tags.as_deref()));
+    tags::write_todo_tags(db, batch_id, model, &tags)
+        .await
+        .map(|_| ())
+}
+
+/// 修改优先级并记录操作日志
+async fn set_priority<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    priority: Option<i32>,
+) -> Result<()> {
+    if let Some(priority) = priority.filter(|p| !PRIORITY_RANGE.contains(p)) {
+        bail!("priority must be between 0 and 9, got {priority}");
+    }
+    let model = load_active_todo(db, id).await?;
+    let before = model.clone();
+    let mut active: entity::ActiveModel = model.into();
+    active.priority = Set(priority);
+    save_details(db, batch_id, id, before, active).await
+}
+
+async fn save_details<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    before: entity::Model,
+    mut active: entity::ActiveModel,
+) -> Result<()> {
+    let now = Utc::now();
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active.dirty = Set(true);
+
+    let updated = active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to update todo {id}"))?;
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::UpdateDetails,
+        Some(&before),
+        Some(&updated),
+    )
+    .await
+}
+
+async fn load_active_todo<C: ConnectionTrait>(db: &C, id: i32) -> Result<entity::Model> {
+    entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
+        .ok_or_else(|| anyhow!("todo {id} not found"))
+}
@@ -0,0 +1,269 @@
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+
+use super::models::Todo;
+use crate::features::todo::data::{entities::todo_dependency, entity};
+
+/// 待办是否被阻塞：存在未完成且不在回收站中的前置待办
+pub(crate) const BLOCKED_SQL: &str = "EXISTS (SELECT 1 FROM todo_dependencies \
+     JOIN todos AS blocker ON blocker.id = todo_dependencies.depends_on_id \
+     WHERE todo_dependencies.todo_id = todos.id \
+     AND blocker.completed = 0 AND blocker.deleted_at IS NULL)";
+
+/// 一个待办的阻塞关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoDependencies {
+    pub todo_id: i32,
+    /// 阻塞该待办的前置待办
+    pub blocked_by: Vec<Todo>,
+    /// 被该待办阻塞的待办
+    pub blocking: Vec<Todo>,
+    /// 是否仍有未完成的前置待办
+    pub blocked: bool,
+}
+
+/// 查询待办的阻塞关系（不含回收站中的待办）
+pub async fn list_dependencies(db: &DatabaseConnection, id: i32) -> Result<TodoDependencies> {
+    load_active(db, id).await?;
+    load_dependencies(db, id).await
+}
+
+/// 添加阻塞关系：todo_id 需要等待 depends_on_id 完成
+///
+/// 与 `update_parent` 一样拒绝自引用和循环；
+/// 被阻塞的待办标记为 dirty，下次同步时更新 RELATED-TO
+pub async fn add_dependency(
+    db: &DatabaseConnection,
+    todo_id: i32,
+    depends_on_id: i32,
+) -> Result<TodoDependencies> {
+    if todo_id == depends_on_id {
+        bail!("A task cannot depend on itself");
+    }
+
+    let todo = load_active(db, todo_id).await?;
+    load_active(db, depends_on_id).await?;
+
+    let exists = todo_dependency::Entity::find_by_id((todo_id, depends_on_id))
+        .one(db)
+        .await
+        .context("failed to load todo dependency")?
+        .is_some();
+
+    if !exists {
+        // 前置待办（直接或间接）依赖当前待办时会形成循环
+        if depends_on(db, depends_on_id, todo_id).await? {
+            bail!("Cannot create circular dependency between todos");
+        }
+
+        todo_dependency::ActiveModel {
+            todo_id: Set(todo_id),
+            depends_on_id: Set(depends_on_id),
+            created_at: Set(Utc::now()),
+        }
+        .insert(db)
+        .await
+        .with_context(|| format!("failed to add dependency {todo_id} -> {depends_on_id}"))?;
+
+        mark_dirty(db, todo).await?;
+    }
+
+    load_dependencies(db, todo_id).await
+}
+
+/// 移除阻塞关系
+pub async fn remove_dependency(
+    db: &DatabaseConnection,
+    todo_id: i32,
+    depends_on_id: i32,
+) -> Result<TodoDependencies> {
+    let todo = load_active(db, todo_id).await?;
+
+    let result = todo_dependency::Entity::delete_by_id((todo_id, depends_on_id))
+        .exec(db)
+        .await
+        .with_context(|| format!("failed to remove dependency {todo_id} -> {depends_on_id}"))?;
+
+    if result.rows_affected > 0 {
+        mark_dirty(db, todo).await?;
+    }
+
+    load_dependencies(db, todo_id).await
+}
+
+/// 用于查询过滤的“被阻塞”条件（作用于 todos 表）
+pub(crate) fn blocked_expr() -> SimpleExpr {
+    Expr::cust(BLOCKED_SQL)
+}
+
+/// 前置待办的 UID（不含回收站中的），用于生成 RELATED-TO;RELTYPE=DEPENDS-ON
+pub(crate) async fn dependency_uids<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+) -> Result<Vec<String>> {
+    let ids = dependency_ids(db, todo_id).await?;
+    let uids = entity::Entity::find()
+        .filter(entity::Column::Id.is_in(ids))
+        .filter(entity::Column::DeletedAt.is_null())
+        .all(db)
+        .await
+        .with_context(|| format!("failed to load dependencies of todo {todo_id}"))?
+        .into_iter()
+        .map(|model| model.uid)
+        .collect();
+    Ok(uids)
+}
+
+/// 用远端的 DEPENDS-ON 关系替换本地关系
+///
+/// 本地不存在的 UID 以及会形成循环的关系会被忽略
+pub(crate) async fn replace_from_remote<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    uids: &[String],
+) -> Result<()> {
+    let mut wanted = Vec::new();
+    if !uids.is_empty() {
+        for model in entity::Entity::find()
+            .filter(entity::Column::Uid.is_in(uids.to_vec()))
+            .all(db)
+            .await
+            .context("failed to resolve dependency UIDs")?
+        {
+            if model.id != todo_id && !wanted.contains(&model.id) {
+                wanted.push(model.id);
+            }
+        }
+    }
+
+    let current = dependency_ids(db, todo_id).await?;
+    if current.iter().all(|id| wanted.contains(id)) && wanted.iter().all(|id| current.contains(id))
+    {
+        return Ok(());
+    }
+
+    todo_dependency::Entity::delete_many()
+        .filter(todo_dependency::Column::TodoId.eq(todo_id))
+        .exec(db)
+        .await
+        .with_context(|| format!("failed to clear dependencies of todo {todo_id}"))?;
+
+    let now = Utc::now();
+    for depends_on_id in wanted {
+        if depends_on(db, depends_on_id, todo_id).await? {
+            eprintln!(
+                "  -> Warning: Skipping circular dependency {} -> {}",
+                todo_id, depends_on_id
+            );
+            continue;
+        }
+
+        todo_dependency::ActiveModel {
+            todo_id: Set(todo_id),
+            depends_on_id: Set(depends_on_id),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .with_context(|| format!("failed to add dependency {todo_id} -> {depends_on_id}"))?;
+    }
+
+    Ok(())
+}
+
+/// `from` 是否（直接或间接）依赖 `target`
+async fn depends_on<C: ConnectionTrait>(db: &C, from: i32, target: i32) -> Result<bool> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([from]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == target {
+            return Ok(true);
+        }
+        if !visited.insert(current) {
+            continue;
+        }
+        queue.extend(dependency_ids(db, current).await?);
+    }
+
+    Ok(false)
+}
+
+async fn dependency_ids<C: ConnectionTrait>(db: &C, todo_id: i32) -> Result<Vec<i32>> {
+    let ids = todo_dependency::Entity::find()
+        .filter(todo_dependency::Column::TodoId.eq(todo_id))
+        .all(db)
+        .await
+        .with_context(|| format!("failed to load dependencies of todo {todo_id}"))?
+        .into_iter()
+        .map(|link| link.depends_on_id)
+        .collect();
+    Ok(ids)
+}
+
+async fn load_dependencies<C: ConnectionTrait>(db: &C, id: i32) -> Result<TodoDependencies> {
+    let blocked_by_ids = dependency_ids(db, id).await?;
+    let blocking_ids: Vec<i32> = todo_dependency::Entity::find()
+        .filter(todo_dependency::Column::DependsOnId.eq(id))
+        .all(db)
+        .await
+        .with_context(|| format!("failed to load todos blocked by {id}"))?
+        .into_iter()
+        .map(|link| link.todo_id)
+        .collect();
+
+    let blocked_by = load_todos(db, blocked_by_ids).await?;
+    let blocking = load_todos(db, blocking_ids).await?;
+    let blocked = blocked_by.iter().any(|todo| !todo.completed);
+
+    Ok(TodoDependencies {
+        todo_id: id,
+        blocked_by: blocked_by.into_iter().map(Into::into).collect(),
+        blocking: blocking.into_iter().map(Into::into).collect(),
+        blocked,
+    })
+}
+
+async fn load_todos<C: ConnectionTrait>(db: &C, ids: Vec<i32>) -> Result<Vec<entity::Model>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    entity::Entity::find()
+        .filter(entity::Column::Id.is_in(ids))
+        .filter(entity::Column::DeletedAt.is_null())
+        .all(db)
+        .await
+        .context("failed to load dependent todos")
+}
+
+async fn load_active<C: ConnectionTrait>(db: &C, id: i32) -> Result<entity::Model> {
+    entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
+        .ok_or_else(|| anyhow!("todo {id} not found"))
+}
+
+async fn mark_dirty<C: ConnectionTrait>(db: &C, model: entity::Model) -> Result<()> {
+    let id = model.id;
+    let now = Utc::now();
+    let mut active: entity::ActiveModel = model.into();
+    active.dirty = Set(true);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to mark todo {id} for sync"))?;
+    Ok(())
+}
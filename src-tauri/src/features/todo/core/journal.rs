@@ -0,0 +1,364 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseBackend,
+    DatabaseConnection, DatabaseTransaction, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::reminders;
+use crate::features::todo::data::{
+    entities::{todo_journal, todo_list},
+    entity,
+};
+
+/// 保留的最近操作批次数量
+const MAX_JOURNAL_BATCHES: u32 = 200;
+
+/// 记录到日志中的操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Create,
+    Update,
+    UpdateDetails,
+    UpdateParent,
+    MoveList,
+    Reorder,
+    Delete,
+}
+
+impl Operation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Create => "create",
+            Operation::Update => "update",
+            Operation::UpdateDetails => "update_details",
+            Operation::UpdateParent => "update_parent",
+            Operation::MoveList => "move_list",
+            Operation::Reorder => "reorder",
+            Operation::Delete => "delete",
+        }
+    }
+}
+
+/// 撤销/重做的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryChange {
+    pub batch_id: String,
+    pub operation: String,
+    /// 受影响的待办 ID
+    pub todo_ids: Vec<i32>,
+}
+
+/// 当前是否可以撤销/重做
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryStatus {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// 生成新的批次 ID（一次用户操作对应一个批次）
+pub fn new_batch_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// 记录一个待办在一次操作前后的快照
+///
+/// 新操作会清空重做栈
+pub async fn record<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    operation: Operation,
+    before: Option<&entity::Model>,
+    after: Option<&entity::Model>,
+) -> Result<()> {
+    let Some(todo_id) = after.or(before).map(|model| model.id) else {
+        return Ok(());
+    };
+
+    todo_journal::Entity::delete_many()
+        .filter(todo_journal::Column::Undone.eq(true))
+        .exec(db)
+        .await
+        .context("failed to clear redo history")?;
+
+    todo_journal::ActiveModel {
+        batch_id: Set(batch_id.to_string()),
+        operation: Set(operation.as_str().to_string()),
+        todo_id: Set(todo_id),
+        before_state: Set(before.map(snapshot).transpose()?),
+        after_state: Set(after.map(snapshot).transpose()?),
+        undone: Set(false),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .with_context(|| format!("failed to record journal entry for todo {todo_id}"))?;
+
+    Ok(())
+}
+
+/// 撤销最近一次操作，没有可撤销的操作时返回 None
+pub async fn undo(db: &DatabaseConnection) -> Result<Option<HistoryChange>> {
+    let latest = todo_journal::Entity::find()
+        .filter(todo_journal::Column::Undone.eq(false))
+        .order_by_desc(todo_journal::Column::Id)
+        .one(db)
+        .await
+        .context("failed to load journal")?;
+
+    match latest {
+        Some(entry) => apply_batch(db, &entry.batch_id, Direction::Undo)
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 重做最近一次被撤销的操作，没有可重做的操作时返回 None
+pub async fn redo(db: &DatabaseConnection) -> Result<Option<HistoryChange>> {
+    let earliest_undone = todo_journal::Entity::find()
+        .filter(todo_journal::Column::Undone.eq(true))
+        .order_by_asc(todo_journal::Column::Id)
+        .one(db)
+        .await
+        .context("failed to load journal")?;
+
+    match earliest_undone {
+        Some(entry) => apply_batch(db, &entry.batch_id, Direction::Redo)
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 查询是否可以撤销/重做
+pub async fn status(db: &DatabaseConnection) -> Result<HistoryStatus> {
+    let can_undo = todo_journal::Entity::find()
+        .filter(todo_journal::Column::Undone.eq(false))
+        .one(db)
+        .await
+        .context("failed to load journal")?
+        .is_some();
+    let can_redo = todo_journal::Entity::find()
+        .filter(todo_journal::Column::Undone.eq(true))
+        .one(db)
+        .await
+        .context("failed to load journal")?
+        .is_some();
+
+    Ok(HistoryStatus { can_undo, can_redo })
+}
+
+/// 清理超出保留数量的旧批次并提交事务
+pub async fn commit(txn: DatabaseTransaction) -> Result<()> {
+    prune(&txn).await?;
+    txn.commit()
+        .await
+        .context("failed to commit todo transaction")?;
+    Ok(())
+}
+
+/// 只保留最近的若干批次
+async fn prune<C: ConnectionTrait>(db: &C) -> Result<()> {
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        r#"
+        DELETE FROM todo_journal
+        WHERE batch_id NOT IN (
+            SELECT batch_id FROM todo_journal
+            GROUP BY batch_id
+            ORDER BY MAX(id) DESC
+            LIMIT ?
+        )
+        "#,
+        [MAX_JOURNAL_BATCHES.into()],
+    ))
+    .await
+    .context("failed to prune todo journal")?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+async fn apply_batch(
+    db: &DatabaseConnection,
+    batch_id: &str,
+    direction: Direction,
+) -> Result<HistoryChange> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start journal transaction")?;
+    let now = Utc::now();
+
+    let mut entries = todo_journal::Entity::find()
+        .filter(todo_journal::Column::BatchId.eq(batch_id))
+        .order_by_asc(todo_journal::Column::Id)
+        .all(&txn)
+        .await
+        .with_context(|| format!("failed to load journal batch {batch_id}"))?;
+
+    let operation = entries
+        .first()
+        .map(|entry| entry.operation.clone())
+        .unwrap_or_default();
+
+    // 撤销按记录的逆序恢复，重做按原顺序重放，保证父任务先于子任务存在
+    if direction == Direction::Undo {
+        entries.reverse();
+    }
+    let mut todo_ids = Vec::new();
+
+    for entry in entries {
+        let state = match direction {
+            Direction::Undo => entry.before_state.as_deref(),
+            Direction::Redo => entry.after_state.as_deref(),
+        };
+        let state = state
+            .map(serde_json::from_str::<entity::Model>)
+            .transpose()
+            .with_context(|| format!("invalid journal snapshot {}", entry.id))?;
+
+        apply_state(&txn, entry.todo_id, state, now).await?;
+
+        if !todo_ids.contains(&entry.todo_id) {
+            todo_ids.push(entry.todo_id);
+        }
+
+        let entry_id = entry.id;
+        let mut active: todo_journal::ActiveModel = entry.into();
+        active.undone = Set(direction == Direction::Undo);
+        active
+            .update(&txn)
+            .await
+            .with_context(|| format!("failed to update journal entry {entry_id}"))?;
+    }
+
+    txn.commit()
+        .await
+        .context("failed to commit journal transaction")?;
+
+    Ok(HistoryChange {
+        batch_id: batch_id.to_string(),
+        operation,
+        todo_ids,
+    })
+}
+
+/// 将待办恢复到快照状态
+///
+/// - 快照为空（该待办在此状态下不存在）时移入回收站，以便同步删除远端
+/// - 同步相关字段保留当前值，并标记 dirty，由下一次同步推送到 CalDAV
+/// - 提醒不记录在日志中，按恢复后的时间和 reminder_offset_minutes 更新（与修改详情时相同）
+async fn apply_state<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    state: Option<entity::Model>,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let current = entity::Entity::find_by_id(todo_id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {todo_id}"))?;
+
+    let Some(snapshot) = state else {
+        if let Some(current) = current.filter(|model| model.deleted_at.is_none()) {
+            let mut active: entity::ActiveModel = current.into();
+            active.deleted_at = Set(Some(now));
+            active.dirty = Set(true);
+            active.updated_at = Set(now);
+            active
+                .update(db)
+                .await
+                .with_context(|| format!("failed to move todo {todo_id} to trash"))?;
+        }
+        return Ok(());
+    };
+
+    // 父任务已不存在时改为顶层任务，避免外键约束失败
+    let mut parent_id = snapshot.parent_id;
+    if let Some(pid) = parent_id {
+        let parent_exists = entity::Entity::find_by_id(pid)
+            .one(db)
+            .await
+            .with_context(|| format!("failed to load parent todo {pid}"))?
+            .is_some();
+        if !parent_exists {
+            parent_id = None;
+        }
+    }
+
+    // 清单已删除时移出清单
+    let mut list_id = snapshot.list_id;
+    if let Some(lid) = list_id {
+        let list_exists = todo_list::Entity::find_by_id(lid)
+            .one(db)
+            .await
+            .with_context(|| format!("failed to load todo list {lid}"))?
+            .is_some();
+        if !list_exists {
+            list_id = None;
+        }
+    }
+
+    let exists = current.is_some();
+    // 开始/截止时间变化后重新允许提醒触发；reminder_offset_minutes 变化或重新创建时同步到提醒
+    let times_changed = current.as_ref().is_some_and(|current| {
+        current.due_date != snapshot.due_date || current.start_at != snapshot.start_at
+    });
+    let offset_changed = current
+        .as_ref()
+        .is_none_or(|current| current.reminder_offset_minutes != snapshot.reminder_offset_minutes);
+    let reminder_offset = snapshot.reminder_offset_minutes;
+
+    let mut restored = snapshot;
+    if let Some(current) = current {
+        restored.remote_url = current.remote_url;
+        restored.remote_etag = current.remote_etag;
+        restored.remote_calendar_url = current.remote_calendar_url;
+        restored.sync_token = current.sync_token;
+        restored.last_synced_at = current.last_synced_at;
+    }
+    restored.parent_id = parent_id;
+    restored.list_id = list_id;
+    restored.purged_at = None;
+    restored.dirty = true;
+    restored.last_modified_at = now;
+    restored.updated_at = now;
+
+    let active = restored.into_active_model().reset_all();
+
+    if exists {
+        active
+            .update(db)
+            .await
+            .with_context(|| format!("failed to restore todo {todo_id} from journal"))?;
+    } else {
+        entity::Entity::insert(active)
+            .exec(db)
+            .await
+            .with_context(|| format!("failed to re-create todo {todo_id} from journal"))?;
+    }
+
+    if times_changed {
+        reminders::reset_in(db, todo_id).await?;
+    }
+    if offset_changed {
+        reminders::set_due_offset_in(db, todo_id, reminder_offset).await?;
+    }
+
+    Ok(())
+}
+
+fn snapshot(model: &entity::Model) -> Result<String> {
+    serde_json::to_string(model).context("failed to serialize todo snapshot")
+}
@@ -0,0 +1,336 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait,
+    DatabaseBackend, DatabaseConnection, EntityTrait, QueryFilter, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::journal::{self, Operation};
+use super::models::Todo;
+use super::service;
+use super::tags;
+use crate::features::todo::data::{entities::todo_list, entity};
+
+/// 清单及其待办数量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoList {
+    pub id: i32,
+    pub name: String,
+    pub color: Option<String>,
+    /// 对应的 CalDAV 日历集合，为空时使用默认日历
+    pub calendar_url: Option<String>,
+    /// 清单中未删除的待办数量
+    pub todo_count: i64,
+}
+
+/// 创建/修改清单的参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TodoListInput {
+    pub name: String,
+    pub color: Option<String>,
+    pub calendar_url: Option<String>,
+}
+
+/// 列出所有清单（按名称排序）
+pub async fn list_lists(db: &DatabaseConnection) -> Result<Vec<TodoList>> {
+    load_summaries(db, None).await
+}
+
+/// 创建清单
+pub async fn create_list(db: &DatabaseConnection, input: TodoListInput) -> Result<TodoList> {
+    let input = validate(db, None, input).await?;
+    let now = Utc::now();
+
+    let list = todo_list::ActiveModel {
+        name: Set(input.name),
+        color: Set(input.color),
+        calendar_url: Set(input.calendar_url),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .context("failed to create todo list")?;
+
+    load_summary(db, list.id).await
+}
+
+/// 修改清单
+///
+/// 日历映射改变时，清单中已同步的待办标记为 dirty，
+/// 下次同步时从原日历删除并在新日历中创建
+pub async fn update_list(
+    db: &DatabaseConnection,
+    id: i32,
+    input: TodoListInput,
+) -> Result<TodoList> {
+    let input = validate(db, Some(id), input).await?;
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start todo list transaction")?;
+    let list = load_list(&txn, id).await?;
+    let calendar_changed = list.calendar_url != input.calendar_url;
+    let now = Utc::now();
+
+    let mut active: todo_list::ActiveModel = list.into();
+    active.name = Set(input.name);
+    active.color = Set(input.color);
+    active.calendar_url = Set(input.calendar_url);
+    active.updated_at = Set(now);
+    active
+        .update(&txn)
+        .await
+        .with_context(|| format!("failed to update todo list {id}"))?;
+
+    if calendar_changed {
+        mark_synced_dirty(&txn, id).await?;
+    }
+
+    txn.commit()
+        .await
+        .context("failed to commit todo list transaction")?;
+
+    load_summary(db, id).await
+}
+
+/// 删除清单，其中的待办移出清单（不会删除待办）
+pub async fn delete_list(db: &DatabaseConnection, id: i32) -> Result<()> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start todo list transaction")?;
+    let list = load_list(&txn, id).await?;
+
+    // 映射了独立日历的清单，其中的待办改为同步到默认日历
+    if list.calendar_url.is_some() {
+        mark_synced_dirty(&txn, id).await?;
+    }
+
+    entity::Entity::update_many()
+        .col_expr(entity::Column::ListId, Expr::value(Option::<i32>::None))
+        .filter(entity::Column::ListId.eq(id))
+        .exec(&txn)
+        .await
+        .with_context(|| format!("failed to detach todos from list {id}"))?;
+
+    todo_list::Entity::delete_by_id(id)
+        .exec(&txn)
+        .await
+        .with_context(|| format!("failed to delete todo list {id}"))?;
+
+    txn.commit()
+        .await
+        .context("failed to commit todo list transaction")?;
+
+    Ok(())
+}
+
+/// 把待办（连同子任务）移到指定清单，None 表示移出清单
+pub async fn move_todo(db: &DatabaseConnection, id: i32, list_id: Option<i32>) -> Result<Todo> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start move transaction")?;
+    let todo = move_todo_in(&txn, &journal::new_batch_id(), id, list_id).await?;
+    journal::commit(txn).await?;
+
+    Ok(todo)
+}
+
+/// 在给定连接中移动待办到清单，并记录到操作日志的指定批次
+///
+/// 子任务与父任务同步到同一个日历，因此只能移动顶层待办
+pub(crate) async fn move_todo_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    list_id: Option<i32>,
+) -> Result<Todo> {
+    if let Some(list_id) = list_id {
+        load_list(db, list_id).await?;
+    }
+
+    let model = entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
+        .ok_or_else(|| anyhow!("todo {id} not found"))?;
+
+    if model.parent_id.is_some() {
+        bail!("subtask {id} follows its parent's list, move the parent instead");
+    }
+
+    let updated = assign_list(db, batch_id, model, list_id).await?;
+    move_descendants_in(db, batch_id, id, list_id).await?;
+
+    Ok(updated.into())
+}
+
+/// 让待办的所有后代使用给定清单（用于移动或调整父任务后）
+pub(crate) async fn move_descendants_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    list_id: Option<i32>,
+) -> Result<()> {
+    for descendant in service::load_descendants(db, id).await? {
+        if descendant.list_id != list_id {
+            assign_list(db, batch_id, descendant, list_id).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn assign_list<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: entity::Model,
+    list_id: Option<i32>,
+) -> Result<entity::Model> {
+    let id = model.id;
+    let now = Utc::now();
+    let before = model.clone();
+
+    let mut active: entity::ActiveModel = model.into();
+    active.list_id = Set(list_id);
+    active.dirty = Set(true);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+
+    let updated = active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to move todo {id} to another list"))?;
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::MoveList,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    Ok(updated)
+}
+
+/// 把清单中已同步的待办标记为 dirty，以便同步时迁移到新的日历
+async fn mark_synced_dirty<C: ConnectionTrait>(db: &C, list_id: i32) -> Result<()> {
+    let now = Utc::now();
+    entity::Entity::update_many()
+        .col_expr(entity::Column::Dirty, Expr::value(true))
+        .col_expr(entity::Column::LastModifiedAt, Expr::value(now))
+        .col_expr(entity::Column::UpdatedAt, Expr::value(now))
+        .filter(entity::Column::ListId.eq(list_id))
+        .filter(entity::Column::RemoteUrl.is_not_null())
+        .filter(entity::Column::DeletedAt.is_null())
+        .exec(db)
+        .await
+        .with_context(|| format!("failed to mark todos of list {list_id} for sync"))?;
+    Ok(())
+}
+
+/// 校验并规范化清单参数
+async fn validate(
+    db: &DatabaseConnection,
+    id: Option<i32>,
+    input: TodoListInput,
+) -> Result<TodoListInput> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        bail!("list name cannot be empty");
+    }
+
+    let color = input
+        .color
+        .map(|color| color.trim().to_ascii_lowercase())
+        .filter(|color| !color.is_empty());
+    if let Some(color) = &color {
+        if !tags::is_hex_color(color) {
+            bail!("invalid colour \"{color}\", expected #RRGGBB");
+        }
+    }
+
+    let calendar_url = input
+        .calendar_url
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &calendar_url {
+        if !(url.starts_with("http://") || url.starts_with("https://") || url.starts_with('/')) {
+            bail!("invalid calendar URL \"{url}\", expected an http(s) URL or an absolute path");
+        }
+    }
+
+    for other in todo_list::Entity::find()
+        .all(db)
+        .await
+        .context("failed to load todo lists")?
+    {
+        if Some(other.id) == id {
+            continue;
+        }
+        if other.name.eq_ignore_ascii_case(&name) {
+            bail!("list \"{}\" already exists", other.name);
+        }
+        if calendar_url.is_some() && other.calendar_url == calendar_url {
+            bail!("calendar is already mapped to list \"{}\"", other.name);
+        }
+    }
+
+    Ok(TodoListInput {
+        name,
+        color,
+        calendar_url,
+    })
+}
+
+async fn load_list<C: ConnectionTrait>(db: &C, id: i32) -> Result<todo_list::Model> {
+    todo_list::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo list {id}"))?
+        .ok_or_else(|| anyhow!("todo list {id} not found"))
+}
+
+async fn load_summary<C: ConnectionTrait>(db: &C, id: i32) -> Result<TodoList> {
+    load_summaries(db, Some(id))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("todo list {id} not found"))
+}
+
+async fn load_summaries<C: ConnectionTrait>(db: &C, id: Option<i32>) -> Result<Vec<TodoList>> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            r#"
+            SELECT todo_lists.id AS id, todo_lists.name AS name, todo_lists.color AS color,
+                   todo_lists.calendar_url AS calendar_url, COUNT(todos.id) AS todo_count
+            FROM todo_lists
+            LEFT JOIN todos ON todos.list_id = todo_lists.id AND todos.deleted_at IS NULL
+            WHERE ? IS NULL OR todo_lists.id = ?
+            GROUP BY todo_lists.id
+            ORDER BY todo_lists.name COLLATE NOCASE
+            "#,
+            [id.into(), id.into()],
+        ))
+        .await
+        .context("failed to load todo lists")?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(TodoList {
+                id: row.try_get("", "id")?,
+                name: row.try_get("", "name")?,
+                color: row.try_get("", "color")?,
+                calendar_url: row.try_get("", "calendar_url")?,
+                todo_count: row.try_get("", "todo_count")?,
+            })
+        })
+        .collect()
+}
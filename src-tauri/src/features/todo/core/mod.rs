@@ -2,6 +2,20 @@
 //
 // 负责业务逻辑实现：CRUD 操作、业务规则、调度任务
 
+pub mod bulk;
+pub mod dependencies;
+pub mod journal;
+pub mod lists;
 pub mod models;
+pub mod ordering;
+pub mod query;
+pub mod quick_add;
+pub mod recurrence;
+pub mod reminders;
 pub mod scheduler;
+pub mod search;
 pub mod service;
+pub mod subtasks;
+pub mod tags;
+pub mod trash;
+pub mod tree;
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 pub struct Todo {
     pub id: i32,
     pub parent_id: Option<i32>,
+    pub list_id: Option<i32>,
+    pub sort_order: Option<i64>,
     pub uid: String,
     pub title: String,
     pub description: Option<String>,
@@ -22,6 +24,8 @@ pub struct Todo {
     pub timezone: Option<String>,
     pub reminder_method: Option<String>,
     pub reminder_last_triggered_at: Option<String>,
+    pub snoozed_until: Option<String>,
+    pub estimated_pomodoros: Option<i32>,
     pub completed_at: Option<String>,
     pub notified: bool,
     pub dirty: bool,
@@ -30,6 +34,7 @@ pub struct Todo {
     pub remote_calendar_url: Option<String>,
     pub sync_token: Option<String>,
     pub last_synced_at: Option<String>,
+    pub deleted_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -45,6 +50,8 @@ impl From<entity::Model> for Todo {
         Self {
             id: model.id,
             parent_id: model.parent_id,
+            list_id: model.list_id,
+            sort_order: model.sort_order,
             uid: model.uid,
             title: model.title,
             description: model.description,
@@ -62,6 +69,8 @@ impl From<entity::Model> for Todo {
             timezone: model.timezone,
             reminder_method: model.reminder_method,
             reminder_last_triggered_at: model.reminder_last_triggered_at.map(|d| d.to_rfc3339()),
+            snoozed_until: model.snoozed_until.map(|d| d.to_rfc3339()),
+            estimated_pomodoros: model.estimated_pomodoros,
             completed_at: model.completed_at.map(|d| d.to_rfc3339()),
             notified: model.notified,
             dirty: model.dirty,
@@ -70,6 +79,7 @@ impl From<entity::Model> for Todo {
             remote_calendar_url: model.remote_calendar_url,
             sync_token: model.sync_token,
             last_synced_at: model.last_synced_at.map(|d| d.to_rfc3339()),
+            deleted_at: model.deleted_at.map(|d| d.to_rfc3339()),
             created_at: model.created_at.to_rfc3339(),
             updated_at: model.updated_at.to_rfc3339(),
         }
@@ -0,0 +1,212 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::NullOrdering, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait,
+    DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder, Select, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::journal::{self, Operation};
+use super::lists;
+use super::models::Todo;
+use super::service;
+use crate::features::todo::data::entity;
+
+/// 重新编号时相邻位置的间隔，留出空间以便之后在中间插入
+const SORT_ORDER_STEP: i64 = 1024;
+
+/// 待办移动到的位置
+///
+/// JSON 形式：`{"before": 5}`、`{"after": 5}` 或 `{"end": {"parent_id": 3}}`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReorderPosition {
+    /// 放在该同级待办之前
+    Before(i32),
+    /// 放在该同级待办之后
+    After(i32),
+    /// 放在该父任务（为空表示顶层）的最后
+    End { parent_id: Option<i32> },
+}
+
+/// 移动待办到指定位置
+///
+/// 参照待办与当前待办不在同一父任务下时，先移到参照待办的父任务下（与 `update_parent` 相同的循环检查）；
+/// 顶层待办还会移到参照待办所在的清单。整个移动作为一次操作记录到操作日志。
+pub async fn reorder(db: &DatabaseConnection, id: i32, position: ReorderPosition) -> Result<Todo> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start reorder transaction")?;
+    let todo = reorder_in(&txn, &journal::new_batch_id(), id, position).await?;
+    journal::commit(txn).await?;
+
+    Ok(todo)
+}
+
+/// 在给定连接中移动待办，并记录到操作日志的指定批次
+pub(crate) async fn reorder_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    position: ReorderPosition,
+) -> Result<Todo> {
+    let model = load_active(db, id).await?;
+
+    let anchor = match position {
+        ReorderPosition::Before(anchor_id) | ReorderPosition::After(anchor_id) => {
+            if anchor_id == id {
+                bail!("A task cannot be placed relative to itself");
+            }
+            Some(load_active(db, anchor_id).await?)
+        }
+        ReorderPosition::End { .. } => None,
+    };
+
+    let parent_id = match (&anchor, position) {
+        (Some(anchor), _) => anchor.parent_id,
+        (None, ReorderPosition::End { parent_id }) => parent_id,
+        (None, _) => model.parent_id,
+    };
+
+    if model.parent_id != parent_id {
+        service::update_parent_in(db, batch_id, id, parent_id).await?;
+    }
+
+    // 顶层待办按清单分别排序，移到其他清单中的待办旁边时一起移动清单
+    if let Some(anchor) = anchor.as_ref().filter(|anchor| anchor.parent_id.is_none()) {
+        let current = load_active(db, id).await?;
+        if current.list_id != anchor.list_id {
+            lists::move_todo_in(db, batch_id, id, anchor.list_id).await?;
+        }
+    }
+
+    let model = load_active(db, id).await?;
+    let siblings = sibling_query(&model)
+        .filter(entity::Column::Id.ne(id))
+        .all(db)
+        .await
+        .with_context(|| format!("failed to load siblings of todo {id}"))?;
+
+    let index = match position {
+        ReorderPosition::Before(anchor_id) => sibling_index(&siblings, anchor_id)?,
+        ReorderPosition::After(anchor_id) => sibling_index(&siblings, anchor_id)? + 1,
+        ReorderPosition::End { .. } => siblings.len(),
+    };
+
+    let previous = index.checked_sub(1).map(|i| siblings[i].sort_order);
+    let next = siblings.get(index).map(|sibling| sibling.sort_order);
+
+    let updated = match position_between(previous, next) {
+        Some(sort_order) => set_sort_order(db, batch_id, model, sort_order).await?,
+        None => {
+            // 没有可用的间隔或同级中有未排序的待办：按新顺序重新编号
+            let mut ordered = siblings;
+            ordered.insert(index, model);
+
+            let mut updated = None;
+            for (i, sibling) in ordered.into_iter().enumerate() {
+                let sort_order = (i as i64 + 1) * SORT_ORDER_STEP;
+                let sibling_id = sibling.id;
+                let result = if sibling.sort_order == Some(sort_order) {
+                    sibling
+                } else {
+                    set_sort_order(db, batch_id, sibling, sort_order).await?
+                };
+                if sibling_id == id {
+                    updated = Some(result);
+                }
+            }
+            updated.ok_or_else(|| anyhow!("todo {id} not found"))?
+        }
+    };
+
+    Ok(updated.into())
+}
+
+/// 按手动顺序排列的查询（未排序的待办排在最后，按创建顺序）
+pub(crate) fn order_by_position(select: Select<entity::Entity>) -> Select<entity::Entity> {
+    select
+        .order_by_with_nulls(entity::Column::SortOrder, Order::Asc, NullOrdering::Last)
+        .order_by_asc(entity::Column::CreatedAt)
+        .order_by_asc(entity::Column::Id)
+}
+
+/// 与给定待办同级的待办：同一父任务的子任务，顶层待办则为同一清单中的顶层待办
+fn sibling_query(model: &entity::Model) -> Select<entity::Entity> {
+    let select = entity::Entity::find().filter(entity::Column::DeletedAt.is_null());
+    let select = match (model.parent_id, model.list_id) {
+        (Some(parent_id), _) => select.filter(entity::Column::ParentId.eq(parent_id)),
+        (None, Some(list_id)) => select
+            .filter(entity::Column::ParentId.is_null())
+            .filter(entity::Column::ListId.eq(list_id)),
+        (None, None) => select
+            .filter(entity::Column::ParentId.is_null())
+            .filter(entity::Column::ListId.is_null()),
+    };
+    order_by_position(select)
+}
+
+fn sibling_index(siblings: &[entity::Model], anchor_id: i32) -> Result<usize> {
+    siblings
+        .iter()
+        .position(|sibling| sibling.id == anchor_id)
+        .ok_or_else(|| anyhow!("todo {anchor_id} is not a sibling"))
+}
+
+/// 计算插入到两个位置之间的新位置
+///
+/// 参数为 None 表示该侧没有同级待办；同级待办未排序或没有间隔时返回 None
+fn position_between(previous: Option<Option<i64>>, next: Option<Option<i64>>) -> Option<i64> {
+    match (previous, next) {
+        (None, None) => Some(SORT_ORDER_STEP),
+        (Some(Some(previous)), None) => previous.checked_add(SORT_ORDER_STEP),
+        (None, Some(Some(next))) => next.checked_sub(SORT_ORDER_STEP),
+        (Some(Some(previous)), Some(Some(next))) if next - previous > 1 => {
+            Some(previous + (next - previous) / 2)
+        }
+        _ => None,
+    }
+}
+
+async fn set_sort_order<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: entity::Model,
+    sort_order: i64,
+) -> Result<entity::Model> {
+    let id = model.id;
+    let now = Utc::now();
+    let before = model.clone();
+
+    let mut active: entity::ActiveModel = model.into();
+    active.sort_order = Set(Some(sort_order));
+    active.dirty = Set(true);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+
+    let updated = active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to reorder todo {id}"))?;
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::Reorder,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    Ok(updated)
+}
+
+async fn load_active<C: ConnectionTrait>(db: &C, id: i32) -> Result<entity::Model> {
+    entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
+        .ok_or_else(|| anyhow!("todo {id} not found"))
+}
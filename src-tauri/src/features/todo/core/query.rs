@@ -0,0 +1,374 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use sea_orm::{
+    sea_query::{Expr, LikeExpr, NullOrdering},
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder,
+    QuerySelect, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use super::dependencies;
+use super::models::Todo;
+use super::service::get_local_timezone;
+use crate::features::todo::data::entity;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+/// 截止时间窗口
+///
+/// JSON 形式：`"overdue"`、`"today"`、`"unscheduled"` 或 `{"next_days": 7}`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DueWindow {
+    /// 已过期且未完成
+    Overdue,
+    /// 截止时间在本地时区的今天
+    Today,
+    /// 从现在起到第 N 天（本地时区）结束前截止
+    NextDays(u32),
+    /// 没有截止时间
+    Unscheduled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoSortField {
+    DueDate,
+    Priority,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    /// 手动排序位置（`todo.reorder`）
+    Position,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// 待办查询条件（所有字段可选，组合为 AND）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TodoQuery {
+    /// 按 VTODO STATUS 过滤，例如 `["NEEDS-ACTION", "IN-PROCESS"]`
+    pub status: Vec<String>,
+    pub completed: Option<bool>,
+    /// 必须同时包含的标签（不区分大小写）
+    pub tags: Vec<String>,
+    pub priority_min: Option<i32>,
+    pub priority_max: Option<i32>,
+    pub due: Option<DueWindow>,
+    pub parent_id: Option<i32>,
+    /// 只返回该清单中的待办
+    pub list_id: Option<i32>,
+    /// 只返回顶层待办
+    pub root_only: bool,
+    /// `false` 排除被未完成前置待办阻塞的待办，`true` 只返回被阻塞的
+    pub blocked: Option<bool>,
+    /// 在标题、描述、地点中模糊匹配
+    pub text: Option<String>,
+    pub sort: TodoSortField,
+    pub direction: SortDirection,
+    pub limit: Option<u64>,
+    /// 上一页返回的 next_cursor
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoPage {
+    pub items: Vec<Todo>,
+    /// 还有更多结果时返回，用作下一次查询的 cursor
+    pub next_cursor: Option<String>,
+}
+
+/// 按条件查询待办（过滤、排序、分页均在 SQL 中完成）
+///
+/// 分页使用键集游标：cursor 携带上一页最后一条的排序键和 ID，
+/// 排序键为空的记录始终排在最后，ID 作为同值时的次序。
+pub async fn query_todos(db: &DatabaseConnection, query: &TodoQuery) -> Result<TodoPage> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let mut select = entity::Entity::find()
+        .filter(entity::Column::DeletedAt.is_null())
+        .filter(build_condition(query));
+
+    if let Some(cursor) = query.cursor.as_deref() {
+        select = select.filter(cursor_condition(query, cursor)?);
+    }
+
+    let order = match query.direction {
+        SortDirection::Asc => Order::Asc,
+        SortDirection::Desc => Order::Desc,
+    };
+
+    let mut rows = select
+        .order_by_with_nulls(sort_column(query.sort), order, NullOrdering::Last)
+        .order_by_asc(entity::Column::Id)
+        .limit(limit + 1)
+        .all(db)
+        .await
+        .context("failed to query todos")?;
+
+    let next_cursor = if rows.len() as u64 > limit {
+        rows.truncate(limit as usize);
+        rows.last()
+            .map(|row| CursorToken::from_row(query, row).encode())
+    } else {
+        None
+    };
+
+    Ok(TodoPage {
+        items: rows.into_iter().map(Into::into).collect(),
+        next_cursor,
+    })
+}
+
+fn build_condition(query: &TodoQuery) -> Condition {
+    let mut condition = Condition::all();
+
+    if !query.status.is_empty() {
+        let statuses: Vec<String> = query
+            .status
+            .iter()
+            .map(|status| status.trim().to_ascii_uppercase())
+            .collect();
+        condition = condition.add(entity::Column::Status.is_in(statuses));
+    }
+
+    if let Some(completed) = query.completed {
+        condition = condition.add(entity::Column::Completed.eq(completed));
+    }
+
+    for tag in query
+        .tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+    {
+        // tags 列是 JSON 数组文本，使用 json_each 展开匹配
+        condition = condition.add(Expr::cust_with_values(
+            "EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid(todos.tags) THEN todos.tags ELSE '[]' END) WHERE lower(json_each.value) = lower(?))",
+            [tag.to_string()],
+        ));
+    }
+
+    if let Some(min) = query.priority_min {
+        condition = condition.add(entity::Column::Priority.gte(min));
+    }
+    if let Some(max) = query.priority_max {
+        condition = condition.add(entity::Column::Priority.lte(max));
+    }
+
+    if let Some(window) = query.due {
+        condition = condition.add(due_condition(window, Utc::now()));
+    }
+
+    if let Some(parent_id) = query.parent_id {
+        condition = condition.add(entity::Column::ParentId.eq(parent_id));
+    } else if query.root_only {
+        condition = condition.add(entity::Column::ParentId.is_null());
+    }
+
+    if let Some(list_id) = query.list_id {
+        condition = condition.add(entity::Column::ListId.eq(list_id));
+    }
+
+    match query.blocked {
+        Some(true) => condition = condition.add(dependencies::blocked_expr()),
+        Some(false) => condition = condition.add(dependencies::blocked_expr().not()),
+        None => {}
+    }
+
+    if let Some(text) = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+    {
+        let pattern = format!("%{}%", escape_like(text));
+        condition = condition.add(
+            Condition::any()
+                .add(Expr::col(entity::Column::Title).like(LikeExpr::new(&pattern).escape('\\')))
+                .add(
+                    Expr::col(entity::Column::Description)
+                        .like(LikeExpr::new(&pattern).escape('\\')),
+                )
+                .add(
+                    Expr::col(entity::Column::Location).like(LikeExpr::new(&pattern).escape('\\')),
+                ),
+        );
+    }
+
+    condition
+}
+
+fn due_condition(window: DueWindow, now: DateTime<Utc>) -> Condition {
+    match window {
+        DueWindow::Overdue => Condition::all()
+            .add(entity::Column::DueDate.lt(now))
+            .add(entity::Column::Completed.eq(false)),
+        DueWindow::Today => {
+            let (start, end) = local_day_bounds(now, 0);
+            Condition::all()
+                .add(entity::Column::DueDate.gte(start))
+                .add(entity::Column::DueDate.lt(end))
+        }
+        DueWindow::NextDays(days) => {
+            let (_, end) = local_day_bounds(now, days);
+            Condition::all()
+                .add(entity::Column::DueDate.gte(now))
+                .add(entity::Column::DueDate.lt(end))
+        }
+        DueWindow::Unscheduled => Condition::all().add(entity::Column::DueDate.is_null()),
+    }
+}
+
+/// 本地时区中“今天”的开始，以及第 `days_ahead` 天结束时对应的 UTC 时间
+fn local_day_bounds(now: DateTime<Utc>, days_ahead: u32) -> (DateTime<Utc>, DateTime<Utc>) {
+    let tz: Tz = get_local_timezone().parse().unwrap_or(Tz::UTC);
+    let today = now.with_timezone(&tz).date_naive();
+    let to_utc = |date: chrono::NaiveDate| {
+        let local = date.and_time(NaiveTime::MIN);
+        tz.from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                tz.from_local_datetime(&(local + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| DateTime::<Utc>::from_naive_utc_and_offset(local, Utc))
+    };
+
+    let start = to_utc(today);
+    let end = to_utc(today + Duration::days(days_ahead as i64 + 1));
+    (start, end)
+}
+
+/// 分页游标：上一页最后一条记录的排序键和 ID
+///
+/// 对外是不透明的十六进制字符串，记录本身之后被修改或清除不影响翻页。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CursorToken {
+    sort: TodoSortField,
+    direction: SortDirection,
+    /// 排序键为空时为 None
+    value: Option<CursorValue>,
+    id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CursorValue {
+    Time(DateTime<Utc>),
+    Int(i64),
+    Text(String),
+}
+
+impl CursorToken {
+    fn from_row(query: &TodoQuery, row: &entity::Model) -> Self {
+        let value = match query.sort {
+            TodoSortField::DueDate => row.due_date.map(CursorValue::Time),
+            TodoSortField::Priority => row.priority.map(|p| CursorValue::Int(p as i64)),
+            TodoSortField::CreatedAt => Some(CursorValue::Time(row.created_at)),
+            TodoSortField::UpdatedAt => Some(CursorValue::Time(row.updated_at)),
+            TodoSortField::Title => Some(CursorValue::Text(row.title.clone())),
+            TodoSortField::Position => row.sort_order.map(CursorValue::Int),
+        };
+        Self {
+            sort: query.sort,
+            direction: query.direction,
+            value,
+            id: row.id,
+        }
+    }
+
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        json.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        let invalid = || anyhow!("invalid cursor: {cursor}");
+        let cursor = cursor.trim();
+        if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+            return Err(invalid());
+        }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+        serde_json::from_slice(&bytes).map_err(|_| invalid())
+    }
+
+    fn value(&self) -> Option<Value> {
+        match self.value.clone()? {
+            CursorValue::Time(time) => Some(time.into()),
+            // 优先级列是 INTEGER，按 i32 比较
+            CursorValue::Int(value) if self.sort == TodoSortField::Priority => {
+                Some((value as i32).into())
+            }
+            CursorValue::Int(value) => Some(value.into()),
+            CursorValue::Text(text) => Some(text.into()),
+        }
+    }
+}
+
+/// 根据游标携带的排序键，构造“位于其后”的条件
+fn cursor_condition(query: &TodoQuery, cursor: &str) -> Result<Condition> {
+    let token = CursorToken::decode(cursor)?;
+    if token.sort != query.sort || token.direction != query.direction {
+        bail!("invalid cursor: sort order changed since the previous page");
+    }
+
+    let column = sort_column(query.sort);
+    let condition = match token.value() {
+        // 游标已进入空值区：只剩同样为空且 ID 更大的记录
+        None => Condition::all()
+            .add(column.is_null())
+            .add(entity::Column::Id.gt(token.id)),
+        Some(value) => {
+            let beyond = match query.direction {
+                SortDirection::Asc => column.gt(value.clone()),
+                SortDirection::Desc => column.lt(value.clone()),
+            };
+            Condition::any()
+                .add(beyond)
+                .add(
+                    Condition::all()
+                        .add(column.eq(value))
+                        .add(entity::Column::Id.gt(token.id)),
+                )
+                .add(column.is_null())
+        }
+    };
+
+    Ok(condition)
+}
+
+fn sort_column(field: TodoSortField) -> entity::Column {
+    match field {
+        TodoSortField::DueDate => entity::Column::DueDate,
+        TodoSortField::Priority => entity::Column::Priority,
+        TodoSortField::CreatedAt => entity::Column::CreatedAt,
+        TodoSortField::UpdatedAt => entity::Column::UpdatedAt,
+        TodoSortField::Title => entity::Column::Title,
+        TodoSortField::Position => entity::Column::SortOrder,
+    }
+}
+
+pub(crate) fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
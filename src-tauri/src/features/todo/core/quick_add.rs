@@ -0,0 +1,1386 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use super::journal;
+use super::models::Todo;
+use super::recurrence::resolve_timezone;
+use super::service::{self, get_local_timezone};
+
+/// 只给出日期、没有给出时间时使用的截止时间
+const DEFAULT_DUE_TIME: (u32, u32) = (9, 0);
+/// “今晚”“明晚”默认的时间
+const EVENING_TIME: (u32, u32) = (20, 0);
+
+/// 优先级（与 iCalendar PRIORITY 一致：1 最高，5 中，9 最低）
+const PRIORITY_HIGH: i32 = 1;
+const PRIORITY_MEDIUM: i32 = 5;
+const PRIORITY_LOW: i32 = 9;
+
+/// 快速添加解析出的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedTodo {
+    pub title: String,
+    pub due_date: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub priority: Option<i32>,
+    pub reminder_offset_minutes: Option<i32>,
+    pub recurrence_rule: Option<String>,
+    /// 解析相对日期所用的时区
+    pub timezone: String,
+}
+
+/// 快速添加结果；dry_run 时 todo 为空
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickAddResult {
+    pub parsed: ParsedTodo,
+    pub todo: Option<Todo>,
+}
+
+/// 解析一句话并创建待办
+///
+/// 例如 `Submit report tomorrow 17:00 #work !2 remind 30m every weekday`
+/// 或 `明天下午5点提交报告 #工作 !1 提前30分钟提醒 每个工作日`。
+/// dry_run 为 true 时只返回解析结果，不写入数据库。
+pub async fn quick_add(
+    db: &DatabaseConnection,
+    input: &str,
+    dry_run: bool,
+) -> Result<QuickAddResult> {
+    let parsed = parse(input, Utc::now(), &get_local_timezone());
+
+    if dry_run {
+        return Ok(QuickAddResult { parsed, todo: None });
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start quick add transaction")?;
+    let batch_id = journal::new_batch_id();
+
+    let created = service::create_todo_in(&txn, &batch_id, Some(parsed.title.clone())).await?;
+    let todo = service::update_todo_details_in(
+        &txn,
+        &batch_id,
+        created.id,
+        None,
+        parsed.priority,
+        None,
+        parsed.tags.clone(),
+        None,
+        parsed.due_date.map(|due| due.to_rfc3339()),
+        parsed.recurrence_rule.clone(),
+        parsed.reminder_offset_minutes,
+        created.reminder_method.clone(),
+        Some(parsed.timezone.clone()),
+    )
+    .await?;
+
+    journal::commit(txn).await?;
+
+    Ok(QuickAddResult {
+        parsed,
+        todo: Some(todo),
+    })
+}
+
+/// 解析快速添加文本
+///
+/// 无法识别的部分作为标题；相对日期按 `timezone` 中的当前日期计算。
+pub fn parse(input: &str, now: DateTime<Utc>, timezone: &str) -> ParsedTodo {
+    let tz = resolve_timezone(Some(timezone));
+    let mut parser = Parser::new(input, now, tz);
+    let title = parser.run();
+
+    ParsedTodo {
+        title,
+        due_date: parser.due_date(),
+        tags: parser.tags,
+        priority: parser.priority,
+        reminder_offset_minutes: parser.reminder,
+        recurrence_rule: parser.recurrence.as_ref().map(Recurrence::to_rule),
+        timezone: timezone.to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+    Tag(String),
+    Priority(i32),
+    Reminder(i32),
+    Recurrence(Recurrence),
+    /// 相对当前时刻的偏移（“in 2 hours”“3天后”）
+    Offset(Duration),
+    /// 日期，以及没有明确时间时使用的默认时间
+    Date(NaiveDate, Option<NaiveTime>),
+    /// 不带“下”的星期几，取今天或之后最近的一天
+    Weekday(Weekday),
+    Time(NaiveTime),
+}
+
+#[derive(Debug, Clone)]
+struct Recurrence {
+    freq: &'static str,
+    interval: u32,
+    by_day: Vec<Weekday>,
+    by_month_day: Option<u32>,
+}
+
+impl Recurrence {
+    fn new(freq: &'static str) -> Self {
+        Self {
+            freq,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: None,
+        }
+    }
+
+    fn weekdays() -> Self {
+        Self {
+            by_day: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            ..Self::new("WEEKLY")
+        }
+    }
+
+    fn weekends() -> Self {
+        Self {
+            by_day: vec![Weekday::Sat, Weekday::Sun],
+            ..Self::new("WEEKLY")
+        }
+    }
+
+    fn to_rule(&self) -> String {
+        let mut rule = format!("FREQ={}", self.freq);
+        if self.interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| weekday_code(*day)).collect();
+            rule.push_str(&format!(";BYDAY={}", days.join(",")));
+        }
+        if let Some(day) = self.by_month_day {
+            rule.push_str(&format!(";BYMONTHDAY={day}"));
+        }
+        rule
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    lower: Vec<char>,
+    now: DateTime<Utc>,
+    tz: Tz,
+    today: NaiveDate,
+    tags: Vec<String>,
+    priority: Option<i32>,
+    reminder: Option<i32>,
+    recurrence: Option<Recurrence>,
+    offset: Option<Duration>,
+    date: Option<NaiveDate>,
+    default_time: Option<NaiveTime>,
+    time: Option<NaiveTime>,
+    /// date 来自 Token::Weekday，当天已过截止时间时顺延一周
+    weekday: bool,
+}
+
+impl Parser {
+    fn new(input: &str, now: DateTime<Utc>, tz: Tz) -> Self {
+        let chars: Vec<char> = input.chars().collect();
+        let lower = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+        Self {
+            chars,
+            lower,
+            now,
+            tz,
+            today: now.with_timezone(&tz).date_naive(),
+            tags: Vec::new(),
+            priority: None,
+            reminder: None,
+            recurrence: None,
+            offset: None,
+            date: None,
+            default_time: None,
+            time: None,
+            weekday: false,
+        }
+    }
+
+    /// 扫描输入，提取所有可识别的片段，返回剩余的标题
+    fn run(&mut self) -> String {
+        let mut title = String::new();
+        let mut i = 0;
+
+        while i < self.chars.len() {
+            if let Some((end, token)) = self.match_token(i) {
+                self.apply(token);
+                title.push(' ');
+                i = end;
+            } else {
+                title.push(self.chars[i]);
+                i += 1;
+            }
+        }
+
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        title
+            .trim_matches(|c: char| c.is_whitespace() || is_separator(c))
+            .to_string()
+    }
+
+    fn apply(&mut self, token: Token) {
+        match token {
+            Token::Tag(tag) => {
+                if !self.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                    self.tags.push(tag);
+                }
+            }
+            Token::Priority(priority) => self.priority = Some(priority),
+            Token::Reminder(minutes) => self.reminder = Some(minutes),
+            Token::Recurrence(recurrence) => self.recurrence = Some(recurrence),
+            Token::Offset(offset) => self.offset = Some(offset),
+            Token::Date(date, default_time) => {
+                self.date = Some(date);
+                self.weekday = false;
+                if default_time.is_some() {
+                    self.default_time = default_time;
+                }
+            }
+            Token::Weekday(day) => {
+                self.date = Some(upcoming(self.today, day));
+                self.weekday = true;
+            }
+            Token::Time(time) => self.time = Some(time),
+        }
+    }
+
+    /// 根据解析结果计算截止时间
+    fn due_date(&self) -> Option<DateTime<Utc>> {
+        if let Some(offset) = self.offset {
+            return Some(self.now + offset);
+        }
+
+        if self.date.is_none() && self.time.is_none() && self.recurrence.is_none() {
+            return None;
+        }
+
+        let time = self
+            .time
+            .or(self.default_time)
+            .unwrap_or_else(|| hm(DEFAULT_DUE_TIME.0, DEFAULT_DUE_TIME.1));
+        let now_local = self.now.with_timezone(&self.tz).naive_local();
+
+        let date = match self.date {
+            // 星期几正好是今天但时间已过：取下一周的这一天
+            Some(date) if self.weekday && date.and_time(time) <= now_local => {
+                date + Duration::days(7)
+            }
+            Some(date) => date,
+            None => {
+                // 只给出时间或重复规则：取今天起第一个符合条件且尚未过去的日期
+                let mut date = self.today;
+                for _ in 0..400 {
+                    if date.and_time(time) > now_local && self.matches_recurrence(date) {
+                        break;
+                    }
+                    date += Duration::days(1);
+                }
+                date
+            }
+        };
+
+        Some(local_to_utc(&self.tz, date, time))
+    }
+
+    fn matches_recurrence(&self, date: NaiveDate) -> bool {
+        let Some(recurrence) = &self.recurrence else {
+            return true;
+        };
+        if !recurrence.by_day.is_empty() && !recurrence.by_day.contains(&date.weekday()) {
+            return false;
+        }
+        if let Some(day) = recurrence.by_month_day {
+            return date.day() == day;
+        }
+        true
+    }
+
+    fn match_token(&self, i: usize) -> Option<(usize, Token)> {
+        self.match_tag(i)
+            .or_else(|| self.match_priority(i))
+            .or_else(|| self.match_reminder(i))
+            .or_else(|| self.match_recurrence(i))
+            .or_else(|| self.match_offset(i))
+            .or_else(|| self.match_filler(i))
+            .or_else(|| self.match_date(i))
+            .or_else(|| self.match_time(i))
+    }
+
+    // ---- 标签与优先级 ----
+
+    fn match_tag(&self, i: usize) -> Option<(usize, Token)> {
+        if !matches!(self.chars[i], '#' | '＃') || !self.at_token_start(i) {
+            return None;
+        }
+        let mut end = i + 1;
+        while end < self.chars.len() && !self.chars[end].is_whitespace() {
+            end += 1;
+        }
+        let tag: String = self.chars[i + 1..end].iter().collect();
+        let tag = tag.trim_end_matches(is_separator);
+        if tag.is_empty() {
+            return None;
+        }
+        Some((end, Token::Tag(tag.to_string())))
+    }
+
+    fn match_priority(&self, i: usize) -> Option<(usize, Token)> {
+        if !matches!(self.chars[i], '!' | '！') || !self.at_token_start(i) {
+            return None;
+        }
+        let mut end = i + 1;
+        while end < self.chars.len() && !self.chars[end].is_whitespace() {
+            end += 1;
+        }
+        let value: String = self.lower[i + 1..end].iter().collect();
+        let priority = match value.as_str() {
+            "1" | "h" | "high" | "高" | "!!" | "！！" => PRIORITY_HIGH,
+            "2" | "m" | "medium" | "中" | "!" | "！" => PRIORITY_MEDIUM,
+            "3" | "l" | "low" | "低" => PRIORITY_LOW,
+            _ => return None,
+        };
+        Some((end, Token::Priority(priority)))
+    }
+
+    // ---- 提醒 ----
+
+    fn match_reminder(&self, i: usize) -> Option<(usize, Token)> {
+        // remind [me] 30m [before] / reminder 1h
+        if let Some(end) = self
+            .word(i, "remind")
+            .or_else(|| self.word(i, "reminder"))
+            .or_else(|| self.word(i, "alert"))
+        {
+            let mut j = self.skip_spaces(end);
+            if let Some(me) = self.word(j, "me") {
+                j = self.skip_spaces(me);
+            }
+            let (duration, mut end) = self.duration_en(j)?;
+            let after = self.skip_spaces(end);
+            if let Some(before) = self
+                .word(after, "before")
+                .or_else(|| self.word(after, "early"))
+            {
+                end = before;
+            }
+            return Some((end, Token::Reminder(duration.num_minutes() as i32)));
+        }
+
+        // 提前30分钟[提醒]
+        if let Some(end) = self.literal(i, "提前") {
+            let j = self.skip_spaces(end);
+            let (duration, end) = self.duration_zh(j)?;
+            let end = self
+                .literal_any(end, &["提醒我", "提醒", "通知"])
+                .unwrap_or(end);
+            return Some((end, Token::Reminder(duration.num_minutes() as i32)));
+        }
+
+        // 30分钟前提醒
+        let (duration, end) = self.duration_zh(i)?;
+        let end = self.literal_any(end, &["前提醒", "之前提醒", "以前提醒"])?;
+        Some((end, Token::Reminder(duration.num_minutes() as i32)))
+    }
+
+    // ---- 重复 ----
+
+    fn match_recurrence(&self, i: usize) -> Option<(usize, Token)> {
+        self.recurrence_en(i)
+            .or_else(|| self.recurrence_zh(i))
+            .map(|(end, recurrence)| (end, Token::Recurrence(recurrence)))
+    }
+
+    fn recurrence_en(&self, i: usize) -> Option<(usize, Recurrence)> {
+        for (word, freq) in [
+            ("daily", "DAILY"),
+            ("weekly", "WEEKLY"),
+            ("monthly", "MONTHLY"),
+            ("yearly", "YEARLY"),
+            ("annually", "YEARLY"),
+        ] {
+            if let Some(end) = self.word(i, word) {
+                return Some((end, Recurrence::new(freq)));
+            }
+        }
+
+        let mut j = self.skip_spaces(self.word(i, "every")?);
+        let mut interval = 1;
+        if let Some(end) = self.word(j, "other") {
+            interval = 2;
+            j = self.skip_spaces(end);
+        } else if let Some((n, end)) = self.number(j) {
+            interval = n.max(1);
+            j = self.skip_spaces(end);
+        }
+
+        for (words, freq) in [
+            (&["day", "days"][..], "DAILY"),
+            (&["week", "weeks"][..], "WEEKLY"),
+            (&["month", "months"][..], "MONTHLY"),
+            (&["year", "years"][..], "YEARLY"),
+        ] {
+            if let Some(end) = self.word_any(j, words) {
+                let recurrence = Recurrence {
+                    interval,
+                    ..Recurrence::new(freq)
+                };
+                return Some((end, recurrence));
+            }
+        }
+
+        if let Some(end) = self.word_any(j, &["weekday", "weekdays", "workday", "workdays"]) {
+            return Some((end, Recurrence::weekdays()));
+        }
+        if let Some(end) = self.word_any(j, &["weekend", "weekends"]) {
+            return Some((end, Recurrence::weekends()));
+        }
+
+        // every monday[, wednesday and friday]
+        let (first, mut end) = self.weekday_en(j)?;
+        let mut days = vec![first];
+        loop {
+            let mut k = self.skip_spaces(end);
+            if self.chars.get(k) == Some(&',') {
+                k = self.skip_spaces(k + 1);
+            }
+            if let Some(and) = self.word(k, "and") {
+                k = self.skip_spaces(and);
+            }
+            match self.weekday_en(k) {
+                Some((day, next)) if k > end => {
+                    if !days.contains(&day) {
+                        days.push(day);
+                    }
+                    end = next;
+                }
+                _ => break,
+            }
+        }
+
+        Some((
+            end,
+            Recurrence {
+                interval,
+                by_day: days,
+                ..Recurrence::new("WEEKLY")
+            },
+        ))
+    }
+
+    fn recurrence_zh(&self, i: usize) -> Option<(usize, Recurrence)> {
+        if let Some(end) = self.literal_any(i, &["每个工作日", "每工作日"]) {
+            return Some((end, Recurrence::weekdays()));
+        }
+        if let Some(end) = self.literal_any(i, &["每周末", "每个周末"]) {
+            return Some((end, Recurrence::weekends()));
+        }
+        if let Some(end) = self.literal_any(i, &["每天", "每日"]) {
+            return Some((end, Recurrence::new("DAILY")));
+        }
+
+        // 每隔 N 天表示中间隔开 N 天，即每 N+1 天一次（每隔一天 = every other day）
+        let mut j = self.literal(i, "每")?;
+        let skip = self.literal(j, "隔");
+        if let Some(end) = skip {
+            j = end;
+        }
+        let interval = |n: u32| if skip.is_some() { n + 1 } else { n.max(1) };
+
+        // 每周一三五 / 每星期二
+        if let Some(end) = self.literal_any(j, &["周", "星期", "礼拜"]) {
+            let mut days = Vec::new();
+            let mut k = end;
+            while let Some(day) = self.chars.get(k).and_then(|c| weekday_zh(*c)) {
+                if !days.contains(&day) {
+                    days.push(day);
+                }
+                k += 1;
+                if matches!(self.chars.get(k), Some('、' | ',' | '，')) {
+                    k += 1;
+                }
+            }
+            let end = if days.is_empty() { end } else { k };
+            return Some((
+                end,
+                Recurrence {
+                    interval: interval(1),
+                    by_day: days,
+                    ..Recurrence::new("WEEKLY")
+                },
+            ));
+        }
+
+        // 每月15号
+        if let Some(end) = self.literal_any(j, &["个月", "月"]) {
+            let mut recurrence = Recurrence {
+                interval: interval(1),
+                ..Recurrence::new("MONTHLY")
+            };
+            let mut end = end;
+            if let Some((day, k)) = self.cn_number(end) {
+                if let Some(k) = self.literal_any(k, &["号", "日"]) {
+                    if (1..=31).contains(&day) {
+                        recurrence.by_month_day = Some(day);
+                        end = k;
+                    }
+                }
+            }
+            return Some((end, recurrence));
+        }
+        if let Some(end) = self.literal(j, "年") {
+            let recurrence = Recurrence {
+                interval: interval(1),
+                ..Recurrence::new("YEARLY")
+            };
+            return Some((end, recurrence));
+        }
+
+        // 每2天 / 每隔两周 / 每三个月 / 每隔天
+        let (n, k) = self.cn_number(j).or_else(|| skip.map(|_| (1, j)))?;
+        for (units, freq) in [
+            (&["天", "日"][..], "DAILY"),
+            (&["个星期", "星期", "周"][..], "WEEKLY"),
+            (&["个月", "月"][..], "MONTHLY"),
+            (&["年"][..], "YEARLY"),
+        ] {
+            if let Some(end) = self.literal_any(k, units) {
+                let recurrence = Recurrence {
+                    interval: interval(n),
+                    ..Recurrence::new(freq)
+                };
+                return Some((end, recurrence));
+            }
+        }
+        None
+    }
+
+    // ---- 相对时间 ----
+
+    fn match_offset(&self, i: usize) -> Option<(usize, Token)> {
+        // in 2 hours / in an hour
+        if let Some(end) = self.word(i, "in") {
+            let j = self.skip_spaces(end);
+            if let Some((duration, end)) = self.duration_en(j) {
+                return Some((end, Token::Offset(duration)));
+            }
+        }
+
+        // 2小时后 / 三天以后
+        let (duration, end) = self.duration_zh(i)?;
+        let end = self.literal_any(end, &["以后", "之后", "后"])?;
+        Some((end, Token::Offset(duration)))
+    }
+
+    // ---- 日期 ----
+
+    /// by / due / on / at 后面紧跟日期或时间时一起吃掉
+    fn match_filler(&self, i: usize) -> Option<(usize, Token)> {
+        let end = self.word_any(i, &["by", "due", "on", "at", "before"])?;
+        let j = self.skip_spaces(end);
+        if j == end {
+            return None;
+        }
+        if let Some(end) = self.word(j, "on") {
+            let k = self.skip_spaces(end);
+            return self.match_date(k);
+        }
+        self.match_date(j)
+            .or_else(|| self.match_time(j))
+            .or_else(|| {
+                // at 5 → 5:00
+                self.word(i, "at")?;
+                let (hour, end) = self.number(j)?;
+                if hour < 24 && self.at_token_end(end) {
+                    Some((end, Token::Time(hm(hour, 0))))
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn match_date(&self, i: usize) -> Option<(usize, Token)> {
+        self.date_en(i).or_else(|| self.date_zh(i))
+    }
+
+    fn date_en(&self, i: usize) -> Option<(usize, Token)> {
+        let today = self.today;
+        let evening = Some(hm(EVENING_TIME.0, EVENING_TIME.1));
+
+        if let Some(end) = self.phrase(i, &["day", "after", "tomorrow"]) {
+            return Some((end, Token::Date(today + Duration::days(2), None)));
+        }
+        if let Some(end) = self.word_any(i, &["tomorrow", "tmr", "tmrw"]) {
+            return Some((end, Token::Date(today + Duration::days(1), None)));
+        }
+        if let Some(end) = self.word(i, "today") {
+            return Some((end, Token::Date(today, None)));
+        }
+        if let Some(end) = self.word(i, "tonight") {
+            return Some((end, Token::Date(today, evening)));
+        }
+        if let Some(end) = self.phrase(i, &["next", "week"]) {
+            return Some((end, Token::Date(next_week(today, Weekday::Mon), None)));
+        }
+        if let Some(end) = self.word(i, "next") {
+            let j = self.skip_spaces(end);
+            let (day, end) = self.weekday_en(j)?;
+            return Some((end, Token::Date(next_week(today, day), None)));
+        }
+        if let Some(end) = self.word(i, "this") {
+            let j = self.skip_spaces(end);
+            let (day, end) = self.weekday_en(j)?;
+            return Some((end, Token::Weekday(day)));
+        }
+        if let Some((day, end)) = self.weekday_en(i) {
+            return Some((end, Token::Weekday(day)));
+        }
+
+        // 2025-03-05
+        if self.at_token_start(i) {
+            if let Some((first, j)) = self.number(i) {
+                if first >= 1000 && self.chars.get(j) == Some(&'-') {
+                    let (month, k) = self.number(j + 1)?;
+                    if self.chars.get(k) != Some(&'-') {
+                        return None;
+                    }
+                    let (day, end) = self.number(k + 1)?;
+                    let date = NaiveDate::from_ymd_opt(first as i32, month, day)?;
+                    return self
+                        .at_token_end(end)
+                        .then_some((end, Token::Date(date, None)));
+                }
+
+                // 3/5
+                if first <= 12 && self.chars.get(j) == Some(&'/') {
+                    let (day, end) = self.number(j + 1)?;
+                    let date = self.next_month_day(first, day)?;
+                    return self
+                        .at_token_end(end)
+                        .then_some((end, Token::Date(date, None)));
+                }
+
+                // 5 mar
+                let k = self.skip_spaces(j);
+                if let Some((month, end)) = self.month_en(k) {
+                    let date = self.next_month_day(month, first)?;
+                    return Some((end, Token::Date(date, None)));
+                }
+            }
+        }
+
+        // mar 5 / march 5th
+        let (month, end) = self.month_en(i)?;
+        let j = self.skip_spaces(end);
+        let (day, mut end) = self.number(j)?;
+        if let Some(suffix) = self.word_suffix(end, &["st", "nd", "rd", "th"]) {
+            end = suffix;
+        }
+        if !self.at_token_end(end) {
+            return None;
+        }
+        let date = self.next_month_day(month, day)?;
+        Some((end, Token::Date(date, None)))
+    }
+
+    fn date_zh(&self, i: usize) -> Option<(usize, Token)> {
+        let today = self.today;
+        let evening = Some(hm(EVENING_TIME.0, EVENING_TIME.1));
+
+        for (words, days, default_time) in [
+            (&["大后天"][..], 3, None),
+            (&["后天"][..], 2, None),
+            (&["明天", "明日"][..], 1, None),
+            (&["明晚"][..], 1, evening),
+            (
+                &["明早"][..],
+                1,
+                Some(hm(DEFAULT_DUE_TIME.0, DEFAULT_DUE_TIME.1)),
+            ),
+            (&["今天", "今日"][..], 0, None),
+            (&["今晚"][..], 0, evening),
+        ] {
+            if let Some(end) = self.literal_any(i, words) {
+                return Some((end, Token::Date(today + Duration::days(days), default_time)));
+            }
+        }
+
+        // 下周三 / 下周
+        if let Some(end) = self.literal_any(i, &["下周", "下星期", "下礼拜", "下个星期"])
+        {
+            if let Some(day) = self.chars.get(end).and_then(|c| weekday_zh(*c)) {
+                return Some((end + 1, Token::Date(next_week(today, day), None)));
+            }
+            return Some((end, Token::Date(next_week(today, Weekday::Mon), None)));
+        }
+
+        // 周三 / 本周五 / 星期日
+        let j = self.literal_any(i, &["本", "这"]).unwrap_or(i);
+        if let Some(end) = self.literal_any(j, &["周", "星期", "礼拜"]) {
+            if let Some(day) = self.chars.get(end).and_then(|c| weekday_zh(*c)) {
+                return Some((end + 1, Token::Weekday(day)));
+            }
+        }
+
+        // 3月5日 / 3月5号 / 5号
+        let (first, end) = self.cn_number(i)?;
+        if let Some(end) = self.literal(end, "月") {
+            let (day, end) = self.cn_number(end)?;
+            let end = self.literal_any(end, &["日", "号"])?;
+            let date = self.next_month_day(first, day)?;
+            return Some((end, Token::Date(date, None)));
+        }
+        let end = self.literal(end, "号")?;
+        let mut date = NaiveDate::from_ymd_opt(today.year(), today.month(), first);
+        if date.is_none_or(|date| date < today) {
+            let (year, month) = if today.month() == 12 {
+                (today.year() + 1, 1)
+            } else {
+                (today.year(), today.month() + 1)
+            };
+            date = NaiveDate::from_ymd_opt(year, month, first);
+        }
+        Some((end, Token::Date(date?, None)))
+    }
+
+    // ---- 时间 ----
+
+    fn match_time(&self, i: usize) -> Option<(usize, Token)> {
+        self.time_en(i).or_else(|| self.time_zh(i))
+    }
+
+    fn time_en(&self, i: usize) -> Option<(usize, Token)> {
+        if let Some(end) = self.word(i, "noon") {
+            return Some((end, Token::Time(hm(12, 0))));
+        }
+        if !self.at_token_start(i) {
+            return None;
+        }
+
+        let (mut hour, mut end) = self.number(i)?;
+        let mut minute = 0;
+        let mut explicit = false;
+        if self.chars.get(end) == Some(&':') {
+            let (m, k) = self.number(end + 1)?;
+            if k - end != 3 {
+                return None;
+            }
+            minute = m;
+            end = k;
+            explicit = true;
+        }
+
+        let k = self.skip_spaces(end);
+        if let Some(suffix) = self.word_suffix(k, &["am", "a.m.", "pm", "p.m."]) {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            let pm = self.lower[k] == 'p';
+            hour = match (pm, hour) {
+                (false, 12) => 0,
+                (true, 12) => 12,
+                (true, h) => h + 12,
+                (false, h) => h,
+            };
+            end = suffix;
+        } else if !explicit {
+            return None;
+        }
+
+        if hour > 23 || minute > 59 || !self.at_token_end(end) {
+            return None;
+        }
+        Some((end, Token::Time(hm(hour, minute))))
+    }
+
+    fn time_zh(&self, i: usize) -> Option<(usize, Token)> {
+        let mut j = i;
+        let mut period = None;
+        for (words, value) in [
+            (&["凌晨"][..], 'n'),
+            (&["早上", "早晨", "上午"][..], 'a'),
+            (&["中午"][..], 'm'),
+            (&["下午", "傍晚", "晚上"][..], 'p'),
+        ] {
+            if let Some(end) = self.literal_any(i, words) {
+                j = end;
+                period = Some(value);
+                break;
+            }
+        }
+
+        let (mut hour, minute, end) = if let Some((h, k)) = self.number(j) {
+            if self.chars.get(k) == Some(&':') || self.chars.get(k) == Some(&'：') {
+                // 下午5:30（冒号形式交给 time_en 处理没有时段的情况）
+                period?;
+                let (m, end) = self.number(k + 1)?;
+                (h, m, end)
+            } else {
+                self.clock_zh(j)?
+            }
+        } else {
+            self.clock_zh(j)?
+        };
+
+        match period {
+            Some('p') if hour < 12 => hour += 12,
+            Some('m') if hour < 6 => hour += 12,
+            _ => {}
+        }
+        if hour == 24 {
+            hour = 0;
+        }
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        Some((end, Token::Time(hm(hour, minute))))
+    }
+
+    /// 5点 / 五点半 / 10点15分 / 8点一刻
+    fn clock_zh(&self, i: usize) -> Option<(u32, u32, usize)> {
+        let (hour, end) = self.cn_number(i)?;
+        let end = self.literal_any(end, &["点钟", "点", "时"])?;
+        if let Some(k) = self.literal(end, "半") {
+            return Some((hour, 30, k));
+        }
+        if let Some(k) = self.literal(end, "一刻") {
+            return Some((hour, 15, k));
+        }
+        if let Some(k) = self.literal(end, "三刻") {
+            return Some((hour, 45, k));
+        }
+        if let Some((minute, k)) = self.cn_number(end) {
+            let k = self.literal(k, "分").unwrap_or(k);
+            return Some((hour, minute, k));
+        }
+        Some((hour, 0, end))
+    }
+
+    // ---- 时长 ----
+
+    /// 30m / 30 minutes / 1h / 2 days / an hour
+    fn duration_en(&self, i: usize) -> Option<(Duration, usize)> {
+        let (amount, end) = match self.word_any(i, &["a", "an"]) {
+            Some(end) => (1, end),
+            None => self.number(i)?,
+        };
+        let j = self.skip_spaces(end);
+        let units: [(&[&str], i64); 4] = [
+            (&["m", "min", "mins", "minute", "minutes"], 1),
+            (&["h", "hr", "hrs", "hour", "hours"], 60),
+            (&["d", "day", "days"], 60 * 24),
+            (&["w", "wk", "wks", "week", "weeks"], 60 * 24 * 7),
+        ];
+        for (words, minutes) in units {
+            // 数字与单位之间没有空格时（30m），单位紧跟在数字后面
+            let unit_end = if j == end {
+                self.word_suffix(j, words)
+            } else {
+                self.word_any(j, words)
+            };
+            if let Some(unit_end) = unit_end {
+                return Some((Duration::minutes(amount as i64 * minutes), unit_end));
+            }
+        }
+        None
+    }
+
+    /// 30分钟 / 两个小时 / 半小时 / 3天
+    fn duration_zh(&self, i: usize) -> Option<(Duration, usize)> {
+        if let Some(end) = self.literal_any(i, &["半个小时", "半小时", "半个钟头"]) {
+            return Some((Duration::minutes(30), end));
+        }
+        let (amount, end) = self.cn_number(i)?;
+        let j = self.skip_spaces(end);
+        let units: [(&[&str], i64); 4] = [
+            (&["分钟", "分"], 1),
+            (&["个小时", "小时", "个钟头", "钟头"], 60),
+            (&["天"], 60 * 24),
+            (&["个星期", "星期", "周"], 60 * 24 * 7),
+        ];
+        for (words, minutes) in units {
+            if let Some(end) = self.literal_any(j, words) {
+                return Some((Duration::minutes(amount as i64 * minutes), end));
+            }
+        }
+        None
+    }
+
+    // ---- 基础匹配 ----
+
+    fn weekday_en(&self, i: usize) -> Option<(Weekday, usize)> {
+        for (words, day) in [
+            (&["monday", "mon"][..], Weekday::Mon),
+            (&["tuesday", "tues", "tue"][..], Weekday::Tue),
+            (&["wednesday", "wed"][..], Weekday::Wed),
+            (&["thursday", "thurs", "thur", "thu"][..], Weekday::Thu),
+            (&["friday", "fri"][..], Weekday::Fri),
+            (&["saturday", "sat"][..], Weekday::Sat),
+            (&["sunday", "sun"][..], Weekday::Sun),
+        ] {
+            if let Some(end) = self.word_any(i, words) {
+                return Some((day, end));
+            }
+        }
+        None
+    }
+
+    fn month_en(&self, i: usize) -> Option<(u32, usize)> {
+        const MONTHS: [&str; 12] = [
+            "january",
+            "february",
+            "march",
+            "april",
+            "may",
+            "june",
+            "july",
+            "august",
+            "september",
+            "october",
+            "november",
+            "december",
+        ];
+        for (index, name) in MONTHS.iter().enumerate() {
+            if let Some(end) = self.word(i, name).or_else(|| self.word(i, &name[..3])) {
+                return Some((index as u32 + 1, end));
+            }
+        }
+        None
+    }
+
+    /// 今天或之后第一个 month/day 日期
+    fn next_month_day(&self, month: u32, day: u32) -> Option<NaiveDate> {
+        let date = NaiveDate::from_ymd_opt(self.today.year(), month, day)?;
+        if date >= self.today {
+            Some(date)
+        } else {
+            NaiveDate::from_ymd_opt(self.today.year() + 1, month, day)
+        }
+    }
+
+    /// 在词边界处匹配一个英文单词（不区分大小写）
+    fn word(&self, i: usize, word: &str) -> Option<usize> {
+        if !self.at_token_start(i) {
+            return None;
+        }
+        self.word_suffix(i, &[word])
+    }
+
+    fn word_any(&self, i: usize, words: &[&str]) -> Option<usize> {
+        words.iter().find_map(|word| self.word(i, word))
+    }
+
+    /// 匹配紧跟在前一个片段之后的单词（只检查结尾边界），取最长的匹配
+    fn word_suffix(&self, i: usize, words: &[&str]) -> Option<usize> {
+        words
+            .iter()
+            .filter_map(|word| {
+                let end = self.literal_lower(i, word)?;
+                self.at_token_end(end).then_some(end)
+            })
+            .max()
+    }
+
+    fn phrase(&self, i: usize, words: &[&str]) -> Option<usize> {
+        let mut end = i;
+        for (index, word) in words.iter().enumerate() {
+            let start = if index == 0 {
+                end
+            } else {
+                let next = self.skip_spaces(end);
+                if next == end {
+                    return None;
+                }
+                next
+            };
+            end = self.word(start, word)?;
+        }
+        Some(end)
+    }
+
+    fn literal(&self, i: usize, text: &str) -> Option<usize> {
+        let mut end = i;
+        for c in text.chars() {
+            if self.chars.get(end) != Some(&c) {
+                return None;
+            }
+            end += 1;
+        }
+        Some(end)
+    }
+
+    fn literal_any(&self, i: usize, texts: &[&str]) -> Option<usize> {
+        texts.iter().find_map(|text| self.literal(i, text))
+    }
+
+    fn literal_lower(&self, i: usize, text: &str) -> Option<usize> {
+        let mut end = i;
+        for c in text.chars() {
+            if self.lower.get(end) != Some(&c) {
+                return None;
+            }
+            end += 1;
+        }
+        Some(end)
+    }
+
+    fn number(&self, i: usize) -> Option<(u32, usize)> {
+        let mut end = i;
+        while end < self.chars.len() && self.chars[end].is_ascii_digit() && end - i < 6 {
+            end += 1;
+        }
+        if end == i {
+            return None;
+        }
+        let text: String = self.chars[i..end].iter().collect();
+        Some((text.parse().ok()?, end))
+    }
+
+    /// 阿拉伯数字或 0-99 的中文数字
+    fn cn_number(&self, i: usize) -> Option<(u32, usize)> {
+        if let Some(result) = self.number(i) {
+            return Some(result);
+        }
+
+        let digit = |index: usize| self.chars.get(index).and_then(|c| cn_digit(*c));
+        let is_ten = |index: usize| self.chars.get(index) == Some(&'十');
+
+        let (mut value, mut end) = match digit(i) {
+            Some(d) if is_ten(i + 1) => (d * 10, i + 2),
+            Some(d) => return Some((d, i + 1)),
+            None if is_ten(i) => (10, i + 1),
+            None => return None,
+        };
+        if let Some(d) = digit(end) {
+            value += d;
+            end += 1;
+        }
+        Some((value, end))
+    }
+
+    fn skip_spaces(&self, mut i: usize) -> usize {
+        while i < self.chars.len() && self.chars[i].is_whitespace() {
+            i += 1;
+        }
+        i
+    }
+
+    fn at_token_start(&self, i: usize) -> bool {
+        i == 0 || !self.chars[i - 1].is_ascii_alphanumeric()
+    }
+
+    fn at_token_end(&self, i: usize) -> bool {
+        i >= self.chars.len() || !self.chars[i].is_ascii_alphanumeric()
+    }
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(NaiveTime::MIN)
+}
+
+/// 今天或之后最近的某个星期几
+fn upcoming(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let diff = (7 + day.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(diff as i64)
+}
+
+/// 下一周（周一开始）的某个星期几
+fn next_week(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let next_monday = today + Duration::days(7 - today.weekday().num_days_from_monday() as i64);
+    next_monday + Duration::days(day.num_days_from_monday() as i64)
+}
+
+fn local_to_utc(tz: &Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| DateTime::<Utc>::from_naive_utc_and_offset(local, Utc))
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn weekday_zh(c: char) -> Option<Weekday> {
+    match c {
+        '一' => Some(Weekday::Mon),
+        '二' => Some(Weekday::Tue),
+        '三' => Some(Weekday::Wed),
+        '四' => Some(Weekday::Thu),
+        '五' => Some(Weekday::Fri),
+        '六' => Some(Weekday::Sat),
+        '日' | '天' => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn cn_digit(c: char) -> Option<u32> {
+    match c {
+        '零' | '〇' => Some(0),
+        '一' => Some(1),
+        '二' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, ',' | '.' | ';' | '，' | '。' | '；' | '、')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHANGHAI: &str = "Asia/Shanghai";
+    const NEW_YORK: &str = "America/New_York";
+
+    /// `timezone` 中的本地时间转为 UTC
+    fn local(timezone: &str, y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        let tz: Tz = timezone.parse().unwrap();
+        tz.with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// 2025-03-05（周三）18:00，上海
+    fn wednesday_evening() -> DateTime<Utc> {
+        local(SHANGHAI, 2025, 3, 5, 18, 0)
+    }
+
+    fn rule(input: &str) -> Option<String> {
+        parse(input, wednesday_evening(), SHANGHAI).recurrence_rule
+    }
+
+    #[test]
+    fn parses_english_example() {
+        let parsed = parse(
+            "Submit report tomorrow 17:00 #work !2 remind 30m every weekday",
+            wednesday_evening(),
+            SHANGHAI,
+        );
+        assert_eq!(parsed.title, "Submit report");
+        assert_eq!(parsed.due_date, Some(local(SHANGHAI, 2025, 3, 6, 17, 0)));
+        assert_eq!(parsed.tags, vec!["work".to_string()]);
+        assert_eq!(parsed.priority, Some(PRIORITY_MEDIUM));
+        assert_eq!(parsed.reminder_offset_minutes, Some(30));
+        assert_eq!(
+            parsed.recurrence_rule.as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+        );
+        assert_eq!(parsed.timezone, SHANGHAI);
+    }
+
+    #[test]
+    fn parses_chinese_example() {
+        let parsed = parse(
+            "明天下午5点提交报告 #工作 !1 提前30分钟提醒 每个工作日",
+            wednesday_evening(),
+            SHANGHAI,
+        );
+        assert_eq!(parsed.title, "提交报告");
+        assert_eq!(parsed.due_date, Some(local(SHANGHAI, 2025, 3, 6, 17, 0)));
+        assert_eq!(parsed.tags, vec!["工作".to_string()]);
+        assert_eq!(parsed.priority, Some(PRIORITY_HIGH));
+        assert_eq!(parsed.reminder_offset_minutes, Some(30));
+        assert_eq!(
+            parsed.recurrence_rule.as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+        );
+    }
+
+    #[test]
+    fn plain_text_has_no_fields() {
+        let parsed = parse("Buy milk", wednesday_evening(), SHANGHAI);
+        assert_eq!(parsed.title, "Buy milk");
+        assert_eq!(parsed.due_date, None);
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.priority, None);
+        assert_eq!(parsed.recurrence_rule, None);
+    }
+
+    #[test]
+    fn keeps_wall_clock_time_across_dst_start() {
+        // 纽约 2025-03-09 02:00 起进入夏令时
+        let now = local(NEW_YORK, 2025, 3, 8, 12, 0);
+        let parsed = parse("Standup tomorrow 9am", now, NEW_YORK);
+        assert_eq!(parsed.due_date, Some(local(NEW_YORK, 2025, 3, 9, 9, 0)));
+        assert_eq!(
+            parsed.due_date,
+            Some(Utc.with_ymd_and_hms(2025, 3, 9, 13, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn skipped_local_time_moves_forward_an_hour() {
+        // 02:30 在夏令时开始当天不存在，取 03:30（EDT）
+        let now = local(NEW_YORK, 2025, 3, 8, 12, 0);
+        let parsed = parse("Backup tomorrow 2:30am", now, NEW_YORK);
+        assert_eq!(
+            parsed.due_date,
+            Some(Utc.with_ymd_and_hms(2025, 3, 9, 7, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn keeps_wall_clock_time_across_dst_end() {
+        // 纽约 2025-11-02 02:00 结束夏令时
+        let now = local(NEW_YORK, 2025, 11, 1, 12, 0);
+        let parsed = parse("明天上午9点 开会", now, NEW_YORK);
+        assert_eq!(parsed.title, "开会");
+        assert_eq!(
+            parsed.due_date,
+            Some(Utc.with_ymd_and_hms(2025, 11, 2, 14, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn weekday_names_roll_over_to_next_week() {
+        let now = wednesday_evening();
+        let due = |input: &str| parse(input, now, SHANGHAI).due_date;
+
+        // 当天的星期几时间已过时取下一周，之前的也取下一周
+        assert_eq!(due("Review wed"), Some(local(SHANGHAI, 2025, 3, 12, 9, 0)));
+        assert_eq!(
+            due("Review wed 9pm"),
+            Some(local(SHANGHAI, 2025, 3, 5, 21, 0))
+        );
+        assert_eq!(
+            due("Review wed 5pm"),
+            Some(local(SHANGHAI, 2025, 3, 12, 17, 0))
+        );
+        assert_eq!(due("本周三复盘"), Some(local(SHANGHAI, 2025, 3, 12, 9, 0)));
+        assert_eq!(
+            due("Review monday"),
+            Some(local(SHANGHAI, 2025, 3, 10, 9, 0))
+        );
+        assert_eq!(
+            due("Review friday"),
+            Some(local(SHANGHAI, 2025, 3, 7, 9, 0))
+        );
+        assert_eq!(
+            due("Review next wed"),
+            Some(local(SHANGHAI, 2025, 3, 12, 9, 0))
+        );
+        assert_eq!(due("周二复盘"), Some(local(SHANGHAI, 2025, 3, 11, 9, 0)));
+        assert_eq!(due("下周五复盘"), Some(local(SHANGHAI, 2025, 3, 14, 9, 0)));
+    }
+
+    #[test]
+    fn weekday_rolls_over_year_end() {
+        // 2025-12-31 是周三
+        let now = local(SHANGHAI, 2025, 12, 31, 10, 0);
+        let parsed = parse("Report friday", now, SHANGHAI);
+        assert_eq!(parsed.due_date, Some(local(SHANGHAI, 2026, 1, 2, 9, 0)));
+    }
+
+    #[test]
+    fn recurrence_without_date_starts_at_next_matching_day() {
+        // 今天 9:00 已过，下一个周一是 3 月 10 日
+        let parsed = parse("Gym every monday", wednesday_evening(), SHANGHAI);
+        assert_eq!(parsed.due_date, Some(local(SHANGHAI, 2025, 3, 10, 9, 0)));
+        assert_eq!(
+            parsed.recurrence_rule.as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO")
+        );
+
+        let parsed = parse("Read at 9pm", wednesday_evening(), SHANGHAI);
+        assert_eq!(parsed.due_date, Some(local(SHANGHAI, 2025, 3, 5, 21, 0)));
+    }
+
+    #[test]
+    fn every_other_doubles_the_interval() {
+        assert_eq!(
+            rule("Water plants every other day").as_deref(),
+            Some("FREQ=DAILY;INTERVAL=2")
+        );
+        assert_eq!(
+            rule("浇花 每隔一天").as_deref(),
+            Some("FREQ=DAILY;INTERVAL=2")
+        );
+        assert_eq!(
+            rule("浇花 每隔天").as_deref(),
+            Some("FREQ=DAILY;INTERVAL=2")
+        );
+        assert_eq!(
+            rule("开会 每隔周一").as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO")
+        );
+        assert_eq!(
+            rule("对账 每隔月").as_deref(),
+            Some("FREQ=MONTHLY;INTERVAL=2")
+        );
+    }
+
+    #[test]
+    fn chinese_intervals() {
+        assert_eq!(rule("浇花 每天").as_deref(), Some("FREQ=DAILY"));
+        assert_eq!(
+            rule("周报 每两周").as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=2")
+        );
+        // 每隔两天：中间隔开两天，每三天一次
+        assert_eq!(
+            rule("换水 每隔两天").as_deref(),
+            Some("FREQ=DAILY;INTERVAL=3")
+        );
+        assert_eq!(
+            rule("还款 每月15号").as_deref(),
+            Some("FREQ=MONTHLY;BYMONTHDAY=15")
+        );
+        assert_eq!(
+            rule("健身 每周一三五").as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,WE,FR")
+        );
+    }
+
+    #[test]
+    fn relative_offsets_and_reminders() {
+        let now = wednesday_evening();
+        let parsed = parse("Call mom in 2 hours", now, SHANGHAI);
+        assert_eq!(parsed.title, "Call mom");
+        assert_eq!(parsed.due_date, Some(now + Duration::hours(2)));
+
+        let parsed = parse("3天后交作业 30分钟前提醒", now, SHANGHAI);
+        assert_eq!(parsed.title, "交作业");
+        assert_eq!(parsed.due_date, Some(now + Duration::days(3)));
+        assert_eq!(parsed.reminder_offset_minutes, Some(30));
+    }
+}
@@ -0,0 +1,782 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::features::settings::core::service::SettingService;
+
+const RECURRENCE_MODE_KEY: &str = "todo.recurrence_mode";
+
+/// 向后查找下一次发生时间时最多遍历的周期数（防止无解规则导致死循环）
+const MAX_PERIODS: u32 = 10_000;
+
+/// 完成重复待办时的推进方式
+///
+/// - `InPlace`: 原地推进，保留 UID，将 DUE/DTSTART 移到下一次发生时间（与 Tasks.org 行为一致）
+/// - `Successor`: 保留已完成的实例（去掉 RRULE），另外创建一个新的待办作为下一次发生
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceMode {
+    #[default]
+    InPlace,
+    Successor,
+}
+
+impl RecurrenceMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RecurrenceMode::InPlace => "in_place",
+            RecurrenceMode::Successor => "successor",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "in_place" => Some(RecurrenceMode::InPlace),
+            "successor" => Some(RecurrenceMode::Successor),
+            _ => None,
+        }
+    }
+}
+
+/// 读取重复待办推进方式（默认原地推进）
+pub async fn get_mode(db: &DatabaseConnection) -> Result<RecurrenceMode> {
+    let raw = SettingService::get(db, RECURRENCE_MODE_KEY).await?;
+    Ok(raw
+        .as_deref()
+        .and_then(RecurrenceMode::parse)
+        .unwrap_or_default())
+}
+
+/// 保存重复待办推进方式
+pub async fn set_mode(db: &DatabaseConnection, mode: RecurrenceMode) -> Result<()> {
+    SettingService::set(db, RECURRENCE_MODE_KEY, mode.as_str()).await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// BYDAY 中的一项，例如 `MO`、`2TU`、`-1FR`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// UNTIL 的三种写法：UTC 时间、本地时间、纯日期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    Utc(DateTime<Utc>),
+    Local(NaiveDateTime),
+    Date(NaiveDate),
+}
+
+/// RFC 5545 RRULE（支持 FREQ/INTERVAL/BYDAY/BYMONTHDAY/BYMONTH/COUNT/UNTIL/WKST）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    pub week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    /// 解析 RRULE 字符串（可带或不带 `RRULE:` 前缀）
+    pub fn parse(raw: &str) -> Result<Self> {
+        let trimmed = raw.trim();
+        let body = trimmed
+            .strip_prefix("RRULE:")
+            .or_else(|| trimmed.strip_prefix("rrule:"))
+            .unwrap_or(trimmed);
+
+        let mut freq = None;
+        let mut interval = 1u32;
+        let mut by_day = Vec::new();
+        let mut by_month_day = Vec::new();
+        let mut by_month = Vec::new();
+        let mut count = None;
+        let mut until = None;
+        let mut week_start = Weekday::Mon;
+
+        for part in body.split(';').filter(|part| !part.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid RRULE part: {part}"))?;
+            let key = key.trim().to_ascii_uppercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => bail!("unsupported RRULE frequency: {other}"),
+                    });
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .with_context(|| format!("invalid RRULE INTERVAL: {value}"))?
+                        .max(1);
+                }
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse::<u32>()
+                            .with_context(|| format!("invalid RRULE COUNT: {value}"))?,
+                    );
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                "BYDAY" => {
+                    for item in value.split(',').filter(|item| !item.is_empty()) {
+                        by_day.push(parse_weekday_num(item)?);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for item in value.split(',').filter(|item| !item.is_empty()) {
+                        let day = item
+                            .trim()
+                            .parse::<i32>()
+                            .with_context(|| format!("invalid RRULE BYMONTHDAY: {item}"))?;
+                        if day == 0 || !(-31..=31).contains(&day) {
+                            bail!("invalid RRULE BYMONTHDAY: {item}");
+                        }
+                        by_month_day.push(day);
+                    }
+                }
+                "BYMONTH" => {
+                    for item in value.split(',').filter(|item| !item.is_empty()) {
+                        let month = item
+                            .trim()
+                            .parse::<u32>()
+                            .with_context(|| format!("invalid RRULE BYMONTH: {item}"))?;
+                        if !(1..=12).contains(&month) {
+                            bail!("invalid RRULE BYMONTH: {item}");
+                        }
+                        by_month.push(month);
+                    }
+                }
+                "WKST" => week_start = parse_weekday(value)?,
+                other => bail!("unsupported RRULE part: {other}"),
+            }
+        }
+
+        let freq = freq.ok_or_else(|| anyhow!("RRULE missing FREQ"))?;
+        by_month.sort_unstable();
+        by_month.dedup();
+
+        Ok(Self {
+            freq,
+            interval,
+            by_day,
+            by_month_day,
+            by_month,
+            count,
+            until,
+            week_start,
+        })
+    }
+
+    /// 计算以 `dtstart` 为起点的序列中，严格晚于 `after` 的第一次发生时间
+    ///
+    /// 展开在 `tz` 的本地时间上进行，保证跨夏令时后仍保持相同的钟点。
+    /// 按 RFC 5545，`dtstart` 本身总是第一次发生，并计入 COUNT。
+    /// 返回 None 表示序列已经结束（COUNT 用尽或超过 UNTIL）。
+    pub fn next_after(
+        &self,
+        dtstart: DateTime<Utc>,
+        after: DateTime<Utc>,
+        tz: Tz,
+    ) -> Option<DateTime<Utc>> {
+        if self.count == Some(0) {
+            return None;
+        }
+        if dtstart > after {
+            return Some(dtstart);
+        }
+
+        let local_start = dtstart.with_timezone(&tz).naive_local();
+        let time = local_start.time();
+        let start_date = local_start.date();
+        let mut produced = 1u32;
+
+        for period in 0..MAX_PERIODS {
+            let step = period.checked_mul(self.interval)?;
+            for date in self.period_candidates(start_date, step)? {
+                let local = date.and_time(time);
+                if local <= local_start {
+                    continue;
+                }
+
+                produced += 1;
+                if self.count.is_some_and(|count| produced > count) {
+                    return None;
+                }
+
+                let occurrence = local_to_utc(&tz, local);
+                if self.is_past_until(local, occurrence) {
+                    return None;
+                }
+                if occurrence > after {
+                    return Some(occurrence);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// 推进一次后的 RRULE 文本：COUNT 减一，其余部分原样保留
+    ///
+    /// 原地推进会移动 DTSTART，COUNT 必须同步减少才能保持剩余次数不变。
+    pub fn advanced_rule_text(raw: &str) -> String {
+        let trimmed = raw.trim();
+        let (prefix, body) = match trimmed.strip_prefix("RRULE:") {
+            Some(rest) => ("RRULE:", rest),
+            None => ("", trimmed),
+        };
+
+        let parts: Vec<String> = body
+            .split(';')
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| match part.split_once('=') {
+                Some((key, value)) if key.trim().eq_ignore_ascii_case("COUNT") => {
+                    match value.trim().parse::<u32>() {
+                        Ok(count) => format!("{}={}", key, count.saturating_sub(1).max(1)),
+                        Err(_) => part.to_string(),
+                    }
+                }
+                _ => part.to_string(),
+            })
+            .collect();
+
+        format!("{}{}", prefix, parts.join(";"))
+    }
+
+    fn is_past_until(&self, local: NaiveDateTime, occurrence: DateTime<Utc>) -> bool {
+        match self.until {
+            Some(Until::Utc(until)) => occurrence > until,
+            Some(Until::Local(until)) => local > until,
+            Some(Until::Date(until)) => local.date() > until,
+            None => false,
+        }
+    }
+
+    /// 生成第 `step` 个周期（相对 dtstart 所在周期）内的候选日期（已排序）
+    fn period_candidates(&self, start_date: NaiveDate, step: u32) -> Option<Vec<NaiveDate>> {
+        let mut dates = match self.freq {
+            Frequency::Daily => {
+                let date = start_date.checked_add_signed(Duration::days(step as i64))?;
+                if self.matches_day_filters(date) {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let offset = (start_date.weekday().num_days_from_monday() + 7
+                    - self.week_start.num_days_from_monday())
+                    % 7;
+                let week_first = start_date
+                    .checked_sub_signed(Duration::days(offset as i64))?
+                    .checked_add_signed(Duration::weeks(step as i64))?;
+
+                (0..7)
+                    .filter_map(|i| week_first.checked_add_signed(Duration::days(i)))
+                    .filter(|date| {
+                        if self.by_day.is_empty() {
+                            date.weekday() == start_date.weekday()
+                        } else {
+                            self.by_day.iter().any(|day| day.weekday == date.weekday())
+                        }
+                    })
+                    .filter(|date| {
+                        self.by_month.is_empty() || self.by_month.contains(&date.month())
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let (year, month) = add_months(start_date.year(), start_date.month(), step)?;
+                if !self.by_month.is_empty() && !self.by_month.contains(&month) {
+                    Vec::new()
+                } else {
+                    self.month_candidates(year, month, start_date.day())
+                }
+            }
+            Frequency::Yearly => {
+                let year = start_date.year().checked_add(step as i32)?;
+                self.year_candidates(year, start_date)
+            }
+        };
+
+        dates.sort_unstable();
+        dates.dedup();
+        Some(dates)
+    }
+
+    fn year_candidates(&self, year: i32, start_date: NaiveDate) -> Vec<NaiveDate> {
+        if !self.by_month.is_empty() {
+            return self
+                .by_month
+                .iter()
+                .flat_map(|month| self.month_candidates(year, *month, start_date.day()))
+                .collect();
+        }
+
+        if !self.by_month_day.is_empty() {
+            return (1..=12)
+                .flat_map(|month| self.month_candidates(year, month, start_date.day()))
+                .collect();
+        }
+
+        if !self.by_day.is_empty() {
+            // 没有 BYMONTH 时，BYDAY 的序号以整年为范围（例如 20MO 表示当年第 20 个周一）
+            let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+                return Vec::new();
+            };
+            let Some(last) = NaiveDate::from_ymd_opt(year, 12, 31) else {
+                return Vec::new();
+            };
+            return expand_by_day(&self.by_day, first, last);
+        }
+
+        NaiveDate::from_ymd_opt(year, start_date.month(), start_date.day())
+            .into_iter()
+            .collect()
+    }
+
+    fn month_candidates(&self, year: i32, month: u32, start_day: u32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        let last_day = days_in_month(year, month);
+        let Some(last) = NaiveDate::from_ymd_opt(year, month, last_day) else {
+            return Vec::new();
+        };
+
+        if !self.by_month_day.is_empty() {
+            return self
+                .by_month_day
+                .iter()
+                .filter_map(|day| resolve_month_day(*day, last_day))
+                .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+                .filter(|date| {
+                    self.by_day.is_empty()
+                        || self.by_day.iter().any(|day| day.weekday == date.weekday())
+                })
+                .collect();
+        }
+
+        if !self.by_day.is_empty() {
+            return expand_by_day(&self.by_day, first, last);
+        }
+
+        // 没有 BY* 约束时沿用 dtstart 的日；当月不存在该日（如 2 月 30 日）则跳过
+        NaiveDate::from_ymd_opt(year, month, start_day)
+            .into_iter()
+            .collect()
+    }
+
+    fn matches_day_filters(&self, date: NaiveDate) -> bool {
+        if !self.by_month.is_empty() && !self.by_month.contains(&date.month()) {
+            return false;
+        }
+        if !self.by_month_day.is_empty() {
+            let last_day = days_in_month(date.year(), date.month());
+            let matched = self
+                .by_month_day
+                .iter()
+                .filter_map(|day| resolve_month_day(*day, last_day))
+                .any(|day| day == date.day());
+            if !matched {
+                return false;
+            }
+        }
+        if !self.by_day.is_empty() && !self.by_day.iter().any(|day| day.weekday == date.weekday()) {
+            return false;
+        }
+        true
+    }
+}
+
+/// 在 [first, last] 范围内展开 BYDAY（支持正负序号）
+fn expand_by_day(by_day: &[WeekdayNum], first: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+    let mut result = Vec::new();
+
+    for item in by_day {
+        let matching: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .filter(|date| date.weekday() == item.weekday)
+            .collect();
+
+        match item.ordinal {
+            None => result.extend(matching),
+            Some(n) if n > 0 => {
+                if let Some(date) = matching.get((n - 1) as usize) {
+                    result.push(*date);
+                }
+            }
+            Some(n) => {
+                let index = matching.len() as i32 + n;
+                if index >= 0 {
+                    if let Some(date) = matching.get(index as usize) {
+                        result.push(*date);
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn resolve_month_day(day: i32, last_day: u32) -> Option<u32> {
+    let resolved = if day > 0 {
+        day
+    } else {
+        last_day as i32 + day + 1
+    };
+    if resolved >= 1 && resolved <= last_day as i32 {
+        Some(resolved as u32)
+    } else {
+        None
+    }
+}
+
+fn add_months(year: i32, month: u32, months: u32) -> Option<(i32, u32)> {
+    let total = (year as i64) * 12 + (month as i64 - 1) + months as i64;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+    Some((year, month))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|date| date.pred_opt())
+        .map(|date| date.day())
+        .unwrap_or(28)
+}
+
+/// 本地时间转 UTC；落在夏令时跳变的空档里时顺延一小时
+fn local_to_utc(tz: &Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| DateTime::<Utc>::from_naive_utc_and_offset(local, Utc))
+}
+
+fn parse_until(value: &str) -> Result<Until> {
+    if let Some(trimmed) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(trimmed, "%Y%m%dT%H%M%S")
+            .with_context(|| format!("invalid RRULE UNTIL: {value}"))?;
+        return Ok(Until::Utc(DateTime::<Utc>::from_naive_utc_and_offset(
+            naive, Utc,
+        )));
+    }
+
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+            .with_context(|| format!("invalid RRULE UNTIL: {value}"))?;
+        return Ok(Until::Date(date));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .with_context(|| format!("invalid RRULE UNTIL: {value}"))?;
+    Ok(Until::Local(naive))
+}
+
+fn parse_weekday_num(value: &str) -> Result<WeekdayNum> {
+    let value = value.trim();
+    if value.len() < 2 {
+        bail!("invalid RRULE BYDAY: {value}");
+    }
+    let (ordinal, code) = value.split_at(value.len() - 2);
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        let parsed = ordinal
+            .trim_start_matches('+')
+            .parse::<i32>()
+            .with_context(|| format!("invalid RRULE BYDAY: {value}"))?;
+        if parsed == 0 {
+            bail!("invalid RRULE BYDAY: {value}");
+        }
+        Some(parsed)
+    };
+
+    Ok(WeekdayNum {
+        ordinal,
+        weekday: parse_weekday(code)?,
+    })
+}
+
+fn parse_weekday(code: &str) -> Result<Weekday> {
+    Ok(match code.trim().to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        other => bail!("invalid weekday: {other}"),
+    })
+}
+
+/// 解析待办的时区，无法识别时使用 UTC
+pub fn resolve_timezone(timezone: Option<&str>) -> Tz {
+    timezone
+        .and_then(|tz| tz.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// 重复待办的下一次发生
+#[derive(Debug, Clone)]
+pub struct NextOccurrence {
+    pub due_date: Option<DateTime<Utc>>,
+    pub start_at: DateTime<Utc>,
+    /// 新旧锚点之间的时间差，用于平移子任务的日期
+    pub shift: Duration,
+    /// 推进后的 RRULE（COUNT 已减一）
+    pub rule: String,
+}
+
+/// 计算重复待办完成后的下一次发生
+///
+/// 以 DUE 作为锚点（没有 DUE 时使用 DTSTART），与 Tasks.org 按截止日期重复的行为一致；
+/// DTSTART 与 DUE 之间的间隔在推进后保持不变。
+pub fn next_occurrence(
+    rule_text: &str,
+    due_date: Option<DateTime<Utc>>,
+    start_at: DateTime<Utc>,
+    timezone: Option<&str>,
+) -> Result<Option<NextOccurrence>> {
+    let rule = RecurrenceRule::parse(rule_text)?;
+    let tz = resolve_timezone(timezone);
+    let anchor = due_date.unwrap_or(start_at);
+
+    let Some(next) = rule.next_after(anchor, anchor, tz) else {
+        return Ok(None);
+    };
+    let shift = next - anchor;
+
+    Ok(Some(NextOccurrence {
+        due_date: due_date.map(|_| next),
+        start_at: start_at + shift,
+        shift,
+        rule: RecurrenceRule::advanced_rule_text(rule_text),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn local(tz: Tz, y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        tz.with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// dtstart 之后的前 `n` 次发生（序列提前结束时更少）
+    fn occurrences(rule: &str, dtstart: DateTime<Utc>, tz: Tz, n: usize) -> Vec<DateTime<Utc>> {
+        let rule = RecurrenceRule::parse(rule).unwrap();
+        let mut result = Vec::new();
+        let mut after = dtstart;
+        while result.len() < n {
+            let Some(next) = rule.next_after(dtstart, after, tz) else {
+                break;
+            };
+            result.push(next);
+            after = next;
+        }
+        result
+    }
+
+    #[test]
+    fn count_decrements_across_in_place_advances() {
+        let mut rule = "RRULE:FREQ=DAILY;COUNT=3".to_string();
+        let mut due = utc(2025, 3, 1, 9, 0);
+        let mut seen = vec![due];
+
+        // 原地推进：每次把 DUE 移到下一次发生并让 COUNT 减一
+        while let Some(next) = next_occurrence(&rule, Some(due), due, Some("UTC")).unwrap() {
+            due = next.due_date.unwrap();
+            rule = next.rule;
+            seen.push(due);
+        }
+
+        assert_eq!(
+            seen,
+            vec![
+                utc(2025, 3, 1, 9, 0),
+                utc(2025, 3, 2, 9, 0),
+                utc(2025, 3, 3, 9, 0)
+            ]
+        );
+        assert_eq!(rule, "RRULE:FREQ=DAILY;COUNT=1");
+    }
+
+    #[test]
+    fn advanced_rule_text_only_touches_count() {
+        assert_eq!(
+            RecurrenceRule::advanced_rule_text("FREQ=WEEKLY;COUNT=5;BYDAY=MO,WE"),
+            "FREQ=WEEKLY;COUNT=4;BYDAY=MO,WE"
+        );
+        assert_eq!(
+            RecurrenceRule::advanced_rule_text("FREQ=DAILY;INTERVAL=2"),
+            "FREQ=DAILY;INTERVAL=2"
+        );
+    }
+
+    #[test]
+    fn until_date_includes_the_whole_local_day() {
+        let tz: Tz = "Asia/Shanghai".parse().unwrap();
+        let dtstart = local(tz, 2025, 3, 8, 23, 30);
+
+        assert_eq!(
+            occurrences("FREQ=DAILY;UNTIL=20250310", dtstart, tz, 5),
+            vec![
+                local(tz, 2025, 3, 9, 23, 30),
+                local(tz, 2025, 3, 10, 23, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn until_utc_compares_the_instant() {
+        let tz: Tz = "Asia/Shanghai".parse().unwrap();
+        let dtstart = local(tz, 2025, 3, 8, 23, 30);
+
+        // 3 月 10 日 23:30（上海）是 15:30Z，晚于 UNTIL
+        assert_eq!(
+            occurrences("FREQ=DAILY;UNTIL=20250310T000000Z", dtstart, tz, 5),
+            vec![local(tz, 2025, 3, 9, 23, 30)]
+        );
+        assert_eq!(
+            RecurrenceRule::parse("FREQ=DAILY;UNTIL=20250310T000000Z")
+                .unwrap()
+                .until,
+            Some(Until::Utc(utc(2025, 3, 10, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn last_day_of_month() {
+        let tz = Tz::UTC;
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYMONTHDAY=-1", utc(2025, 1, 31, 9, 0), tz, 3),
+            vec![
+                utc(2025, 2, 28, 9, 0),
+                utc(2025, 3, 31, 9, 0),
+                utc(2025, 4, 30, 9, 0)
+            ]
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYMONTHDAY=-1", utc(2024, 1, 31, 9, 0), tz, 1),
+            vec![utc(2024, 2, 29, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn month_day_31_skips_short_months() {
+        assert_eq!(
+            occurrences(
+                "FREQ=MONTHLY;BYMONTHDAY=31",
+                utc(2025, 1, 31, 9, 0),
+                Tz::UTC,
+                4
+            ),
+            vec![
+                utc(2025, 3, 31, 9, 0),
+                utc(2025, 5, 31, 9, 0),
+                utc(2025, 7, 31, 9, 0),
+                utc(2025, 8, 31, 9, 0)
+            ]
+        );
+        // 没有 BYMONTHDAY 时沿用 dtstart 的日，同样跳过没有 31 日的月份
+        assert_eq!(
+            occurrences("FREQ=MONTHLY", utc(2025, 1, 31, 9, 0), Tz::UTC, 1),
+            vec![utc(2025, 3, 31, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn yearly_by_day_ordinals() {
+        let tz = Tz::UTC;
+        // RFC 5545 示例：每年第 20 个周一
+        assert_eq!(
+            occurrences("FREQ=YEARLY;BYDAY=20MO", utc(1997, 5, 19, 9, 0), tz, 2),
+            vec![utc(1998, 5, 18, 9, 0), utc(1999, 5, 17, 9, 0)]
+        );
+        // 有 BYMONTH 时序号以月为范围：11 月第 4 个周四
+        assert_eq!(
+            occurrences(
+                "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+                utc(2024, 11, 28, 9, 0),
+                tz,
+                2
+            ),
+            vec![utc(2025, 11, 27, 9, 0), utc(2026, 11, 26, 9, 0)]
+        );
+        // 负序号：每年最后一个周日
+        assert_eq!(
+            occurrences("FREQ=YEARLY;BYDAY=-1SU", utc(2024, 12, 29, 9, 0), tz, 1),
+            vec![utc(2025, 12, 28, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn keeps_wall_clock_time_across_dst() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+
+        // 3 月 9 日进入夏令时，09:00 从 14:00Z 变为 13:00Z
+        assert_eq!(
+            occurrences("FREQ=DAILY", local(tz, 2025, 3, 8, 9, 0), tz, 2),
+            vec![utc(2025, 3, 9, 13, 0), utc(2025, 3, 10, 13, 0)]
+        );
+        // 11 月 2 日结束夏令时
+        assert_eq!(
+            occurrences("FREQ=WEEKLY", local(tz, 2025, 10, 27, 9, 0), tz, 1),
+            vec![utc(2025, 11, 3, 14, 0)]
+        );
+    }
+
+    #[test]
+    fn skipped_local_time_moves_forward_an_hour() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+
+        // 3 月 9 日 02:30 不存在，当天顺延到 03:30，之后恢复 02:30
+        assert_eq!(
+            occurrences("FREQ=DAILY", local(tz, 2025, 3, 8, 2, 30), tz, 2),
+            vec![utc(2025, 3, 9, 7, 30), utc(2025, 3, 10, 6, 30)]
+        );
+    }
+}
@@ -0,0 +1,769 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseBackend,
+    DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::dependencies;
+use crate::features::settings::core::service::SettingService;
+use crate::features::todo::data::{entities::todo_reminder, entity};
+
+const DEFAULT_ACTION: &str = "DISPLAY";
+
+const CATCH_UP_HOURS_KEY: &str = "todo.reminders.catch_up_hours";
+const DEFAULT_CATCH_UP_HOURS: i64 = 24;
+
+/// 相对触发的参照时间
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderRelated {
+    /// 开始时间（DTSTART，对应 `RELATED=START`）
+    Start,
+    /// 截止时间（DUE，对应 `RELATED=END`）
+    Due,
+}
+
+impl ReminderRelated {
+    fn as_str(self) -> &'static str {
+        match self {
+            ReminderRelated::Start => "start",
+            ReminderRelated::Due => "due",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "start" => Some(ReminderRelated::Start),
+            "due" => Some(ReminderRelated::Due),
+            _ => None,
+        }
+    }
+}
+
+/// 提醒的触发方式
+///
+/// JSON 形式：`{"type": "relative", "related": "due", "offset_minutes": -15}`
+/// 或 `{"type": "absolute", "at": "2025-01-20T09:00:00Z"}`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReminderTrigger {
+    /// 相对开始或截止时间，offset_minutes 为负数表示之前
+    Relative {
+        related: ReminderRelated,
+        offset_minutes: i32,
+    },
+    /// 在指定时间触发
+    Absolute { at: DateTime<Utc> },
+}
+
+impl ReminderTrigger {
+    /// 根据待办当前的开始/截止时间计算触发时间（相对截止时间但没有截止时间时为空）
+    pub fn resolve(&self, todo: &entity::Model) -> Option<DateTime<Utc>> {
+        match *self {
+            ReminderTrigger::Relative {
+                related,
+                offset_minutes,
+            } => {
+                let anchor = match related {
+                    ReminderRelated::Start => Some(todo.start_at),
+                    ReminderRelated::Due => todo.due_date,
+                };
+                anchor.map(|anchor| anchor + Duration::minutes(offset_minutes as i64))
+            }
+            ReminderTrigger::Absolute { at } => Some(at),
+        }
+    }
+}
+
+/// 新建或替换提醒时的输入，也用于 CalDAV 中解析出的 VALARM
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReminderInput {
+    pub trigger: ReminderTrigger,
+    /// VALARM 的 ACTION，默认 DISPLAY
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// 待办的一个提醒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoReminder {
+    pub id: i32,
+    pub todo_id: i32,
+    pub trigger: ReminderTrigger,
+    pub action: String,
+    pub description: Option<String>,
+    /// 按待办当前的开始/截止时间计算出的提醒时间
+    pub remind_at: Option<String>,
+    pub last_triggered_at: Option<String>,
+}
+
+/// 调度器下一次需要触发的提醒
+#[derive(Debug, Clone)]
+pub struct DueReminder {
+    pub todo: entity::Model,
+    /// 触发的提醒，为空表示稍后提醒（snoozed_until）
+    pub reminder_id: Option<i32>,
+    pub remind_at: DateTime<Utc>,
+}
+
+/// 应用关闭期间错过的提醒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedReminder {
+    pub todo_id: i32,
+    pub title: String,
+    /// 为空表示稍后提醒
+    pub reminder_id: Option<i32>,
+    pub remind_at: String,
+}
+
+/// 提醒设置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReminderSettings {
+    /// 启动时合并通知多少小时内错过的提醒（更早的直接标记为已提醒，0 表示全部丢弃）
+    pub catch_up_hours: i64,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            catch_up_hours: DEFAULT_CATCH_UP_HOURS,
+        }
+    }
+}
+
+/// 列出待办的所有提醒（按创建顺序）
+pub async fn list_reminders(db: &DatabaseConnection, todo_id: i32) -> Result<Vec<TodoReminder>> {
+    let todo = load_active(db, todo_id).await?;
+    load_reminders(db, &todo).await
+}
+
+/// 替换待办的全部提醒
+///
+/// 待办标记为 dirty，下次同步时更新 VALARM；
+/// reminder_offset_minutes 随第一个相对截止时间的提醒更新，保持旧接口可用
+pub async fn set_reminders(
+    db: &DatabaseConnection,
+    todo_id: i32,
+    reminders: Vec<ReminderInput>,
+) -> Result<Vec<TodoReminder>> {
+    for reminder in &reminders {
+        validate(reminder)?;
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start reminder transaction")?;
+    let todo = load_active(&txn, todo_id).await?;
+
+    delete_all(&txn, todo_id).await?;
+    for reminder in &reminders {
+        insert(&txn, todo_id, reminder, None).await?;
+    }
+
+    let todo = touch_todo(&txn, todo).await?;
+    let result = load_reminders(&txn, &todo).await?;
+    txn.commit()
+        .await
+        .context("failed to commit reminder transaction")?;
+
+    Ok(result)
+}
+
+/// 为待办添加一个提醒
+pub async fn add_reminder(
+    db: &DatabaseConnection,
+    todo_id: i32,
+    reminder: ReminderInput,
+) -> Result<Vec<TodoReminder>> {
+    validate(&reminder)?;
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start reminder transaction")?;
+    let todo = load_active(&txn, todo_id).await?;
+    insert(&txn, todo_id, &reminder, None).await?;
+
+    let todo = touch_todo(&txn, todo).await?;
+    let result = load_reminders(&txn, &todo).await?;
+    txn.commit()
+        .await
+        .context("failed to commit reminder transaction")?;
+
+    Ok(result)
+}
+
+/// 移除待办的一个提醒
+pub async fn remove_reminder(
+    db: &DatabaseConnection,
+    todo_id: i32,
+    reminder_id: i32,
+) -> Result<Vec<TodoReminder>> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start reminder transaction")?;
+    let todo = load_active(&txn, todo_id).await?;
+
+    let deleted = todo_reminder::Entity::delete_many()
+        .filter(todo_reminder::Column::Id.eq(reminder_id))
+        .filter(todo_reminder::Column::TodoId.eq(todo_id))
+        .exec(&txn)
+        .await
+        .with_context(|| format!("failed to remove reminder {reminder_id}"))?;
+    if deleted.rows_affected == 0 {
+        bail!("reminder {reminder_id} not found");
+    }
+
+    let todo = touch_todo(&txn, todo).await?;
+    let result = load_reminders(&txn, &todo).await?;
+    txn.commit()
+        .await
+        .context("failed to commit reminder transaction")?;
+
+    Ok(result)
+}
+
+/// 找到下一个需要触发的提醒（用于调度器）
+///
+/// 每个提醒单独触发，另外稍后提醒的待办在 snoozed_until 触发一次；
+/// 已删除、已完成和被阻塞的待办不提醒，相对截止时间但没有截止时间的提醒不触发
+pub async fn next_due_reminder(db: &DatabaseConnection) -> Result<Option<DueReminder>> {
+    Ok(load_pending(db, None, Some(1)).await?.into_iter().next())
+}
+
+/// 标记提醒已触发（用于调度器）
+///
+/// reminder_id 为空时表示稍后提醒已触发，清除 snoozed_until
+pub async fn mark_fired(
+    db: &DatabaseConnection,
+    todo_id: i32,
+    reminder_id: Option<i32>,
+) -> Result<()> {
+    mark_fired_in(db, todo_id, reminder_id, Utc::now()).await
+}
+
+/// 读取提醒设置
+pub async fn get_settings(db: &DatabaseConnection) -> Result<ReminderSettings> {
+    let default = ReminderSettings::default();
+    let catch_up_hours = SettingService::get(db, CATCH_UP_HOURS_KEY)
+        .await?
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|hours| *hours >= 0)
+        .unwrap_or(default.catch_up_hours);
+
+    Ok(ReminderSettings { catch_up_hours })
+}
+
+/// 保存提醒设置
+pub async fn set_settings(db: &DatabaseConnection, settings: ReminderSettings) -> Result<()> {
+    if settings.catch_up_hours < 0 {
+        bail!("catch_up_hours must not be negative");
+    }
+    SettingService::set(db, CATCH_UP_HOURS_KEY, &settings.catch_up_hours.to_string()).await?;
+    Ok(())
+}
+
+/// 启动时处理应用关闭期间错过的提醒
+///
+/// 所有已经过了触发时间的提醒都标记为已触发，不再逐个发送；
+/// 返回其中在 `catch_up_hours` 小时内的提醒（按触发时间排序），由调用方合并成一条通知，
+/// 更早的提醒直接丢弃
+pub async fn catch_up_missed(db: &DatabaseConnection) -> Result<Vec<MissedReminder>> {
+    let settings = get_settings(db).await?;
+    let now = Utc::now();
+    let since = now - Duration::hours(settings.catch_up_hours);
+
+    let missed = load_pending(db, Some(now), None).await?;
+    if missed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start reminder catch-up transaction")?;
+    for due in &missed {
+        mark_fired_in(&txn, due.todo.id, due.reminder_id, now).await?;
+    }
+    txn.commit()
+        .await
+        .context("failed to commit reminder catch-up")?;
+
+    Ok(missed
+        .into_iter()
+        .filter(|due| due.remind_at >= since)
+        .map(|due| MissedReminder {
+            todo_id: due.todo.id,
+            title: due.todo.title,
+            reminder_id: due.reminder_id,
+            remind_at: due.remind_at.to_rfc3339(),
+        })
+        .collect())
+}
+
+/// 为新建的待办添加默认提醒：截止时间之前 `minutes` 分钟
+pub(crate) async fn insert_default_in<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    minutes: i32,
+) -> Result<()> {
+    let reminder = ReminderInput {
+        trigger: ReminderTrigger::Relative {
+            related: ReminderRelated::Due,
+            offset_minutes: -minutes,
+        },
+        action: None,
+        description: None,
+    };
+    insert(db, todo_id, &reminder, None).await?;
+    Ok(())
+}
+
+/// 通过旧的 reminder_offset_minutes 修改提醒：更新第一个相对截止时间的提醒，没有时新建
+pub(crate) async fn set_due_offset_in<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    minutes: i32,
+) -> Result<()> {
+    let primary = todo_reminder::Entity::find()
+        .filter(todo_reminder::Column::TodoId.eq(todo_id))
+        .filter(todo_reminder::Column::Related.eq(ReminderRelated::Due.as_str()))
+        .order_by_asc(todo_reminder::Column::Id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load reminders of todo {todo_id}"))?;
+
+    match primary {
+        Some(reminder) => {
+            let mut active: todo_reminder::ActiveModel = reminder.into();
+            active.offset_minutes = Set(Some(-minutes));
+            active.last_triggered_at = Set(None);
+            active
+                .update(db)
+                .await
+                .with_context(|| format!("failed to update reminder of todo {todo_id}"))?;
+        }
+        None => insert_default_in(db, todo_id, minutes).await?,
+    }
+
+    Ok(())
+}
+
+/// 清除待办所有提醒的触发记录（开始/截止时间变化、重新打开待办时调用）
+pub(crate) async fn reset_in<C: ConnectionTrait>(db: &C, todo_id: i32) -> Result<()> {
+    todo_reminder::Entity::update_many()
+        .col_expr(
+            todo_reminder::Column::LastTriggeredAt,
+            sea_orm::sea_query::Expr::value(Option::<DateTime<Utc>>::None),
+        )
+        .filter(todo_reminder::Column::TodoId.eq(todo_id))
+        .exec(db)
+        .await
+        .with_context(|| format!("failed to reset reminders of todo {todo_id}"))?;
+    Ok(())
+}
+
+/// 重复待办原地推进到下一次发生：绝对时间的提醒同样后移，并清除触发记录
+pub(crate) async fn advance_in<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    shift: Duration,
+) -> Result<()> {
+    for reminder in load_rows(db, todo_id).await? {
+        let id = reminder.id;
+        let trigger_at = reminder.trigger_at.map(|at| at + shift);
+        let mut active: todo_reminder::ActiveModel = reminder.into();
+        active.trigger_at = Set(trigger_at);
+        active.last_triggered_at = Set(None);
+        active
+            .update(db)
+            .await
+            .with_context(|| format!("failed to advance reminder {id}"))?;
+    }
+    Ok(())
+}
+
+/// 复制提醒到重复待办的下一次发生，绝对时间的提醒后移 `shift`
+pub(crate) async fn copy_in<C: ConnectionTrait>(
+    db: &C,
+    from_todo_id: i32,
+    to_todo_id: i32,
+    shift: Duration,
+) -> Result<()> {
+    for reminder in load_rows(db, from_todo_id).await? {
+        let Some(trigger) = to_trigger(&reminder) else {
+            continue;
+        };
+        let trigger = match trigger {
+            ReminderTrigger::Absolute { at } => ReminderTrigger::Absolute { at: at + shift },
+            relative => relative,
+        };
+        let input = ReminderInput {
+            trigger,
+            action: Some(reminder.action),
+            description: reminder.description,
+        };
+        insert(db, to_todo_id, &input, None).await?;
+    }
+    Ok(())
+}
+
+/// 稍后提醒时跳过在 `until` 之前会触发的提醒，避免比稍后提醒更早地再次提醒
+pub(crate) async fn skip_until_in<C: ConnectionTrait>(
+    db: &C,
+    todo: &entity::Model,
+    until: DateTime<Utc>,
+) -> Result<()> {
+    let now = Utc::now();
+    for reminder in load_rows(db, todo.id).await? {
+        let due = to_trigger(&reminder)
+            .and_then(|trigger| trigger.resolve(todo))
+            .is_some_and(|time| time <= until);
+        if reminder.last_triggered_at.is_none() && due {
+            let id = reminder.id;
+            let mut active: todo_reminder::ActiveModel = reminder.into();
+            active.last_triggered_at = Set(Some(now));
+            active
+                .update(db)
+                .await
+                .with_context(|| format!("failed to skip reminder {id}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// 同步时生成 VALARM 所需的提醒
+pub(crate) async fn remote_alarms<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+) -> Result<Vec<ReminderInput>> {
+    Ok(load_rows(db, todo_id)
+        .await?
+        .into_iter()
+        .filter_map(|reminder| {
+            Some(ReminderInput {
+                trigger: to_trigger(&reminder)?,
+                action: Some(reminder.action),
+                description: reminder.description,
+            })
+        })
+        .collect())
+}
+
+/// 用远端的 VALARM 替换本地提醒
+///
+/// 提醒没有变化时不做修改；触发方式相同的提醒保留触发记录，避免同步后重复提醒
+pub(crate) async fn replace_from_remote<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    alarms: &[ReminderInput],
+) -> Result<()> {
+    let current = load_rows(db, todo_id).await?;
+    let unchanged = current.len() == alarms.len()
+        && current.iter().zip(alarms).all(|(reminder, alarm)| {
+            to_trigger(reminder) == Some(alarm.trigger)
+                && reminder.action == action_of(alarm)
+                && reminder.description == alarm.description
+        });
+    if unchanged {
+        return Ok(());
+    }
+
+    delete_all(db, todo_id).await?;
+    for alarm in alarms {
+        let last_triggered_at = current
+            .iter()
+            .find(|reminder| to_trigger(reminder) == Some(alarm.trigger))
+            .and_then(|reminder| reminder.last_triggered_at);
+        insert(db, todo_id, alarm, last_triggered_at).await?;
+    }
+
+    Ok(())
+}
+
+fn validate(reminder: &ReminderInput) -> Result<()> {
+    if let Some(action) = &reminder.action {
+        if action.trim().is_empty() {
+            bail!("reminder action cannot be empty");
+        }
+    }
+    Ok(())
+}
+
+fn action_of(reminder: &ReminderInput) -> String {
+    reminder
+        .action
+        .as_deref()
+        .map(|action| action.trim().to_ascii_uppercase())
+        .filter(|action| !action.is_empty())
+        .unwrap_or_else(|| DEFAULT_ACTION.to_string())
+}
+
+fn to_trigger(reminder: &todo_reminder::Model) -> Option<ReminderTrigger> {
+    match reminder.related.as_deref() {
+        Some(related) => Some(ReminderTrigger::Relative {
+            related: ReminderRelated::parse(related)?,
+            offset_minutes: reminder.offset_minutes.unwrap_or(0),
+        }),
+        None => reminder
+            .trigger_at
+            .map(|at| ReminderTrigger::Absolute { at }),
+    }
+}
+
+async fn insert<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    reminder: &ReminderInput,
+    last_triggered_at: Option<DateTime<Utc>>,
+) -> Result<todo_reminder::Model> {
+    let (related, offset_minutes, trigger_at) = match reminder.trigger {
+        ReminderTrigger::Relative {
+            related,
+            offset_minutes,
+        } => (
+            Some(related.as_str().to_string()),
+            Some(offset_minutes),
+            None,
+        ),
+        ReminderTrigger::Absolute { at } => (None, None, Some(at)),
+    };
+
+    todo_reminder::ActiveModel {
+        todo_id: Set(todo_id),
+        related: Set(related),
+        offset_minutes: Set(offset_minutes),
+        trigger_at: Set(trigger_at),
+        action: Set(action_of(reminder)),
+        description: Set(reminder
+            .description
+            .clone()
+            .filter(|description| !description.trim().is_empty())),
+        last_triggered_at: Set(last_triggered_at),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .with_context(|| format!("failed to add reminder to todo {todo_id}"))
+}
+
+async fn delete_all<C: ConnectionTrait>(db: &C, todo_id: i32) -> Result<()> {
+    todo_reminder::Entity::delete_many()
+        .filter(todo_reminder::Column::TodoId.eq(todo_id))
+        .exec(db)
+        .await
+        .with_context(|| format!("failed to clear reminders of todo {todo_id}"))?;
+    Ok(())
+}
+
+async fn load_rows<C: ConnectionTrait>(db: &C, todo_id: i32) -> Result<Vec<todo_reminder::Model>> {
+    todo_reminder::Entity::find()
+        .filter(todo_reminder::Column::TodoId.eq(todo_id))
+        .order_by_asc(todo_reminder::Column::Id)
+        .all(db)
+        .await
+        .with_context(|| format!("failed to load reminders of todo {todo_id}"))
+}
+
+async fn load_reminders<C: ConnectionTrait>(
+    db: &C,
+    todo: &entity::Model,
+) -> Result<Vec<TodoReminder>> {
+    Ok(load_rows(db, todo.id)
+        .await?
+        .into_iter()
+        .filter_map(|reminder| {
+            let trigger = to_trigger(&reminder)?;
+            Some(TodoReminder {
+                id: reminder.id,
+                todo_id: reminder.todo_id,
+                trigger,
+                action: reminder.action,
+                description: reminder.description,
+                remind_at: trigger.resolve(todo).map(|time| time.to_rfc3339()),
+                last_triggered_at: reminder.last_triggered_at.map(|time| time.to_rfc3339()),
+            })
+        })
+        .collect())
+}
+
+async fn load_active<C: ConnectionTrait>(db: &C, id: i32) -> Result<entity::Model> {
+    entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
+        .ok_or_else(|| anyhow!("todo {id} not found"))
+}
+
+/// 提醒修改后标记待办 dirty，并让 reminder_offset_minutes 跟随第一个相对截止时间的提醒
+async fn touch_todo<C: ConnectionTrait>(db: &C, model: entity::Model) -> Result<entity::Model> {
+    let id = model.id;
+    let offset =
+        load_rows(db, id)
+            .await?
+            .into_iter()
+            .find_map(|reminder| match to_trigger(&reminder)? {
+                ReminderTrigger::Relative {
+                    related: ReminderRelated::Due,
+                    offset_minutes,
+                } => Some(-offset_minutes),
+                _ => None,
+            });
+
+    let now = Utc::now();
+    let mut active: entity::ActiveModel = model.into();
+    if let Some(offset) = offset {
+        active.reminder_offset_minutes = Set(offset);
+    }
+    active.dirty = Set(true);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to mark todo {id} for sync"))
+}
+
+/// 尚未触发的提醒及其触发时间（含稍后提醒），按触发时间排序
+///
+/// 只查询未删除、未完成且未被阻塞的待办；触发时间在 SQL 中计算，
+/// `until` 限制最晚的触发时间，`limit` 限制返回数量
+async fn load_pending(
+    db: &DatabaseConnection,
+    until: Option<DateTime<Utc>>,
+    limit: Option<u64>,
+) -> Result<Vec<DueReminder>> {
+    let sql = format!(
+        r#"
+        SELECT reminder_id, todo_id FROM (
+            SELECT todo_reminders.id AS reminder_id, todos.id AS todo_id,
+                CASE
+                    WHEN todo_reminders.related IS NULL THEN julianday(todo_reminders.trigger_at)
+                    -- start_at 对应 created_date 列
+                    WHEN todo_reminders.related = 'start' THEN julianday(todos.created_date)
+                        + IFNULL(todo_reminders.offset_minutes, 0) / 1440.0
+                    WHEN todo_reminders.related = 'due' THEN julianday(todos.due_date)
+                        + IFNULL(todo_reminders.offset_minutes, 0) / 1440.0
+                END AS remind_at
+            FROM todo_reminders
+            JOIN todos ON todos.id = todo_reminders.todo_id
+            WHERE todo_reminders.last_triggered_at IS NULL
+                AND todos.deleted_at IS NULL AND todos.completed = 0 AND NOT {blocked}
+            UNION ALL
+            SELECT NULL, todos.id, julianday(todos.snoozed_until)
+            FROM todos
+            WHERE todos.snoozed_until IS NOT NULL
+                AND todos.deleted_at IS NULL AND todos.completed = 0 AND NOT {blocked}
+        )
+        WHERE remind_at IS NOT NULL AND (? IS NULL OR remind_at <= julianday(?))
+        ORDER BY remind_at, todo_id, reminder_id
+        LIMIT ?
+        "#,
+        // 被阻塞的待办推迟提醒，前置待办完成后重新调度
+        blocked = dependencies::BLOCKED_SQL,
+    );
+    let until = until.map(|time| time.to_rfc3339());
+    let limit = limit.map_or(-1, |limit| limit as i64);
+
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            sql,
+            [until.clone().into(), until.into(), limit.into()],
+        ))
+        .await
+        .context("failed to query pending reminders")?;
+
+    let mut pending = Vec::with_capacity(rows.len());
+    for row in rows {
+        let reminder_id: Option<i32> = row.try_get("", "reminder_id")?;
+        let todo_id: i32 = row.try_get("", "todo_id")?;
+        pending.push((reminder_id, todo_id));
+    }
+
+    let todo_ids: Vec<i32> = pending.iter().map(|(_, todo_id)| *todo_id).collect();
+    let todos: HashMap<i32, entity::Model> = entity::Entity::find()
+        .filter(entity::Column::Id.is_in(todo_ids))
+        .all(db)
+        .await
+        .context("failed to query todos with reminders")?
+        .into_iter()
+        .map(|todo| (todo.id, todo))
+        .collect();
+
+    let reminder_ids: Vec<i32> = pending.iter().filter_map(|(id, _)| *id).collect();
+    let reminders: HashMap<i32, todo_reminder::Model> = todo_reminder::Entity::find()
+        .filter(todo_reminder::Column::Id.is_in(reminder_ids))
+        .all(db)
+        .await
+        .context("failed to query pending reminders")?
+        .into_iter()
+        .map(|reminder| (reminder.id, reminder))
+        .collect();
+
+    Ok(pending
+        .into_iter()
+        .filter_map(|(reminder_id, todo_id)| {
+            let todo = todos.get(&todo_id)?;
+            let remind_at = match reminder_id {
+                Some(id) => to_trigger(reminders.get(&id)?)?.resolve(todo)?,
+                None => todo.snoozed_until?,
+            };
+            Some(DueReminder {
+                todo: todo.clone(),
+                reminder_id,
+                remind_at,
+            })
+        })
+        .collect())
+}
+
+async fn mark_fired_in<C: ConnectionTrait>(
+    db: &C,
+    todo_id: i32,
+    reminder_id: Option<i32>,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    if let Some(reminder_id) = reminder_id {
+        let reminder = todo_reminder::Entity::find_by_id(reminder_id)
+            .one(db)
+            .await
+            .with_context(|| format!("failed to load reminder {reminder_id}"))?
+            .ok_or_else(|| anyhow!("reminder {reminder_id} not found"))?;
+        let mut active: todo_reminder::ActiveModel = reminder.into();
+        active.last_triggered_at = Set(Some(now));
+        active
+            .update(db)
+            .await
+            .with_context(|| format!("failed to mark reminder {reminder_id} as fired"))?;
+    }
+
+    let model = entity::Entity::find_by_id(todo_id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {todo_id}"))?
+        .ok_or_else(|| anyhow!("todo {todo_id} not found"))?;
+
+    let mut active: entity::ActiveModel = model.into();
+    active.reminder_last_triggered_at = Set(Some(now));
+    if reminder_id.is_none() {
+        active.snoozed_until = Set(None);
+    }
+    active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to mark todo {todo_id} as reminded"))?;
+
+    Ok(())
+}
@@ -3,25 +3,35 @@ use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 
-use super::service;
+use super::{reminders, service};
 use crate::features::todo::api::notifications;
 use crate::infrastructure::notification::NotificationManager;
 
 /// 到期提醒调度器
 ///
 /// 工作流程：
-/// 1. 找到最近需要提醒的 Todo（due_date - reminder_offset_minutes 最早的）
+/// 0. 启动时把应用关闭期间错过的提醒合并成一条通知，并全部标记为已触发
+/// 1. 找到最近需要触发的提醒（每个待办的每个提醒单独触发，稍后提醒按 snoozed_until）
 /// 2. 等待到提醒时间点
 /// 3. 发送统一通知（Toast + WebSocket）
-/// 4. 标记为已提醒（更新 reminder_last_triggered_at）
-/// 5. 自动 reschedule 找下一个需要提醒的 Todo
+/// 4. 标记该提醒已触发（稍后提醒则清除 snoozed_until）
+/// 5. 自动 reschedule 找下一个需要触发的提醒
 pub struct DueNotificationScheduler {
     db: DatabaseConnection,
     notification_manager: Arc<NotificationManager>,
-    next_reminder: Arc<RwLock<Option<(i32, DateTime<Utc>)>>>, // (todo_id, reminder_time)
+    next_reminder: Arc<RwLock<Option<NextReminder>>>,
     reschedule_tx: mpsc::Sender<()>,
 }
 
+/// 已调度的下一次提醒
+#[derive(Debug, Clone, Copy)]
+struct NextReminder {
+    todo_id: i32,
+    /// 为空表示稍后提醒
+    reminder_id: Option<i32>,
+    reminder_time: DateTime<Utc>,
+}
+
 impl Clone for DueNotificationScheduler {
     fn clone(&self) -> Self {
         Self {
@@ -37,7 +47,7 @@ impl DueNotificationScheduler {
     /// 创建新的调度器
     pub fn new(db: DatabaseConnection, notification_manager: Arc<NotificationManager>) -> Self {
         let (reschedule_tx, mut reschedule_rx) = mpsc::channel::<()>(32);
-        let next_reminder = Arc::new(RwLock::new(None::<(i32, DateTime<Utc>)>));
+        let next_reminder = Arc::new(RwLock::new(None::<NextReminder>));
 
         let scheduler = Self {
             db,
@@ -51,6 +61,9 @@ impl DueNotificationScheduler {
         tauri::async_runtime::spawn(async move {
             println!("[Scheduler] 后台任务已启动");
 
+            // 先处理错过的提醒，避免启动时逐个弹出
+            scheduler_clone.catch_up_missed_reminders().await;
+
             // 初始化时计算一次
             scheduler_clone.schedule_next_reminder().await;
 
@@ -83,11 +96,30 @@ impl DueNotificationScheduler {
         }
     }
 
+    /// 合并通知应用关闭期间错过的提醒
+    async fn catch_up_missed_reminders(&self) {
+        match reminders::catch_up_missed(&self.db).await {
+            Ok(missed) if missed.is_empty() => {}
+            Ok(missed) => {
+                println!("[Scheduler] 应用关闭期间错过了 {} 个提醒", missed.len());
+                notifications::notify_todos_missed(&self.notification_manager, &missed);
+            }
+            Err(e) => {
+                eprintln!("[Scheduler] 处理错过的提醒时出错: {}", e);
+            }
+        }
+    }
+
     /// 等待到下次提醒时间
     async fn wait_until_next_reminder(&self) {
         loop {
-            let next = self.next_reminder.read().await.clone();
-            if let Some((todo_id, reminder_time)) = next {
+            let next = *self.next_reminder.read().await;
+            if let Some(NextReminder {
+                todo_id,
+                reminder_time,
+                ..
+            }) = next
+            {
                 let now = Utc::now();
                 if reminder_time > now {
                     let duration = (reminder_time - now)
@@ -108,25 +140,25 @@ impl DueNotificationScheduler {
 
     /// 计算并调度下次提醒
     async fn schedule_next_reminder(&self) {
-        println!("[Scheduler] 查找下一个需要提醒的 Todo...");
-        match service::get_next_reminder_todo(&self.db).await {
-            Ok(Some(todo)) => {
-                // 计算提醒时间 = due_date - reminder_offset_minutes
-                if let Some(due_date) = todo.due_date {
-                    let offset_minutes = todo.reminder_offset_minutes;
-                    let reminder_time = due_date - chrono::Duration::minutes(offset_minutes as i64);
-
-                    *self.next_reminder.write().await = Some((todo.id, reminder_time));
-                    println!(
-                        "[Scheduler] 下次提醒: Todo#{} \"{}\" 在 {}",
-                        todo.id,
-                        todo.title,
-                        reminder_time.format("%Y-%m-%d %H:%M:%S")
-                    );
-                } else {
-                    eprintln!("[Scheduler] Todo#{} 缺少 due_date", todo.id);
-                    *self.next_reminder.write().await = None;
-                }
+        println!("[Scheduler] 查找下一个需要触发的提醒...");
+        match reminders::next_due_reminder(&self.db).await {
+            Ok(Some(due)) => {
+                *self.next_reminder.write().await = Some(NextReminder {
+                    todo_id: due.todo.id,
+                    reminder_id: due.reminder_id,
+                    reminder_time: due.remind_at,
+                });
+                println!(
+                    "[Scheduler] 下次提醒: Todo#{} \"{}\" 在 {}{}",
+                    due.todo.id,
+                    due.todo.title,
+                    due.remind_at.format("%Y-%m-%d %H:%M:%S"),
+                    if due.reminder_id.is_none() {
+                        "（稍后提醒）"
+                    } else {
+                        ""
+                    }
+                );
             }
             Ok(None) => {
                 *self.next_reminder.write().await = None;
@@ -141,9 +173,14 @@ impl DueNotificationScheduler {
 
     /// 发送提醒并自动重新调度
     async fn send_reminder_and_reschedule(&self) -> anyhow::Result<()> {
-        let next = self.next_reminder.read().await.clone();
-
-        if let Some((todo_id, _)) = next {
+        let next = *self.next_reminder.read().await;
+
+        if let Some(NextReminder {
+            todo_id,
+            reminder_id,
+            ..
+        }) = next
+        {
             println!("[Scheduler] 发送提醒给 Todo#{}", todo_id);
 
             // 获取 Todo 详情
@@ -153,8 +190,8 @@ impl DueNotificationScheduler {
             notifications::notify_todo_due(&self.notification_manager, todo.id, &todo.title);
             println!("[Scheduler] 通知已发送 (Todo#{})", todo_id);
 
-            // 标记为已提醒
-            service::mark_todo_reminded(&self.db, todo_id).await?;
+            // 标记该提醒已触发
+            reminders::mark_fired(&self.db, todo_id, reminder_id).await?;
             println!("[Scheduler] Todo#{} 已标记为已提醒", todo_id);
 
             // 自动重新调度找下一个
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use sea_orm::{
+    sea_query::{Expr, LikeExpr},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Statement,
+};
+use serde::{Deserialize, Serialize};
+
+use super::models::Todo;
+use super::query::escape_like;
+use crate::features::todo::data::entity;
+
+const DEFAULT_SEARCH_LIMIT: u64 = 50;
+const MAX_SEARCH_LIMIT: u64 = 200;
+
+/// trigram 分词器只能匹配不少于 3 个字符的词
+const MIN_TRIGRAM_CHARS: usize = 3;
+
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+/// 一条搜索结果
+///
+/// 高亮片段中的原文没有做 HTML 转义，前端渲染时需要自行处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoSearchHit {
+    pub todo: Todo,
+    /// 相关度得分，越大越相关
+    pub score: f64,
+    /// 高亮后的标题
+    pub title_highlight: String,
+    /// 描述中命中的片段（高亮）
+    pub description_snippet: Option<String>,
+}
+
+/// 全文搜索待办（标题、描述、地点、标签）
+///
+/// 多个关键词之间为 AND 关系；结果按 bm25 相关度排序，标题权重最高。
+/// 含有少于 3 个字符的关键词（例如两个字的中文词）时无法使用 trigram 索引，
+/// 退化为 LIKE 匹配，按最近更新排序。
+pub async fn search_todos(
+    db: &DatabaseConnection,
+    query: &str,
+    limit: Option<u64>,
+) -> Result<Vec<TodoSearchHit>> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    if terms
+        .iter()
+        .all(|term| term.chars().count() >= MIN_TRIGRAM_CHARS)
+    {
+        search_with_index(db, &terms, limit).await
+    } else {
+        search_with_like(db, &terms, limit).await
+    }
+}
+
+/// 重建全文索引（用于批量变更之后，例如 CalDAV 同步）
+pub async fn rebuild_index<C: ConnectionTrait>(db: &C) -> Result<()> {
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO todos_fts(todos_fts) VALUES ('rebuild');".to_string(),
+    ))
+    .await
+    .context("failed to rebuild todo search index")?;
+
+    Ok(())
+}
+
+async fn search_with_index(
+    db: &DatabaseConnection,
+    terms: &[&str],
+    limit: u64,
+) -> Result<Vec<TodoSearchHit>> {
+    // 每个关键词作为短语加引号，避免用户输入被解析成 FTS5 语法
+    let match_expr = terms
+        .iter()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let sql = format!(
+        r#"
+        SELECT
+            todos.id AS id,
+            bm25(todos_fts, 10.0, 2.0, 1.0, 4.0) AS rank,
+            highlight(todos_fts, 0, '{start}', '{end}') AS title_highlight,
+            snippet(todos_fts, 1, '{start}', '{end}', '…', 16) AS description_snippet
+        FROM todos_fts
+        JOIN todos ON todos.id = todos_fts.rowid
+        WHERE todos_fts MATCH ? AND todos.deleted_at IS NULL
+        ORDER BY rank
+        LIMIT ?
+        "#,
+        start = HIGHLIGHT_START,
+        end = HIGHLIGHT_END,
+    );
+
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            sql,
+            [match_expr.into(), (limit as i64).into()],
+        ))
+        .await
+        .context("failed to search todos")?;
+
+    let mut hits = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: i32 = row.try_get("", "id")?;
+        let rank: f64 = row.try_get("", "rank")?;
+        let title_highlight: String = row.try_get("", "title_highlight")?;
+        let description_snippet: Option<String> = row.try_get("", "description_snippet")?;
+        hits.push((id, rank, title_highlight, description_snippet));
+    }
+
+    let ids: Vec<i32> = hits.iter().map(|(id, ..)| *id).collect();
+    let mut models: HashMap<i32, entity::Model> = entity::Entity::find()
+        .filter(entity::Column::Id.is_in(ids))
+        .all(db)
+        .await
+        .context("failed to load search results")?
+        .into_iter()
+        .map(|model| (model.id, model))
+        .collect();
+
+    Ok(hits
+        .into_iter()
+        .filter_map(|(id, rank, title_highlight, description_snippet)| {
+            let model = models.remove(&id)?;
+            // snippet() 在描述未命中时返回描述开头，只保留真正命中的片段
+            let description_snippet =
+                description_snippet.filter(|snippet| snippet.contains(HIGHLIGHT_START));
+            Some(TodoSearchHit {
+                todo: model.into(),
+                score: -rank,
+                title_highlight,
+                description_snippet,
+            })
+        })
+        .collect())
+}
+
+async fn search_with_like(
+    db: &DatabaseConnection,
+    terms: &[&str],
+    limit: u64,
+) -> Result<Vec<TodoSearchHit>> {
+    let mut condition = Condition::all();
+    for term in terms {
+        let pattern = format!("%{}%", escape_like(term));
+        let like = || LikeExpr::new(&pattern).escape('\\');
+        condition = condition.add(
+            Condition::any()
+                .add(Expr::col(entity::Column::Title).like(like()))
+                .add(Expr::col(entity::Column::Description).like(like()))
+                .add(Expr::col(entity::Column::Location).like(like()))
+                .add(Expr::col(entity::Column::Tags).like(like())),
+        );
+    }
+
+    let models = entity::Entity::find()
+        .filter(entity::Column::DeletedAt.is_null())
+        .filter(condition)
+        .order_by_desc(entity::Column::UpdatedAt)
+        .limit(limit)
+        .all(db)
+        .await
+        .context("failed to search todos")?;
+
+    Ok(models
+        .into_iter()
+        .map(|model| {
+            let title_highlight = highlight_terms(&model.title, terms);
+            let description_snippet = model
+                .description
+                .as_deref()
+                .map(|description| highlight_terms(description, terms))
+                .filter(|snippet| snippet.contains(HIGHLIGHT_START));
+            TodoSearchHit {
+                todo: model.into(),
+                score: 0.0,
+                title_highlight,
+                description_snippet,
+            }
+        })
+        .collect())
+}
+
+/// 在文本中为关键词加上高亮标记（ASCII 大小写不敏感，与 SQLite LIKE 一致）
+fn highlight_terms(text: &str, terms: &[&str]) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for term in terms {
+        let needle = term.to_ascii_lowercase();
+        if needle.is_empty() {
+            continue;
+        }
+        let mut from = 0;
+        while let Some(pos) = lower[from..].find(&needle) {
+            let start = from + pos;
+            let end = start + needle.len();
+            ranges.push((start, end));
+            from = end;
+        }
+    }
+
+    if ranges.is_empty() {
+        return text.to_string();
+    }
+
+    ranges.sort_unstable();
+    let mut result = String::with_capacity(text.len() + ranges.len() * 13);
+    let mut cursor = 0;
+    for (start, end) in ranges {
+        if start < cursor {
+            // 与上一个命中重叠，只延长
+            if end > cursor {
+                let trimmed = result.len() - HIGHLIGHT_END.len();
+                result.truncate(trimmed);
+                result.push_str(&text[cursor..end]);
+                result.push_str(HIGHLIGHT_END);
+                cursor = end;
+            }
+            continue;
+        }
+        result.push_str(&text[cursor..start]);
+        result.push_str(HIGHLIGHT_START);
+        result.push_str(&text[start..end]);
+        result.push_str(HIGHLIGHT_END);
+        cursor = end;
+    }
+    result.push_str(&text[cursor..]);
+    result
+}
@@ -1,36 +1,57 @@
 use std::borrow::Cow;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use chrono::{DateTime, Utc};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
 };
 use uuid::Uuid;
 
+use super::journal::{self, Operation};
+use super::lists;
 use super::models::Todo;
+use super::ordering;
+use super::recurrence::{self, NextOccurrence, RecurrenceMode};
+use super::reminders;
+use super::subtasks;
 use crate::features::todo::data::entity;
 
 const DEFAULT_STATUS: &str = "NEEDS-ACTION";
 const COMPLETED_STATUS: &str = "COMPLETED";
 const DEFAULT_REMINDER_MINUTES: i32 = 15;
+/// 稍后提醒最多推迟的天数
+const MAX_SNOOZE_DAYS: i64 = 366;
 
 pub async fn list_todos(db: &DatabaseConnection) -> Result<Vec<Todo>> {
-    // 清理孤立的已删除项（deleted_at 不为空但没有 remote_url 的项）
-    // 这些可能是因为删除后 CalDAV 配置被移除导致的
-    cleanup_orphaned_deleted_todos(db).await?;
-
-    let todos = entity::Entity::find()
-        .filter(entity::Column::DeletedAt.is_null())
-        .all(db)
-        .await
-        .context("failed to load todos")?;
+    let todos = ordering::order_by_position(
+        entity::Entity::find().filter(entity::Column::DeletedAt.is_null()),
+    )
+    .all(db)
+    .await
+    .context("failed to load todos")?;
 
     Ok(todos.into_iter().map(Into::into).collect())
 }
 
 pub async fn create_todo(db: &DatabaseConnection, title: Option<String>) -> Result<Todo> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start create transaction")?;
+    let todo = create_todo_in(&txn, &journal::new_batch_id(), title).await?;
+    journal::commit(txn).await?;
+
+    Ok(todo)
+}
+
+/// 在给定连接（通常是事务）中创建 todo，并记录到操作日志的指定批次
+pub(crate) async fn create_todo_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    title: Option<String>,
+) -> Result<Todo> {
     let now = Utc::now();
     let normalized_title = normalize_title(title);
 
@@ -40,6 +61,8 @@ pub async fn create_todo(db: &DatabaseConnection, title: Option<String>) -> Resu
     let model = entity::ActiveModel {
         id: NotSet,
         parent_id: Set(None),
+        list_id: Set(None),
+        sort_order: Set(None),
         uid: Set(Uuid::new_v4().to_string()),
         title: Set(normalized_title.into_owned()),
         description: Set(None),
@@ -57,6 +80,8 @@ pub async fn create_todo(db: &DatabaseConnection, title: Option<String>) -> Resu
         timezone: Set(Some(local_timezone)), // 设置本地时区
         reminder_method: Set(Some("display".to_string())),
         reminder_last_triggered_at: Set(None),
+        snoozed_until: Set(None),
+        estimated_pomodoros: Set(None),
         completed_at: Set(None),
         notified: Set(false),
         dirty: Set(true),
@@ -66,6 +91,7 @@ pub async fn create_todo(db: &DatabaseConnection, title: Option<String>) -> Resu
         sync_token: Set(None),
         last_synced_at: Set(None),
         deleted_at: Set(None),
+        purged_at: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
     }
@@ -73,6 +99,9 @@ pub async fn create_todo(db: &DatabaseConnection, title: Option<String>) -> Resu
     .await
     .context("failed to insert todo")?;
 
+    reminders::insert_default_in(db, model.id, DEFAULT_REMINDER_MINUTES).await?;
+    journal::record(db, batch_id, Operation::Create, None, Some(&model)).await?;
+
     Ok(model.into())
 }
 
@@ -90,6 +119,28 @@ pub async fn update_todo(
     id: i32,
     title: Option<String>,
     completed: Option<bool>,
+) -> Result<Todo> {
+    let mode = recurrence::get_mode(db).await?;
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start update transaction")?;
+    let todo = update_todo_in(&txn, &journal::new_batch_id(), mode, id, title, completed).await?;
+    journal::commit(txn).await?;
+
+    Ok(todo)
+}
+
+/// 在给定连接中更新 todo 的标题/完成状态，并记录到操作日志的指定批次
+///
+/// `mode` 为重复待办的推进方式，由调用方预先读取
+pub(crate) async fn update_todo_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    mode: RecurrenceMode,
+    id: i32,
+    title: Option<String>,
+    completed: Option<bool>,
 ) -> Result<Todo> {
     let model = entity::Entity::find_by_id(id)
         .one(db)
@@ -97,6 +148,32 @@ pub async fn update_todo(
         .with_context(|| format!("failed to load todo {id}"))?
         .ok_or_else(|| anyhow!("todo {id} not found"))?;
 
+    // 完成重复待办时推进到下一次发生，而不是直接标记为完成
+    if completed == Some(true) && !model.completed {
+        if let Some(rule) = model
+            .recurrence_rule
+            .clone()
+            .filter(|rule| !rule.trim().is_empty())
+        {
+            match recurrence::next_occurrence(
+                &rule,
+                model.due_date,
+                model.start_at,
+                model.timezone.as_deref(),
+            ) {
+                Ok(Some(next)) => {
+                    return complete_recurring_todo(db, batch_id, mode, model, title, next).await
+                }
+                // 序列已结束（COUNT 用尽或超过 UNTIL），按普通待办完成
+                Ok(None) => {}
+                Err(err) => {
+                    eprintln!("[Service] 无法解析 Todo#{id} 的重复规则 \"{rule}\": {err:#}");
+                }
+            }
+        }
+    }
+
+    let before = model.clone();
     let mut active: entity::ActiveModel = model.into();
     let now = Utc::now();
 
@@ -113,12 +190,14 @@ pub async fn update_todo(
             active.completed_at = Set(Some(now));
             // 完成后清除提醒记录
             active.reminder_last_triggered_at = Set(None);
+            active.snoozed_until = Set(None);
         } else {
             active.status = Set(DEFAULT_STATUS.to_string());
             active.percent_complete = Set(Some(0));
             active.completed_at = Set(None);
             // 取消完成时也清除提醒记录，允许重新提醒
             active.reminder_last_triggered_at = Set(None);
+            active.snoozed_until = Set(None);
         }
     }
 
@@ -131,9 +210,255 @@ pub async fn update_todo(
         .await
         .with_context(|| format!("failed to update todo {id}"))?;
 
+    journal::record(
+        db,
+        batch_id,
+        Operation::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    if updated.completed != before.completed {
+        reminders::reset_in(db, id).await?;
+        // 级联完成子任务/祖先并更新进度汇总，可能再次修改当前待办
+        subtasks::apply_completion_in(db, batch_id, &updated).await?;
+        return reload(db, id).await;
+    }
+
     Ok(updated.into())
 }
 
+/// 完成一个重复待办并推进到下一次发生
+///
+/// 根据设置选择推进方式：
+/// - 原地推进：保留 UID，移动 DUE/DTSTART，重置完成状态与提醒记录，子任务一并重置
+/// - 生成后继：原待办标记完成并移除 RRULE，复制一个新待办（连同子任务树）作为下一次发生
+async fn complete_recurring_todo<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    mode: RecurrenceMode,
+    model: entity::Model,
+    title: Option<String>,
+    next: NextOccurrence,
+) -> Result<Todo> {
+    let id = model.id;
+    let now = Utc::now();
+    let descendants = load_descendants(db, id).await?;
+    let before = model.clone();
+
+    let updated = match mode {
+        RecurrenceMode::InPlace => {
+            let mut active: entity::ActiveModel = model.into();
+            if let Some(title) = title {
+                active.title = Set(normalize_title(Some(title)).into_owned());
+            }
+            active.due_date = Set(next.due_date);
+            active.start_at = Set(next.start_at);
+            active.recurrence_rule = Set(Some(next.rule.clone()));
+            reset_for_next_occurrence(&mut active, now);
+
+            let updated = active
+                .update(db)
+                .await
+                .with_context(|| format!("failed to advance recurring todo {id}"))?;
+            reminders::advance_in(db, id, next.shift).await?;
+            journal::record(
+                db,
+                batch_id,
+                Operation::Update,
+                Some(&before),
+                Some(&updated),
+            )
+            .await?;
+
+            for child in descendants {
+                let child_id = child.id;
+                let mut child_active: entity::ActiveModel = child.clone().into();
+                child_active.due_date = Set(child.due_date.map(|due| due + next.shift));
+                child_active.start_at = Set(child.start_at + next.shift);
+                reset_for_next_occurrence(&mut child_active, now);
+                let child_updated = child_active
+                    .update(db)
+                    .await
+                    .with_context(|| format!("failed to reset subtask {child_id}"))?;
+                reminders::advance_in(db, child_id, next.shift).await?;
+                journal::record(
+                    db,
+                    batch_id,
+                    Operation::Update,
+                    Some(&child),
+                    Some(&child_updated),
+                )
+                .await?;
+            }
+
+            updated
+        }
+        RecurrenceMode::Successor => {
+            let mut active: entity::ActiveModel = model.into();
+            if let Some(title) = title {
+                active.title = Set(normalize_title(Some(title)).into_owned());
+            }
+            active.completed = Set(true);
+            active.status = Set(COMPLETED_STATUS.to_string());
+            active.percent_complete = Set(Some(100));
+            active.completed_at = Set(Some(now));
+            active.reminder_last_triggered_at = Set(None);
+            active.snoozed_until = Set(None);
+            // 已完成的实例不再重复，后续由新待办承接
+            active.recurrence_rule = Set(None);
+            active.last_modified_at = Set(now);
+            active.updated_at = Set(now);
+            active.dirty = Set(true);
+
+            let updated = active
+                .update(db)
+                .await
+                .with_context(|| format!("failed to complete recurring todo {id}"))?;
+            journal::record(
+                db,
+                batch_id,
+                Operation::Update,
+                Some(&before),
+                Some(&updated),
+            )
+            .await?;
+
+            let successor = clone_for_next_occurrence(
+                db,
+                &before,
+                before.parent_id,
+                next.due_date,
+                before.start_at + next.shift,
+                Some(next.rule.clone()),
+                now,
+            )
+            .await?;
+            reminders::copy_in(db, id, successor.id, next.shift).await?;
+            journal::record(db, batch_id, Operation::Create, None, Some(&successor)).await?;
+
+            // 按父子关系复制子任务树（descendants 已按层级顺序排列）
+            let mut id_map = std::collections::HashMap::new();
+            id_map.insert(id, successor.id);
+            for child in &descendants {
+                let Some(new_parent) = child.parent_id.and_then(|pid| id_map.get(&pid).copied())
+                else {
+                    continue;
+                };
+                let copied = clone_for_next_occurrence(
+                    db,
+                    child,
+                    Some(new_parent),
+                    child.due_date.map(|due| due + next.shift),
+                    child.start_at + next.shift,
+                    child.recurrence_rule.clone(),
+                    now,
+                )
+                .await?;
+                reminders::copy_in(db, child.id, copied.id, next.shift).await?;
+                journal::record(db, batch_id, Operation::Create, None, Some(&copied)).await?;
+                id_map.insert(child.id, copied.id);
+            }
+
+            updated
+        }
+    };
+
+    // 推进后祖先的进度和完成状态可能随之改变
+    subtasks::apply_recurrence_in(db, batch_id, &updated).await?;
+    reload(db, id).await
+}
+
+/// 将待办重置为未完成、未提醒状态（用于重复待办的下一次发生）
+fn reset_for_next_occurrence(active: &mut entity::ActiveModel, now: DateTime<Utc>) {
+    active.completed = Set(false);
+    active.status = Set(DEFAULT_STATUS.to_string());
+    active.percent_complete = Set(Some(0));
+    active.completed_at = Set(None);
+    active.notified = Set(false);
+    active.reminder_last_triggered_at = Set(None);
+    active.snoozed_until = Set(None);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active.dirty = Set(true);
+}
+
+/// 复制一个待办作为下一次发生（新 UID，不带远端信息，等待同步创建）
+async fn clone_for_next_occurrence<C: ConnectionTrait>(
+    db: &C,
+    source: &entity::Model,
+    parent_id: Option<i32>,
+    due_date: Option<DateTime<Utc>>,
+    start_at: DateTime<Utc>,
+    recurrence_rule: Option<String>,
+    now: DateTime<Utc>,
+) -> Result<entity::Model> {
+    entity::ActiveModel {
+        id: NotSet,
+        parent_id: Set(parent_id),
+        list_id: Set(source.list_id),
+        sort_order: Set(source.sort_order),
+        uid: Set(Uuid::new_v4().to_string()),
+        title: Set(source.title.clone()),
+        description: Set(source.description.clone()),
+        completed: Set(false),
+        status: Set(DEFAULT_STATUS.to_string()),
+        percent_complete: Set(Some(0)),
+        priority: Set(source.priority),
+        location: Set(source.location.clone()),
+        tags: Set(source.tags.clone()),
+        start_at: Set(start_at),
+        last_modified_at: Set(now),
+        due_date: Set(due_date),
+        recurrence_rule: Set(recurrence_rule),
+        reminder_offset_minutes: Set(source.reminder_offset_minutes),
+        timezone: Set(source.timezone.clone()),
+        reminder_method: Set(source.reminder_method.clone()),
+        reminder_last_triggered_at: Set(None),
+        snoozed_until: Set(None),
+        estimated_pomodoros: Set(source.estimated_pomodoros),
+        completed_at: Set(None),
+        notified: Set(false),
+        dirty: Set(true),
+        remote_url: Set(None),
+        remote_etag: Set(None),
+        remote_calendar_url: Set(source.remote_calendar_url.clone()),
+        sync_token: Set(None),
+        last_synced_at: Set(None),
+        deleted_at: Set(None),
+        purged_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .with_context(|| format!("failed to create next occurrence of todo {}", source.id))
+}
+
+/// 按层级顺序加载某个待办的全部未删除后代
+pub(crate) async fn load_descendants<C: ConnectionTrait>(
+    db: &C,
+    id: i32,
+) -> Result<Vec<entity::Model>> {
+    let mut result = Vec::new();
+    let mut frontier = vec![id];
+
+    while !frontier.is_empty() {
+        let children = entity::Entity::find()
+            .filter(entity::Column::ParentId.is_in(frontier.clone()))
+            .filter(entity::Column::DeletedAt.is_null())
+            .all(db)
+            .await
+            .with_context(|| format!("failed to load descendants of todo {id}"))?;
+
+        frontier = children.iter().map(|child| child.id).collect();
+        result.extend(children);
+    }
+
+    Ok(result)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn update_todo_details(
     db: &DatabaseConnection,
@@ -148,6 +473,48 @@ pub async fn update_todo_details(
     reminder_offset_minutes: Option<i32>,
     reminder_method: Option<String>,
     timezone: Option<String>,
+) -> Result<Todo> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start update transaction")?;
+    let todo = update_todo_details_in(
+        &txn,
+        &journal::new_batch_id(),
+        id,
+        description,
+        priority,
+        location,
+        tags,
+        start_at,
+        due_date,
+        recurrence_rule,
+        reminder_offset_minutes,
+        reminder_method,
+        timezone,
+    )
+    .await?;
+    journal::commit(txn).await?;
+
+    Ok(todo)
+}
+
+/// 在给定连接中更新 todo 详情，并记录到操作日志的指定批次
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn update_todo_details_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    description: Option<String>,
+    priority: Option<i32>,
+    location: Option<String>,
+    tags: Vec<String>,
+    start_at: Option<String>,
+    due_date: Option<String>,
+    recurrence_rule: Option<String>,
+    reminder_offset_minutes: Option<i32>,
+    reminder_method: Option<String>,
+    timezone: Option<String>,
 ) -> Result<Todo> {
     let model = entity::Entity::find_by_id(id)
         .one(db)
@@ -156,8 +523,10 @@ pub async fn update_todo_details(
         .ok_or_else(|| anyhow!("todo {id} not found"))?;
 
     let previous_due_date = model.due_date;
+    let previous_start_at = model.start_at;
     let previous_reminder_offset = model.reminder_offset_minutes;
 
+    let before = model.clone();
     let mut active: entity::ActiveModel = model.into();
     let now = Utc::now();
 
@@ -181,8 +550,9 @@ pub async fn update_todo_details(
     if parsed_due != previous_due_date {
         active.due_date = Set(parsed_due);
         active.notified = Set(false);
-        // 清除提醒记录，因为提醒时间可能变化
+        // 清除提醒记录和稍后提醒，因为提醒时间可能变化
         active.reminder_last_triggered_at = Set(None);
+        active.snoozed_until = Set(None);
     }
 
     active.recurrence_rule = Set(recurrence_rule);
@@ -191,8 +561,9 @@ pub async fn update_todo_details(
         if minutes != previous_reminder_offset {
             active.reminder_offset_minutes = Set(minutes);
             active.notified = Set(false);
-            // 清除提醒记录，因为提醒时间变化了
+            // 清除提醒记录和稍后提醒，因为提醒时间变化了
             active.reminder_last_triggered_at = Set(None);
+            active.snoozed_until = Set(None);
         }
     }
 
@@ -208,50 +579,96 @@ pub async fn update_todo_details(
         .await
         .with_context(|| format!("failed to update todo details {id}"))?;
 
+    // 开始/截止时间变化后所有提醒的时间都可能变化，重新允许触发
+    if updated.due_date != previous_due_date || updated.start_at != previous_start_at {
+        reminders::reset_in(db, id).await?;
+    }
+    if updated.reminder_offset_minutes != previous_reminder_offset {
+        reminders::set_due_offset_in(db, id, updated.reminder_offset_minutes).await?;
+    }
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::UpdateDetails,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
     Ok(updated.into())
 }
 
-/// 删除 todo：根据是否有远端同步配置决定删除策略
-/// - 如果 todo 有 remote_url（已同步到 CalDAV）：软删除，标记 deleted_at 和 dirty，等待同步删除远端
-/// - 如果 todo 没有 remote_url（纯本地）：直接硬删除
+/// 删除 todo：移入回收站（软删除）
+///
+/// 子任务随父任务一起移入回收站，并使用相同的 deleted_at，以便一起恢复。
+/// 已同步到 CalDAV 的项会在下次同步时删除远端资源，本地记录保留在回收站中。
 pub async fn delete_todo(db: &DatabaseConnection, id: i32) -> Result<()> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start delete transaction")?;
+    delete_todo_in(&txn, &journal::new_batch_id(), id).await?;
+    journal::commit(txn).await
+}
+
+/// 在给定连接中将 todo 及其子任务移入回收站，并记录到操作日志的指定批次
+pub(crate) async fn delete_todo_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+) -> Result<()> {
     let model = entity::Entity::find_by_id(id)
         .one(db)
         .await
         .with_context(|| format!("failed to find todo {id}"))?
         .ok_or_else(|| anyhow!("todo {id} not found"))?;
 
-    // 如果有远端 URL，说明已同步到 CalDAV，需要软删除等待同步
-    if model.remote_url.is_some() {
-        let now = Utc::now();
-        let mut active: entity::ActiveModel = model.into();
+    if model.deleted_at.is_some() {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let parent_id = model.parent_id;
+    let descendants = load_descendants(db, id).await?;
+
+    for item in std::iter::once(model).chain(descendants) {
+        let item_id = item.id;
+        let before = item.clone();
+        let mut active: entity::ActiveModel = item.into();
         active.deleted_at = Set(Some(now));
         active.dirty = Set(true);
         active.updated_at = Set(now);
 
-        active
+        let trashed = active
             .update(db)
             .await
-            .with_context(|| format!("failed to mark todo {id} as deleted"))?;
-    } else {
-        // 纯本地 todo，直接硬删除
-        entity::Entity::delete_by_id(id)
-            .exec(db)
-            .await
-            .with_context(|| format!("failed to delete todo {id}"))?;
+            .with_context(|| format!("failed to move todo {item_id} to trash"))?;
+        journal::record(
+            db,
+            batch_id,
+            Operation::Delete,
+            Some(&before),
+            Some(&trashed),
+        )
+        .await?;
     }
 
+    subtasks::rollup_in(db, batch_id, parent_id).await?;
+
     Ok(())
 }
 
 /// 获取指定任务的所有子任务
 pub async fn get_subtasks(db: &DatabaseConnection, parent_id: i32) -> Result<Vec<Todo>> {
-    let todos = entity::Entity::find()
-        .filter(entity::Column::ParentId.eq(parent_id))
-        .filter(entity::Column::DeletedAt.is_null())
-        .all(db)
-        .await
-        .with_context(|| format!("failed to load subtasks for parent {}", parent_id))?;
+    let todos = ordering::order_by_position(
+        entity::Entity::find()
+            .filter(entity::Column::ParentId.eq(parent_id))
+            .filter(entity::Column::DeletedAt.is_null()),
+    )
+    .all(db)
+    .await
+    .with_context(|| format!("failed to load subtasks for parent {}", parent_id))?;
 
     Ok(todos.into_iter().map(Into::into).collect())
 }
@@ -261,8 +678,26 @@ pub async fn update_parent(
     db: &DatabaseConnection,
     id: i32,
     parent_id: Option<i32>,
+) -> Result<Todo> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start update transaction")?;
+    let todo = update_parent_in(&txn, &journal::new_batch_id(), id, parent_id).await?;
+    journal::commit(txn).await?;
+
+    Ok(todo)
+}
+
+/// 在给定连接中更新父任务关系，并记录到操作日志的指定批次
+pub(crate) async fn update_parent_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    id: i32,
+    parent_id: Option<i32>,
 ) -> Result<Todo> {
     let now = Utc::now();
+    let mut list_id = None;
 
     // 检查是否会创建循环引用
     if let Some(new_parent_id) = parent_id {
@@ -271,14 +706,13 @@ pub async fn update_parent(
         }
 
         // 检查新父任务是否存在
-        let parent_exists = entity::Entity::find_by_id(new_parent_id)
+        let parent = entity::Entity::find_by_id(new_parent_id)
             .one(db)
             .await?
-            .is_some();
+            .ok_or_else(|| anyhow!("Parent task {} not found", new_parent_id))?;
 
-        if !parent_exists {
-            return Err(anyhow!("Parent task {} not found", new_parent_id));
-        }
+        // 子任务与父任务在同一个清单（同一个日历）中
+        list_id = parent.list_id;
 
         // 检查是否会创建循环引用（新父任务是否是当前任务的后代）
         let mut current_parent_id = Some(new_parent_id);
@@ -299,8 +733,16 @@ pub async fn update_parent(
         .with_context(|| format!("failed to find todo {}", id))?
         .ok_or_else(|| anyhow!("todo {} not found", id))?;
 
+    // 移为顶层任务时保留原清单
+    if parent_id.is_none() {
+        list_id = model.list_id;
+    }
+    let list_changed = model.list_id != list_id;
+
+    let before = model.clone();
     let mut active: entity::ActiveModel = model.into();
     active.parent_id = Set(parent_id);
+    active.list_id = Set(list_id);
     active.dirty = Set(true);
     active.updated_at = Set(now);
     active.last_modified_at = Set(now);
@@ -310,150 +752,186 @@ pub async fn update_parent(
         .await
         .with_context(|| format!("failed to update parent for todo {}", id))?;
 
+    journal::record(
+        db,
+        batch_id,
+        Operation::UpdateParent,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    if list_changed {
+        lists::move_descendants_in(db, batch_id, id, list_id).await?;
+    }
+
+    // 原父任务和新父任务的进度都可能变化
+    if before.parent_id != parent_id {
+        subtasks::rollup_in(db, batch_id, before.parent_id).await?;
+        subtasks::rollup_in(db, batch_id, parent_id).await?;
+        return reload(db, id).await;
+    }
+
     Ok(updated.into())
 }
 
-/// 获取下一个需要提醒的 Todo（用于调度器）
-///
-/// 查询条件：
-/// - 未删除
-/// - 未完成
-/// - 有 due_date
-/// - reminder_last_triggered_at 为空（未提醒过）
+/// 稍后提醒：在指定分钟数之后，或在指定时间再次提醒
 ///
-/// 排序：按 (due_date - reminder_offset_minutes) 升序，即最早需要提醒的在前
-pub async fn get_next_reminder_todo(db: &DatabaseConnection) -> Result<Option<entity::Model>> {
-    // 不在数据库层过滤 reminder_last_triggered_at，因为可能是空字符串或 NULL
-    let todos = entity::Entity::find()
-        .filter(entity::Column::DeletedAt.is_null())
-        .filter(entity::Column::Completed.eq(false))
-        .filter(entity::Column::DueDate.is_not_null())
-        .all(db)
-        .await
-        .context("failed to query todos with reminders")?;
-
-    println!(
-        "[Service] 查询到 {} 个未完成且有 due_date 的 Todo",
-        todos.len()
-    );
-
-    // 在内存中计算提醒时间并排序（因为 SQL 无法直接计算时间差）
-    let mut todos_with_reminder_time: Vec<_> = todos
-        .into_iter()
-        .filter_map(|todo| {
-            println!("[Service] Todo#{}: title=\"{}\", due_date={:?}, offset={}, reminder_last_triggered_at={:?}",
-                todo.id, todo.title, todo.due_date, todo.reminder_offset_minutes, todo.reminder_last_triggered_at);
-
-            // 过滤掉已经提醒过的（reminder_last_triggered_at 不为空）
-            if todo.reminder_last_triggered_at.is_some() {
-                println!("[Service]   -> 已提醒过，跳过");
-                return None;
+/// 用于提醒触发后推迟，也可以提前推迟尚未触发的提醒；在此之前会触发的提醒被跳过。
+/// 只是本地的提醒状态，不标记 dirty，也不记录到操作日志。
+pub async fn snooze_todo(
+    db: &DatabaseConnection,
+    id: i32,
+    minutes: Option<i64>,
+    until: Option<String>,
+) -> Result<Todo> {
+    let now = Utc::now();
+    let snoozed_until = match (minutes, until) {
+        (Some(_), Some(_)) => bail!("only one of minutes and until can be given"),
+        (Some(minutes), None) => {
+            if minutes <= 0 {
+                bail!("snooze minutes must be positive");
             }
+            if minutes > MAX_SNOOZE_DAYS * 24 * 60 {
+                bail!("cannot snooze for more than {MAX_SNOOZE_DAYS} days");
+            }
+            now + chrono::Duration::minutes(minutes)
+        }
+        (None, Some(until)) => parse_datetime(&until)?,
+        (None, None) => bail!("either minutes or until is required"),
+    };
+    if snoozed_until <= now {
+        bail!("snooze time must be in the future");
+    }
+    if snoozed_until > now + chrono::Duration::days(MAX_SNOOZE_DAYS) {
+        bail!("cannot snooze for more than {MAX_SNOOZE_DAYS} days");
+    }
 
-            let due_date = todo.due_date?;
-            let offset = todo.reminder_offset_minutes;
-            let reminder_time = due_date - chrono::Duration::minutes(offset as i64);
-
-            println!("[Service]   -> 计算出的提醒时间: {}", reminder_time.format("%Y-%m-%d %H:%M:%S"));
-
-            Some((todo, reminder_time))
-        })
-        .collect();
-
-    println!(
-        "[Service] 有 {} 个 Todo 可以计算出提醒时间",
-        todos_with_reminder_time.len()
-    );
-
-    // 按提醒时间排序
-    todos_with_reminder_time.sort_by_key(|(_, time)| *time);
-
-    let result = todos_with_reminder_time
-        .into_iter()
-        .next()
-        .map(|(todo, _)| todo);
-    if let Some(ref todo) = result {
-        println!(
-            "[Service] 选中的下一个提醒 Todo: #{} \"{}\"",
-            todo.id, todo.title
-        );
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start snooze transaction")?;
+    let model = entity::Entity::find_by_id(id)
+        .one(&txn)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
+        .ok_or_else(|| anyhow!("todo {id} not found"))?;
+
+    if model.completed {
+        bail!("cannot snooze a completed todo");
     }
 
-    Ok(result)
-}
+    reminders::skip_until_in(&txn, &model, snoozed_until).await?;
 
-/// 根据 ID 获取 Todo（用于调度器发送提醒）
-pub async fn get_todo_by_id(db: &DatabaseConnection, id: i32) -> Result<entity::Model> {
-    entity::Entity::find_by_id(id)
-        .one(db)
+    let mut active: entity::ActiveModel = model.into();
+    active.snoozed_until = Set(Some(snoozed_until));
+    active.reminder_last_triggered_at = Set(None);
+    active.notified = Set(false);
+    active.updated_at = Set(now);
+
+    let updated = active
+        .update(&txn)
         .await
-        .with_context(|| format!("failed to load todo {id}"))?
-        .ok_or_else(|| anyhow!("todo {id} not found"))
+        .with_context(|| format!("failed to snooze todo {id}"))?;
+    txn.commit()
+        .await
+        .context("failed to commit snooze transaction")?;
+
+    Ok(updated.into())
 }
 
-/// 标记 Todo 为已提醒（用于调度器）
-pub async fn mark_todo_reminded(db: &DatabaseConnection, id: i32) -> Result<()> {
+/// 设置预估番茄数（为空表示清除）
+///
+/// 预估只保存在本地，不同步到 CalDAV；修改记录到操作日志
+pub async fn set_estimated_pomodoros(
+    db: &DatabaseConnection,
+    id: i32,
+    estimated_pomodoros: Option<i32>,
+) -> Result<Todo> {
+    if estimated_pomodoros.is_some_and(|count| count <= 0) {
+        bail!("estimated pomodoros must be positive");
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start estimate transaction")?;
+
     let model = entity::Entity::find_by_id(id)
-        .one(db)
+        .one(&txn)
         .await
         .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none())
         .ok_or_else(|| anyhow!("todo {id} not found"))?;
 
+    let before = model.clone();
     let mut active: entity::ActiveModel = model.into();
-    active.reminder_last_triggered_at = Set(Some(Utc::now()));
+    active.estimated_pomodoros = Set(estimated_pomodoros);
+    active.updated_at = Set(Utc::now());
 
-    active
-        .update(db)
+    let updated = active
+        .update(&txn)
         .await
-        .with_context(|| format!("failed to mark todo {id} as reminded"))?;
+        .with_context(|| format!("failed to update estimate of todo {id}"))?;
+
+    journal::record(
+        &txn,
+        &journal::new_batch_id(),
+        Operation::UpdateDetails,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+    journal::commit(txn).await?;
 
-    Ok(())
+    Ok(updated.into())
+}
+
+/// 根据 ID 获取 Todo（用于调度器发送提醒）
+pub async fn get_todo_by_id(db: &DatabaseConnection, id: i32) -> Result<entity::Model> {
+    entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .ok_or_else(|| anyhow!("todo {id} not found"))
 }
 
-/// 清理所有待删除的 todo（公开函数，供清除 CalDAV 配置时调用）
+/// 解除回收站中待删除项与远端的关联（供清除 CalDAV 配置时调用）
+///
+/// 配置清除后无法再删除远端资源：回收站中的项改为纯本地项继续保留，
+/// 已彻底删除、仅等待同步的项直接从本地移除。
 pub async fn cleanup_pending_deletes(db: &DatabaseConnection) -> Result<()> {
     let pending_deletes = entity::Entity::find()
         .filter(entity::Column::DeletedAt.is_not_null())
+        .filter(entity::Column::RemoteUrl.is_not_null())
         .all(db)
         .await
         .context("failed to query pending deleted todos")?;
 
     for model in pending_deletes {
-        entity::Entity::delete_by_id(model.id)
-            .exec(db)
+        let id = model.id;
+        let mut active: entity::ActiveModel = model.into();
+        active.remote_url = Set(None);
+        active.remote_etag = Set(None);
+        active.dirty = Set(false);
+
+        active
+            .update(db)
             .await
-            .with_context(|| format!("failed to cleanup pending deleted todo {}", model.id))?;
+            .with_context(|| format!("failed to detach pending deleted todo {id}"))?;
     }
 
-    Ok(())
+    super::trash::finalize_purged(db).await
 }
 
-/// 清理孤立的已删除 todo（deleted_at 不为空但没有 remote_url）
-/// 这些通常是因为用户在标记删除后移除了 CalDAV 配置，导致无法完成同步删除
-async fn cleanup_orphaned_deleted_todos(db: &DatabaseConnection) -> Result<()> {
-    use sea_orm::sea_query::Expr;
-
-    // 查找所有已标记删除但没有远端 URL 的 todo
-    let orphaned = entity::Entity::find()
-        .filter(entity::Column::DeletedAt.is_not_null())
-        .filter(
-            Expr::col(entity::Column::RemoteUrl)
-                .is_null()
-                .or(Expr::col(entity::Column::RemoteUrl).eq("")),
-        )
-        .all(db)
+async fn reload<C: ConnectionTrait>(db: &C, id: i32) -> Result<Todo> {
+    entity::Entity::find_by_id(id)
+        .one(db)
         .await
-        .context("failed to query orphaned deleted todos")?;
-
-    // 彻底删除这些孤立项
-    for model in orphaned {
-        entity::Entity::delete_by_id(model.id)
-            .exec(db)
-            .await
-            .with_context(|| format!("failed to cleanup orphaned deleted todo {}", model.id))?;
-    }
-
-    Ok(())
+        .with_context(|| format!("failed to load todo {id}"))?
+        .map(Into::into)
+        .ok_or_else(|| anyhow!("todo {id} not found"))
 }
 
 fn normalize_title(title: Option<String>) -> Cow<'static, str> {
@@ -483,7 +961,7 @@ fn parse_datetime_opt(value: Option<String>) -> Result<Option<DateTime<Utc>>> {
 }
 
 /// 获取本地时区（尝试检测系统时区，失败则使用 Asia/Shanghai）
-fn get_local_timezone() -> String {
+pub(crate) fn get_local_timezone() -> String {
     // 尝试使用 iana-time-zone 检测系统时区
     #[cfg(not(target_arch = "wasm32"))]
     {
@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::journal::{self, Operation};
+use super::reminders;
+use super::service;
+use crate::features::settings::core::service::SettingService;
+use crate::features::settings::data::entity as setting;
+use crate::features::todo::data::entity;
+
+const ROLLUP_PROGRESS_KEY: &str = "todo.subtasks.rollup_progress";
+const COMPLETE_DESCENDANTS_KEY: &str = "todo.subtasks.complete_descendants";
+const COMPLETE_ANCESTORS_KEY: &str = "todo.subtasks.complete_ancestors";
+const UNCOMPLETE_DESCENDANTS_KEY: &str = "todo.subtasks.uncomplete_descendants";
+const UNCOMPLETE_ANCESTORS_KEY: &str = "todo.subtasks.uncomplete_ancestors";
+
+const DEFAULT_STATUS: &str = "NEEDS-ACTION";
+const COMPLETED_STATUS: &str = "COMPLETED";
+
+/// 子任务的进度汇总与级联完成规则（默认全部关闭）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct SubtaskSettings {
+    /// 父任务的 percent_complete 由后代中的叶子任务平均得出（已完成的父任务保持 100）
+    pub rollup_progress: bool,
+    /// 完成父任务时同时完成所有未完成的后代（重复待办除外）
+    pub complete_descendants: bool,
+    /// 子任务全部完成时自动完成父任务（逐级向上，遇到重复待办停止）
+    pub complete_ancestors: bool,
+    /// 取消完成父任务时同时取消完成所有后代
+    pub uncomplete_descendants: bool,
+    /// 取消完成子任务时同时取消完成已完成的祖先
+    pub uncomplete_ancestors: bool,
+}
+
+/// 读取子任务设置
+pub async fn get_settings(db: &DatabaseConnection) -> Result<SubtaskSettings> {
+    load_settings(db).await
+}
+
+/// 保存子任务设置
+///
+/// 开启进度汇总时立即重新计算所有父任务的进度（作为一次操作记录到操作日志）
+pub async fn set_settings(db: &DatabaseConnection, settings: SubtaskSettings) -> Result<()> {
+    let previous = load_settings(db).await?;
+
+    for (key, value) in [
+        (ROLLUP_PROGRESS_KEY, settings.rollup_progress),
+        (COMPLETE_DESCENDANTS_KEY, settings.complete_descendants),
+        (COMPLETE_ANCESTORS_KEY, settings.complete_ancestors),
+        (UNCOMPLETE_DESCENDANTS_KEY, settings.uncomplete_descendants),
+        (UNCOMPLETE_ANCESTORS_KEY, settings.uncomplete_ancestors),
+    ] {
+        SettingService::set_bool(db, key, value).await?;
+    }
+
+    if settings.rollup_progress && !previous.rollup_progress {
+        let txn = db
+            .begin()
+            .await
+            .context("failed to start rollup transaction")?;
+        rollup_all_in(&txn, &journal::new_batch_id()).await?;
+        journal::commit(txn).await?;
+    }
+
+    Ok(())
+}
+
+/// 待办完成状态改变后应用级联规则并更新进度汇总
+///
+/// 在调用方的事务中执行，所有修改记录到同一个操作日志批次
+pub(crate) async fn apply_completion_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: &entity::Model,
+) -> Result<()> {
+    let settings = load_settings(db).await?;
+    let now = Utc::now();
+
+    if model.completed {
+        if settings.complete_descendants {
+            for descendant in service::load_descendants(db, model.id).await? {
+                // 重复待办的完成需要推进到下一次发生，不自动处理
+                if !descendant.completed && !is_recurring(&descendant) {
+                    set_completed(db, batch_id, descendant, true, now).await?;
+                }
+            }
+        }
+        if settings.complete_ancestors {
+            complete_ancestors(db, batch_id, model.parent_id, now).await?;
+        }
+    } else {
+        if settings.uncomplete_descendants {
+            for descendant in service::load_descendants(db, model.id).await? {
+                if descendant.completed {
+                    set_completed(db, batch_id, descendant, false, now).await?;
+                }
+            }
+        }
+        if settings.uncomplete_ancestors {
+            let mut current = model.parent_id;
+            while let Some(parent_id) = current {
+                let Some(parent) = load_active(db, parent_id).await? else {
+                    break;
+                };
+                current = parent.parent_id;
+                if parent.completed {
+                    set_completed(db, batch_id, parent, false, now).await?;
+                }
+            }
+        }
+    }
+
+    if settings.rollup_progress {
+        rollup_from(db, batch_id, Some(model.id)).await?;
+    }
+
+    Ok(())
+}
+
+/// 重新计算从 `start` 开始（包括它自身）的所有祖先的进度
+///
+/// 未开启进度汇总时不做任何修改，用于调整父任务、删除子任务之后
+pub(crate) async fn rollup_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    start: Option<i32>,
+) -> Result<()> {
+    if load_settings(db).await?.rollup_progress {
+        rollup_from(db, batch_id, start).await?;
+    }
+    Ok(())
+}
+
+/// 重复待办推进到下一次发生后更新祖先
+///
+/// `model` 为推进后的记录：原地推进时仍未完成，只重新汇总进度；
+/// 生成后继时是已完成的实例，按设置级联完成祖先后再汇总
+pub(crate) async fn apply_recurrence_in<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: &entity::Model,
+) -> Result<()> {
+    let settings = load_settings(db).await?;
+
+    if model.completed && settings.complete_ancestors {
+        complete_ancestors(db, batch_id, model.parent_id, Utc::now()).await?;
+    }
+    if settings.rollup_progress {
+        rollup_from(db, batch_id, Some(model.id)).await?;
+    }
+
+    Ok(())
+}
+
+async fn rollup_from<C: ConnectionTrait>(db: &C, batch_id: &str, start: Option<i32>) -> Result<()> {
+    let mut current = start;
+    while let Some(id) = current {
+        let Some(model) = load_active(db, id).await? else {
+            break;
+        };
+        current = model.parent_id;
+        rollup_one(db, batch_id, model).await?;
+    }
+    Ok(())
+}
+
+/// 重新计算所有父任务的进度
+async fn rollup_all_in<C: ConnectionTrait>(db: &C, batch_id: &str) -> Result<()> {
+    let parent_ids: HashSet<i32> = entity::Entity::find()
+        .filter(entity::Column::DeletedAt.is_null())
+        .filter(entity::Column::ParentId.is_not_null())
+        .all(db)
+        .await
+        .context("failed to load subtasks")?
+        .into_iter()
+        .filter_map(|model| model.parent_id)
+        .collect();
+
+    for id in parent_ids {
+        if let Some(model) = load_active(db, id).await? {
+            rollup_one(db, batch_id, model).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 用叶子后代的平均进度更新一个父任务；没有后代或已完成的待办保持不变
+async fn rollup_one<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: entity::Model,
+) -> Result<()> {
+    if model.completed {
+        return Ok(());
+    }
+
+    let descendants = service::load_descendants(db, model.id).await?;
+    let parents: HashSet<i32> = descendants.iter().filter_map(|d| d.parent_id).collect();
+    let leaves: Vec<i32> = descendants
+        .iter()
+        .filter(|d| !parents.contains(&d.id))
+        .map(|d| {
+            if d.completed {
+                100
+            } else {
+                d.percent_complete.unwrap_or(0).clamp(0, 100)
+            }
+        })
+        .collect();
+
+    if leaves.is_empty() {
+        return Ok(());
+    }
+
+    let total: i32 = leaves.iter().sum();
+    let percent = (total as f64 / leaves.len() as f64).round() as i32;
+    if model.percent_complete == Some(percent) {
+        return Ok(());
+    }
+
+    let id = model.id;
+    let now = Utc::now();
+    let before = model.clone();
+    let mut active: entity::ActiveModel = model.into();
+    active.percent_complete = Set(Some(percent));
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active.dirty = Set(true);
+
+    let updated = active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to update progress of todo {id}"))?;
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::UpdateDetails,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// 子任务全部完成时逐级完成祖先
+async fn complete_ancestors<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    parent_id: Option<i32>,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let mut current = parent_id;
+    while let Some(id) = current {
+        let Some(parent) = load_active(db, id).await? else {
+            break;
+        };
+        // 重复待办的完成需要推进到下一次发生，不自动处理
+        if parent.completed || is_recurring(&parent) {
+            break;
+        }
+
+        let open_children = entity::Entity::find()
+            .filter(entity::Column::ParentId.eq(id))
+            .filter(entity::Column::DeletedAt.is_null())
+            .filter(entity::Column::Completed.eq(false))
+            .all(db)
+            .await
+            .with_context(|| format!("failed to load subtasks of todo {id}"))?;
+        if !open_children.is_empty() {
+            break;
+        }
+
+        current = parent.parent_id;
+        set_completed(db, batch_id, parent, true, now).await?;
+    }
+    Ok(())
+}
+
+fn is_recurring(model: &entity::Model) -> bool {
+    model
+        .recurrence_rule
+        .as_deref()
+        .is_some_and(|rule| !rule.trim().is_empty())
+}
+
+async fn set_completed<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: entity::Model,
+    completed: bool,
+    now: DateTime<Utc>,
+) -> Result<entity::Model> {
+    let id = model.id;
+    let before = model.clone();
+    let mut active: entity::ActiveModel = model.into();
+    active.completed = Set(completed);
+    if completed {
+        active.status = Set(COMPLETED_STATUS.to_string());
+        active.percent_complete = Set(Some(100));
+        active.completed_at = Set(Some(now));
+    } else {
+        active.status = Set(DEFAULT_STATUS.to_string());
+        active.percent_complete = Set(Some(0));
+        active.completed_at = Set(None);
+    }
+    active.reminder_last_triggered_at = Set(None);
+    active.snoozed_until = Set(None);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active.dirty = Set(true);
+
+    let updated = active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to update completion of todo {id}"))?;
+    reminders::reset_in(db, id).await?;
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    Ok(updated)
+}
+
+async fn load_active<C: ConnectionTrait>(db: &C, id: i32) -> Result<Option<entity::Model>> {
+    Ok(entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .filter(|model| model.deleted_at.is_none()))
+}
+
+/// 在给定连接（可能是事务）中读取设置
+async fn load_settings<C: ConnectionTrait>(db: &C) -> Result<SubtaskSettings> {
+    let keys = [
+        ROLLUP_PROGRESS_KEY,
+        COMPLETE_DESCENDANTS_KEY,
+        COMPLETE_ANCESTORS_KEY,
+        UNCOMPLETE_DESCENDANTS_KEY,
+        UNCOMPLETE_ANCESTORS_KEY,
+    ];
+    let rows = setting::Entity::find()
+        .filter(setting::Column::Key.is_in(keys))
+        .all(db)
+        .await
+        .context("failed to load subtask settings")?;
+
+    let flag = |key: &str| {
+        rows.iter()
+            .find(|row| row.key == key)
+            .and_then(|row| row.value.parse::<bool>().ok())
+            .unwrap_or(false)
+    };
+
+    Ok(SubtaskSettings {
+        rollup_progress: flag(ROLLUP_PROGRESS_KEY),
+        complete_descendants: flag(COMPLETE_DESCENDANTS_KEY),
+        complete_ancestors: flag(COMPLETE_ANCESTORS_KEY),
+        uncomplete_descendants: flag(UNCOMPLETE_DESCENDANTS_KEY),
+        uncomplete_ancestors: flag(UNCOMPLETE_ANCESTORS_KEY),
+    })
+}
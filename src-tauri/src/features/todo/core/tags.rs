@@ -0,0 +1,366 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseBackend,
+    DatabaseConnection, EntityTrait, QueryFilter, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::journal::{self, Operation};
+use crate::features::todo::data::{
+    entities::{tag, todo_tag},
+    entity,
+};
+
+/// 标签及其使用次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagSummary {
+    pub id: i32,
+    pub name: String,
+    pub color: Option<String>,
+    /// 使用该标签的未删除待办数量
+    pub todo_count: i64,
+}
+
+/// 标签修改结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagChange {
+    /// 修改后的标签（删除时为空）
+    pub tag: Option<TagSummary>,
+    /// 标签被改写的待办 ID
+    pub todo_ids: Vec<i32>,
+}
+
+/// 列出所有标签（按名称排序）
+pub async fn list_tags(db: &DatabaseConnection) -> Result<Vec<TagSummary>> {
+    load_summaries(db, None).await
+}
+
+/// 重命名标签
+///
+/// 所有使用该标签的待办同步改名并标记 dirty，下次同步时更新 CATEGORIES。
+/// 新名称已被其他标签使用时报错，应改用合并。
+pub async fn rename_tag(db: &DatabaseConnection, id: i32, name: &str) -> Result<TagChange> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("tag name cannot be empty");
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start tag transaction")?;
+    let current = load_tag(&txn, id).await?;
+
+    if let Some(existing) = find_by_name(&txn, name).await? {
+        if existing.id != id {
+            bail!("tag \"{}\" already exists", existing.name);
+        }
+    }
+
+    let old_name = current.name.clone();
+    let mut active: tag::ActiveModel = current.into();
+    active.name = Set(name.to_string());
+    active
+        .update(&txn)
+        .await
+        .with_context(|| format!("failed to rename tag {id}"))?;
+
+    let new_name = name.to_string();
+    let todo_ids = rewrite_todos(&txn, &[id], |tags| {
+        tags.into_iter()
+            .map(|tag| {
+                if tag.eq_ignore_ascii_case(&old_name) {
+                    new_name.clone()
+                } else {
+                    tag
+                }
+            })
+            .collect()
+    })
+    .await?;
+
+    let tag = load_summaries(&txn, Some(id)).await?.pop();
+    journal::commit(txn).await?;
+
+    Ok(TagChange { tag, todo_ids })
+}
+
+/// 把若干标签合并到目标标签
+///
+/// 使用来源标签的待办改为使用目标标签，来源标签随后删除。
+pub async fn merge_tags(
+    db: &DatabaseConnection,
+    source_ids: &[i32],
+    target_id: i32,
+) -> Result<TagChange> {
+    let source_ids: Vec<i32> = source_ids
+        .iter()
+        .copied()
+        .filter(|id| *id != target_id)
+        .collect();
+    if source_ids.is_empty() {
+        bail!("no tags to merge");
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start tag transaction")?;
+    let target = load_tag(&txn, target_id).await?;
+
+    let mut source_names = Vec::new();
+    for id in &source_ids {
+        source_names.push(load_tag(&txn, *id).await?.name);
+    }
+
+    let target_name = target.name.clone();
+    let todo_ids = rewrite_todos(&txn, &source_ids, |tags| {
+        let mut merged: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = if contains_tag(&source_names, &tag) {
+                target_name.clone()
+            } else {
+                tag
+            };
+            if !contains_tag(&merged, &tag) {
+                merged.push(tag);
+            }
+        }
+        merged
+    })
+    .await?;
+
+    delete_tag_rows(&txn, &source_ids).await?;
+
+    let tag = load_summaries(&txn, Some(target_id)).await?.pop();
+    journal::commit(txn).await?;
+
+    Ok(TagChange { tag, todo_ids })
+}
+
+/// 删除标签，并从所有待办中移除
+pub async fn delete_tag(db: &DatabaseConnection, id: i32) -> Result<TagChange> {
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start tag transaction")?;
+    let name = load_tag(&txn, id).await?.name;
+
+    let todo_ids = rewrite_todos(&txn, &[id], |tags| {
+        tags.into_iter()
+            .filter(|tag| !tag.eq_ignore_ascii_case(&name))
+            .collect()
+    })
+    .await?;
+
+    delete_tag_rows(&txn, &[id]).await?;
+    journal::commit(txn).await?;
+
+    Ok(TagChange {
+        tag: None,
+        todo_ids,
+    })
+}
+
+/// 设置标签颜色（`#RRGGBB`），None 表示清除
+pub async fn set_tag_color(
+    db: &DatabaseConnection,
+    id: i32,
+    color: Option<String>,
+) -> Result<TagSummary> {
+    let color = color
+        .map(|color| color.trim().to_ascii_lowercase())
+        .filter(|color| !color.is_empty());
+    if let Some(color) = &color {
+        if !is_hex_color(color) {
+            bail!("invalid colour \"{color}\", expected #RRGGBB");
+        }
+    }
+
+    let mut active: tag::ActiveModel = load_tag(db, id).await?.into();
+    active.color = Set(color);
+    active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to update colour of tag {id}"))?;
+
+    load_summaries(db, Some(id))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("tag {id} not found"))
+}
+
+/// 替换一个待办的全部标签，标记 dirty 并记录到操作日志
+pub(crate) async fn write_todo_tags<C: ConnectionTrait>(
+    db: &C,
+    batch_id: &str,
+    model: entity::Model,
+    tags: &[String],
+) -> Result<entity::Model> {
+    let id = model.id;
+    let serialized = if tags.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(tags).context("failed to serialize tags")?)
+    };
+
+    let now = Utc::now();
+    let before = model.clone();
+    let mut active: entity::ActiveModel = model.into();
+    active.tags = Set(serialized);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+    active.dirty = Set(true);
+
+    let updated = active
+        .update(db)
+        .await
+        .with_context(|| format!("failed to update tags of todo {id}"))?;
+
+    journal::record(
+        db,
+        batch_id,
+        Operation::UpdateDetails,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    Ok(updated)
+}
+
+/// 解析 todos.tags 中的 JSON 标签数组
+pub(crate) fn parse_tags(raw: Option<&str>) -> Vec<String> {
+    raw.and_then(|raw| serde_json::from_str(raw).ok())
+        .unwrap_or_default()
+}
+
+/// 去除空白和重复（不区分大小写）的标签
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !contains_tag(&result, tag) {
+            result.push(tag.to_string());
+        }
+    }
+    result
+}
+
+pub(crate) fn contains_tag(tags: &[String], tag: &str) -> bool {
+    tags.iter()
+        .any(|existing| existing.eq_ignore_ascii_case(tag))
+}
+
+/// 改写使用指定标签的所有待办（包括回收站中的），返回被修改的待办 ID
+async fn rewrite_todos<C, F>(db: &C, tag_ids: &[i32], change: F) -> Result<Vec<i32>>
+where
+    C: ConnectionTrait,
+    F: Fn(Vec<String>) -> Vec<String>,
+{
+    let todo_ids: Vec<i32> = todo_tag::Entity::find()
+        .filter(todo_tag::Column::TagId.is_in(tag_ids.to_vec()))
+        .all(db)
+        .await
+        .context("failed to load tagged todos")?
+        .into_iter()
+        .map(|link| link.todo_id)
+        .collect();
+
+    let todos = entity::Entity::find()
+        .filter(entity::Column::Id.is_in(todo_ids))
+        .all(db)
+        .await
+        .context("failed to load tagged todos")?;
+
+    let batch_id = journal::new_batch_id();
+    let mut changed = Vec::new();
+    for todo in todos {
+        let current = normalize_tags(parse_tags(todo.tags.as_deref()));
+        let tags = change(current.clone());
+        if tags == current {
+            continue;
+        }
+        changed.push(todo.id);
+        write_todo_tags(db, &batch_id, todo, &tags).await?;
+    }
+
+    Ok(changed)
+}
+
+async fn delete_tag_rows<C: ConnectionTrait>(db: &C, ids: &[i32]) -> Result<()> {
+    todo_tag::Entity::delete_many()
+        .filter(todo_tag::Column::TagId.is_in(ids.to_vec()))
+        .exec(db)
+        .await
+        .context("failed to unlink tags")?;
+    tag::Entity::delete_many()
+        .filter(tag::Column::Id.is_in(ids.to_vec()))
+        .exec(db)
+        .await
+        .context("failed to delete tags")?;
+    Ok(())
+}
+
+async fn load_tag<C: ConnectionTrait>(db: &C, id: i32) -> Result<tag::Model> {
+    tag::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load tag {id}"))?
+        .ok_or_else(|| anyhow!("tag {id} not found"))
+}
+
+async fn find_by_name<C: ConnectionTrait>(db: &C, name: &str) -> Result<Option<tag::Model>> {
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT id FROM tags WHERE name = ? COLLATE NOCASE",
+            [name.into()],
+        ))
+        .await
+        .context("failed to look up tag")?;
+
+    match row {
+        Some(row) => {
+            let id: i32 = row.try_get("", "id")?;
+            load_tag(db, id).await.map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+async fn load_summaries<C: ConnectionTrait>(db: &C, id: Option<i32>) -> Result<Vec<TagSummary>> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            r#"
+            SELECT tags.id AS id, tags.name AS name, tags.color AS color,
+                   COUNT(todos.id) AS todo_count
+            FROM tags
+            LEFT JOIN todo_tags ON todo_tags.tag_id = tags.id
+            LEFT JOIN todos ON todos.id = todo_tags.todo_id AND todos.deleted_at IS NULL
+            WHERE ? IS NULL OR tags.id = ?
+            GROUP BY tags.id
+            ORDER BY tags.name COLLATE NOCASE
+            "#,
+            [id.into(), id.into()],
+        ))
+        .await
+        .context("failed to load tags")?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(TagSummary {
+                id: row.try_get("", "id")?,
+                name: row.try_get("", "name")?,
+                color: row.try_get("", "color")?,
+                todo_count: row.try_get("", "todo_count")?,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
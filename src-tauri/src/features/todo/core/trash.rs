@@ -0,0 +1,246 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseBackend,
+    DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Statement, TransactionTrait,
+};
+
+use super::models::Todo;
+use crate::features::settings::core::service::SettingService;
+use crate::features::todo::data::entity;
+
+const TRASH_RETENTION_KEY: &str = "todo.trash_retention_days";
+
+/// 回收站默认保留天数
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// 获取回收站保留天数（0 表示不自动清理）
+pub async fn get_retention_days(db: &DatabaseConnection) -> Result<u32> {
+    let raw = SettingService::get(db, TRASH_RETENTION_KEY).await?;
+    Ok(raw
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS))
+}
+
+/// 设置回收站保留天数（0 表示不自动清理）
+pub async fn set_retention_days(db: &DatabaseConnection, days: u32) -> Result<()> {
+    SettingService::set(db, TRASH_RETENTION_KEY, &days.to_string()).await?;
+    Ok(())
+}
+
+/// 列出回收站中的 todo（最近删除的在前）
+pub async fn list_trash(db: &DatabaseConnection) -> Result<Vec<Todo>> {
+    let todos = entity::Entity::find()
+        .filter(entity::Column::DeletedAt.is_not_null())
+        .filter(entity::Column::PurgedAt.is_null())
+        .order_by_desc(entity::Column::DeletedAt)
+        .all(db)
+        .await
+        .context("failed to load trashed todos")?;
+
+    Ok(todos.into_iter().map(Into::into).collect())
+}
+
+/// 从回收站恢复 todo
+///
+/// - 与它一起删除的子任务同时恢复
+/// - 仍在回收站中的父任务也会恢复，保证层级完整；父任务已彻底删除时变为顶层任务
+/// - 恢复的项标记为 dirty，下次同步时重新上传（远端已删除则重新创建）
+pub async fn restore_todo(db: &DatabaseConnection, id: i32) -> Result<Todo> {
+    let model = load_trashed(db, id).await?;
+    let deleted_at = model.deleted_at;
+
+    let txn = db
+        .begin()
+        .await
+        .context("failed to start restore transaction")?;
+    let now = Utc::now();
+
+    // 一起删除的子任务（deleted_at 相同）
+    let mut to_restore = Vec::new();
+    let mut frontier = vec![id];
+    while !frontier.is_empty() {
+        let children = entity::Entity::find()
+            .filter(entity::Column::ParentId.is_in(frontier.clone()))
+            .filter(entity::Column::DeletedAt.eq(deleted_at))
+            .filter(entity::Column::PurgedAt.is_null())
+            .all(&txn)
+            .await
+            .with_context(|| format!("failed to load trashed subtasks of todo {id}"))?;
+
+        frontier = children.iter().map(|child| child.id).collect();
+        to_restore.extend(children);
+    }
+
+    // 仍在回收站中的祖先
+    let mut detach = false;
+    let mut current_parent = model.parent_id;
+    while let Some(parent_id) = current_parent {
+        let Some(parent) = entity::Entity::find_by_id(parent_id)
+            .one(&txn)
+            .await
+            .with_context(|| format!("failed to load parent todo {parent_id}"))?
+        else {
+            detach = true;
+            break;
+        };
+
+        if parent.purged_at.is_some() {
+            detach = true;
+            break;
+        }
+
+        current_parent = parent.parent_id;
+        if parent.deleted_at.is_some() {
+            to_restore.push(parent);
+        } else {
+            break;
+        }
+    }
+
+    let mut active: entity::ActiveModel = model.into();
+    if detach {
+        active.parent_id = Set(None);
+    }
+    mark_restored(&mut active, now);
+    let restored = active
+        .update(&txn)
+        .await
+        .with_context(|| format!("failed to restore todo {id}"))?;
+
+    for item in to_restore {
+        let item_id = item.id;
+        let mut active: entity::ActiveModel = item.into();
+        mark_restored(&mut active, now);
+        active
+            .update(&txn)
+            .await
+            .with_context(|| format!("failed to restore todo {item_id}"))?;
+    }
+
+    txn.commit()
+        .await
+        .context("failed to commit restore transaction")?;
+
+    Ok(restored.into())
+}
+
+/// 彻底删除回收站中的 todo（连同子任务）
+///
+/// 远端资源尚未删除的项先标记为 purged，等待同步删除远端后再从本地移除
+pub async fn purge_todo(db: &DatabaseConnection, id: i32) -> Result<()> {
+    load_trashed(db, id).await?;
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        r#"
+        WITH RECURSIVE subtree(id) AS (
+            SELECT ?
+            UNION
+            SELECT todos.id FROM todos JOIN subtree ON todos.parent_id = subtree.id
+        )
+        UPDATE todos SET purged_at = ? WHERE id IN (SELECT id FROM subtree)
+        "#,
+        [id.into(), Utc::now().into()],
+    ))
+    .await
+    .with_context(|| format!("failed to purge todo {id}"))?;
+
+    finalize_purged(db).await
+}
+
+/// 清空回收站
+pub async fn empty_trash(db: &DatabaseConnection) -> Result<()> {
+    entity::Entity::update_many()
+        .col_expr(
+            entity::Column::PurgedAt,
+            sea_orm::sea_query::Expr::value(Utc::now()),
+        )
+        .filter(entity::Column::DeletedAt.is_not_null())
+        .filter(entity::Column::PurgedAt.is_null())
+        .exec(db)
+        .await
+        .context("failed to empty trash")?;
+
+    finalize_purged(db).await
+}
+
+/// 自动清理超过保留期的回收站项，返回清理数量
+pub async fn purge_expired(db: &DatabaseConnection) -> Result<u64> {
+    let days = get_retention_days(db).await?;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let now = Utc::now();
+    let cutoff = now - Duration::days(days as i64);
+
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            r#"
+            WITH RECURSIVE subtree(id) AS (
+                SELECT id FROM todos
+                WHERE deleted_at IS NOT NULL AND purged_at IS NULL AND deleted_at < ?
+                UNION
+                SELECT todos.id FROM todos JOIN subtree ON todos.parent_id = subtree.id
+            )
+            UPDATE todos SET purged_at = ? WHERE id IN (SELECT id FROM subtree)
+            "#,
+            [cutoff.into(), now.into()],
+        ))
+        .await
+        .context("failed to mark expired trashed todos")?;
+
+    finalize_purged(db).await?;
+
+    Ok(result.rows_affected())
+}
+
+/// 从本地移除已彻底删除、且远端已清理的项
+///
+/// 仍有后代在等待删除远端资源时保留该项，避免级联删除把它们一起带走
+pub async fn finalize_purged<C: ConnectionTrait>(db: &C) -> Result<()> {
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        r#"
+        WITH RECURSIVE blocked(id) AS (
+            SELECT parent_id FROM todos
+            WHERE remote_url IS NOT NULL AND parent_id IS NOT NULL
+            UNION
+            SELECT todos.parent_id FROM todos JOIN blocked ON todos.id = blocked.id
+            WHERE todos.parent_id IS NOT NULL
+        )
+        DELETE FROM todos
+        WHERE purged_at IS NOT NULL
+          AND remote_url IS NULL
+          AND id NOT IN (SELECT id FROM blocked)
+        "#
+        .to_string(),
+    ))
+    .await
+    .context("failed to remove purged todos")?;
+
+    Ok(())
+}
+
+async fn load_trashed(db: &DatabaseConnection, id: i32) -> Result<entity::Model> {
+    let model = entity::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .with_context(|| format!("failed to load todo {id}"))?
+        .ok_or_else(|| anyhow!("todo {id} not found"))?;
+
+    if model.deleted_at.is_none() || model.purged_at.is_some() {
+        bail!("todo {id} is not in trash");
+    }
+
+    Ok(model)
+}
+
+fn mark_restored(active: &mut entity::ActiveModel, now: chrono::DateTime<Utc>) {
+    active.deleted_at = Set(None);
+    active.dirty = Set(true);
+    active.last_modified_at = Set(now);
+    active.updated_at = Set(now);
+}
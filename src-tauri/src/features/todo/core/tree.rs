@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait, QueryFilter,
+    Statement,
+};
+use serde::{Deserialize, Serialize};
+
+use super::models::Todo;
+use super::ordering;
+use crate::features::todo::data::entity;
+
+/// 递归查询的最大深度（防止异常数据中的循环引用导致无限递归）
+const MAX_DEPTH: i32 = 64;
+
+/// 待办树中的一个节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoTreeNode {
+    #[serde(flatten)]
+    pub todo: Todo,
+    /// 相对于树根的深度（根为 0）
+    pub depth: i32,
+    /// 直接子任务数量
+    pub child_count: usize,
+    /// 全部后代数量
+    pub descendant_count: usize,
+    /// 已完成的后代数量
+    pub completed_descendant_count: usize,
+    /// 汇总进度（0-100）：已完成为 100，有后代时为叶子后代的平均进度，否则为自身的 percent_complete
+    pub progress: i32,
+    pub children: Vec<TodoTreeNode>,
+}
+
+/// 一次查询出完整的待办树（不含回收站中的待办）
+///
+/// `root_id` 为空时返回所有顶层待办及其后代，否则只返回以该待办为根的子树。
+/// 同级节点按手动排序位置排列，未排序的按创建顺序排在最后。
+pub async fn load_tree(db: &DatabaseConnection, root_id: Option<i32>) -> Result<Vec<TodoTreeNode>> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            r#"
+            WITH RECURSIVE tree(id, depth) AS (
+                SELECT id, 0 FROM todos
+                WHERE deleted_at IS NULL
+                  AND CASE WHEN ? IS NULL THEN parent_id IS NULL ELSE id = ? END
+                UNION ALL
+                SELECT todos.id, tree.depth + 1
+                FROM todos JOIN tree ON todos.parent_id = tree.id
+                WHERE todos.deleted_at IS NULL AND tree.depth < ?
+            )
+            SELECT id, depth FROM tree
+            "#,
+            [root_id.into(), root_id.into(), MAX_DEPTH.into()],
+        ))
+        .await
+        .context("failed to query todo tree")?;
+
+    let mut depths = HashMap::new();
+    for row in rows {
+        let id: i32 = row.try_get("", "id")?;
+        let depth: i32 = row.try_get("", "depth")?;
+        depths.entry(id).or_insert(depth);
+    }
+
+    if let Some(root_id) = root_id {
+        if !depths.contains_key(&root_id) {
+            return Err(anyhow!("todo {root_id} not found"));
+        }
+    }
+
+    let models = ordering::order_by_position(
+        entity::Entity::find()
+            .filter(entity::Column::Id.is_in(depths.keys().copied().collect::<Vec<_>>())),
+    )
+    .all(db)
+    .await
+    .context("failed to load todo tree")?;
+
+    let mut roots = Vec::new();
+    let mut children: HashMap<i32, Vec<entity::Model>> = HashMap::new();
+    for model in models {
+        if depths.get(&model.id) == Some(&0) {
+            roots.push(model);
+        } else if let Some(parent_id) = model.parent_id {
+            children.entry(parent_id).or_default().push(model);
+        }
+    }
+
+    Ok(roots
+        .into_iter()
+        .map(|root| build_node(root, 0, &mut children).0)
+        .collect())
+}
+
+/// 递归构造节点，同时返回叶子后代的进度之和与数量（用于上层汇总）
+fn build_node(
+    model: entity::Model,
+    depth: i32,
+    children: &mut HashMap<i32, Vec<entity::Model>>,
+) -> (TodoTreeNode, i32, usize) {
+    let nodes: Vec<(TodoTreeNode, i32, usize)> = children
+        .remove(&model.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build_node(child, depth + 1, children))
+        .collect();
+
+    let own_progress = if model.completed {
+        100
+    } else {
+        model.percent_complete.unwrap_or(0).clamp(0, 100)
+    };
+
+    let (leaf_sum, leaf_count) = if nodes.is_empty() {
+        (own_progress, 1)
+    } else {
+        nodes
+            .iter()
+            .fold((0, 0), |(sum, count), (_, s, c)| (sum + s, count + c))
+    };
+
+    let progress = if model.completed || nodes.is_empty() {
+        own_progress
+    } else {
+        (leaf_sum as f64 / leaf_count as f64).round() as i32
+    };
+
+    let descendant_count = nodes
+        .iter()
+        .map(|(node, _, _)| node.descendant_count + 1)
+        .sum();
+    let completed_descendant_count = nodes
+        .iter()
+        .map(|(node, _, _)| node.completed_descendant_count + usize::from(node.todo.completed))
+        .sum();
+
+    let node = TodoTreeNode {
+        todo: model.into(),
+        depth,
+        child_count: nodes.len(),
+        descendant_count,
+        completed_descendant_count,
+        progress,
+        children: nodes.into_iter().map(|(node, _, _)| node).collect(),
+    };
+
+    (node, leaf_sum, leaf_count)
+}
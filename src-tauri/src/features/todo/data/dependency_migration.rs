@@ -0,0 +1,75 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::todo_dependency;
+
+/// 创建待办阻塞关系表
+///
+/// 不使用外键：旧的迁移会重建 todos 表，级联删除会清空关系，
+/// 因此与 todo_tags 一样由触发器在待办彻底删除时清理。
+#[derive(Debug, Clone, Copy)]
+pub struct TodoDependencyMigration;
+
+impl MigrationName for TodoDependencyMigration {
+    fn name(&self) -> &str {
+        "m20251225_000001_create_todo_dependencies"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for TodoDependencyMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Creating todo_dependencies table...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        let mut create_dependencies = schema.create_table_from_entity(todo_dependency::Entity);
+        create_dependencies.if_not_exists();
+        db.execute(backend.build(&create_dependencies))
+            .await
+            .context("failed to create todo_dependencies table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        // 触发器随 todos 表重建而丢失，因此每次都确保存在
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_todo_dependencies_depends_on
+                ON todo_dependencies(depends_on_id);
+
+            CREATE TRIGGER IF NOT EXISTS todo_dependencies_ad AFTER DELETE ON todos BEGIN
+                DELETE FROM todo_dependencies
+                WHERE todo_id = old.id OR depends_on_id = old.id;
+            END;
+            "#
+            .to_string(),
+        ))
+        .await
+        .context("failed to create todo_dependencies index and trigger")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Todo dependency migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "DROP TRIGGER IF EXISTS todo_dependencies_ad;".to_string(),
+        ))
+        .await?;
+
+        manager
+            .drop_table(Table::drop().table(todo_dependency::Entity).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,6 @@
+pub mod tag;
+pub mod todo_dependency;
+pub mod todo_journal;
+pub mod todo_list;
+pub mod todo_reminder;
+pub mod todo_tag;
@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 标签
+///
+/// 名称不区分大小写唯一；待办上的标签仍以 JSON 形式保存在 todos.tags 中
+/// （对应 VTODO 的 CATEGORIES），本表和 todo_tags 由触发器随之维护
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "tags")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+    /// 颜色（#RRGGBB）
+    pub color: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::todo_tag::Entity")]
+    TodoTag,
+}
+
+impl Related<super::todo_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoTag.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
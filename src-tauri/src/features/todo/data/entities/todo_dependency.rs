@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 待办之间的阻塞关系：todo_id 需要等待 depends_on_id 完成
+///
+/// 与父子关系（parent_id）相互独立，同步为 `RELATED-TO;RELTYPE=DEPENDS-ON`；
+/// 待办被彻底删除时由触发器清理
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "todo_dependencies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub todo_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub depends_on_id: i32,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Todo 操作日志（用于撤销/重做）
+///
+/// 每条记录保存一个待办在一次操作前后的完整快照，
+/// 同一次操作影响多个待办时共用 batch_id
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "todo_journal")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub batch_id: String,
+    /// create | update | update_details | update_parent | move_list | delete
+    pub operation: String,
+    pub todo_id: i32,
+    /// 操作前的快照（JSON），为空表示操作前不存在
+    pub before_state: Option<String>,
+    /// 操作后的快照（JSON），为空表示操作后不存在
+    pub after_state: Option<String>,
+    #[sea_orm(default_value = false)]
+    pub undone: bool,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
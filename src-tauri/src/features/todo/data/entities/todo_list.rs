@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 待办清单（如“工作”、“家庭”）
+///
+/// 每个清单可以对应一个 CalDAV 日历集合；未设置 calendar_url 的清单
+/// 与不属于任何清单的待办一样，同步到 CalDAV 配置中的默认日历
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "todo_lists")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+    /// 颜色（#RRGGBB）
+    pub color: Option<String>,
+    /// 对应的 CalDAV 日历集合 URL
+    pub calendar_url: Option<String>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 待办的一个提醒（对应 VTODO 中的一个 VALARM）
+///
+/// 相对触发时 related 为 "start" 或 "due"，offset_minutes 为相对该时间的偏移（负数表示之前）；
+/// 绝对触发时 related 为空，使用 trigger_at。待办被彻底删除时由触发器清理
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "todo_reminders")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub todo_id: i32,
+    pub related: Option<String>,
+    pub offset_minutes: Option<i32>,
+    pub trigger_at: Option<DateTimeUtc>,
+    #[sea_orm(default_value = "DISPLAY")]
+    pub action: String,
+    pub description: Option<String>,
+    pub last_triggered_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
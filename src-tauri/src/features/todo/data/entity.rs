@@ -1,11 +1,18 @@
 use sea_orm::{entity::prelude::*, RelationDef, RelationTrait};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "todos")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub parent_id: Option<i32>,
+    /// 所属清单，为空表示不属于任何清单
+    #[serde(default)]
+    pub list_id: Option<i32>,
+    /// 同级待办中的手动排序位置（升序，为空时排在最后），对应 X-APPLE-SORT-ORDER
+    #[serde(default)]
+    pub sort_order: Option<i64>,
     pub uid: String,
     pub title: String,
     pub description: Option<String>,
@@ -28,6 +35,12 @@ pub struct Model {
     pub timezone: Option<String>,
     pub reminder_method: Option<String>,
     pub reminder_last_triggered_at: Option<DateTimeUtc>,
+    /// 稍后提醒的时间，到达时再提醒一次（之前会触发的提醒被跳过）
+    #[serde(default)]
+    pub snoozed_until: Option<DateTimeUtc>,
+    /// 预估需要的番茄数（仅本地保存）
+    #[serde(default)]
+    pub estimated_pomodoros: Option<i32>,
     pub completed_at: Option<DateTimeUtc>,
     #[sea_orm(default_value = false)]
     pub notified: bool,
@@ -39,6 +52,7 @@ pub struct Model {
     pub sync_token: Option<String>,
     pub last_synced_at: Option<DateTimeUtc>,
     pub deleted_at: Option<DateTimeUtc>,
+    pub purged_at: Option<DateTimeUtc>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
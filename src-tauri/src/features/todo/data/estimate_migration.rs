@@ -0,0 +1,71 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+/// 为 todos 添加预估番茄数 estimated_pomodoros
+#[derive(Debug, Clone, Copy)]
+pub struct EstimateMigration;
+
+impl MigrationName for EstimateMigration {
+    fn name(&self) -> &str {
+        "m20260105_000001_add_todo_estimate"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for EstimateMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+
+        println!("Adding pomodoro estimate to todos table...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        // 检查 estimated_pomodoros 列是否已存在
+        let check_column = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM pragma_table_info('todos') WHERE name='estimated_pomodoros';"
+                    .to_string(),
+            ))
+            .await;
+
+        let column_exists = if let Ok(Some(row)) = check_column {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        if !column_exists {
+            println!("  -> Adding estimated_pomodoros column...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "ALTER TABLE todos ADD COLUMN estimated_pomodoros INTEGER;".to_string(),
+            ))
+            .await
+            .context("failed to add estimated_pomodoros column")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        } else {
+            println!("  -> Column already exists, skipping...");
+        }
+
+        println!("Estimate migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "ALTER TABLE todos DROP COLUMN estimated_pomodoros;".to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::todo_journal;
+
+/// Todo 操作日志表迁移
+#[derive(Debug, Clone, Copy)]
+pub struct TodoJournalMigration;
+
+impl MigrationName for TodoJournalMigration {
+    fn name(&self) -> &str {
+        "m20251210_000001_create_todo_journal"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for TodoJournalMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Creating todo_journal table...");
+
+        let mut create_journal = schema.create_table_from_entity(todo_journal::Entity);
+        create_journal.if_not_exists();
+
+        db.execute(backend.build(&create_journal))
+            .await
+            .context("failed to create todo_journal table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        db.execute(Statement::from_string(
+            backend,
+            "CREATE INDEX IF NOT EXISTS idx_todo_journal_batch ON todo_journal(batch_id);"
+                .to_string(),
+        ))
+        .await
+        .context("failed to create todo_journal index")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Todo journal migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(todo_journal::Entity).to_owned())
+            .await
+    }
+}
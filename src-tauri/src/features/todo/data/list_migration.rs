@@ -0,0 +1,100 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::todo_list;
+
+/// 创建待办清单表，并为 todos 添加 list_id 字段
+#[derive(Debug, Clone, Copy)]
+pub struct TodoListMigration;
+
+impl MigrationName for TodoListMigration {
+    fn name(&self) -> &str {
+        "m20251220_000001_create_todo_lists"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for TodoListMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Adding todo lists...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        let mut create_lists = schema.create_table_from_entity(todo_list::Entity);
+        create_lists.if_not_exists();
+        db.execute(backend.build(&create_lists))
+            .await
+            .context("failed to create todo_lists table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        // 检查 list_id 列是否已存在
+        let check_column = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM pragma_table_info('todos') WHERE name='list_id';"
+                    .to_string(),
+            ))
+            .await;
+
+        let column_exists = if let Ok(Some(row)) = check_column {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        if !column_exists {
+            println!("  -> Adding list_id column...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "ALTER TABLE todos ADD COLUMN list_id INTEGER REFERENCES todo_lists(id) ON DELETE SET NULL;"
+                    .to_string(),
+            ))
+            .await
+            .context("failed to add list_id column")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        } else {
+            println!("  -> Column already exists, skipping...");
+        }
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_todo_lists_name ON todo_lists(name COLLATE NOCASE);
+            CREATE INDEX IF NOT EXISTS idx_todos_list ON todos(list_id);
+            "#
+            .to_string(),
+        ))
+        .await
+        .context("failed to create todo list indexes")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Todo list migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            DROP INDEX IF EXISTS idx_todos_list;
+            ALTER TABLE todos DROP COLUMN list_id;
+            "#
+            .to_string(),
+        ))
+        .await?;
+
+        manager
+            .drop_table(Table::drop().table(todo_list::Entity).to_owned())
+            .await
+    }
+}
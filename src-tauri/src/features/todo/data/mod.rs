@@ -3,5 +3,16 @@
 // 负责数据持久化：数据库实体定义、数据库迁移
 
 pub mod add_subtask_migration;
+pub mod dependency_migration;
+pub mod entities;
 pub mod entity;
+pub mod estimate_migration;
+pub mod journal_migration;
+pub mod list_migration;
 pub mod migration;
+pub mod reminder_migration;
+pub mod search_migration;
+pub mod snooze_migration;
+pub mod sort_order_migration;
+pub mod tags_migration;
+pub mod trash_migration;
@@ -0,0 +1,109 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::todo_reminder;
+
+/// 创建待办提醒表，支持每个待办多个提醒（VALARM）
+///
+/// 首次创建时把已有待办的 remind_before_minutes 迁移为相对截止时间的提醒；
+/// 与 todo_dependencies 一样不使用外键，由触发器在待办彻底删除时清理。
+#[derive(Debug, Clone, Copy)]
+pub struct TodoReminderMigration;
+
+impl MigrationName for TodoReminderMigration {
+    fn name(&self) -> &str {
+        "m20260102_000001_create_todo_reminders"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for TodoReminderMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Creating todo_reminders table...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        // 检查 todo_reminders 表是否已存在
+        let check_table = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM sqlite_master WHERE type='table' AND name='todo_reminders';"
+                    .to_string(),
+            ))
+            .await;
+
+        let table_exists = if let Ok(Some(row)) = check_table {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        if !table_exists {
+            let create_reminders = schema.create_table_from_entity(todo_reminder::Entity);
+            db.execute(backend.build(&create_reminders))
+                .await
+                .context("failed to create todo_reminders table")
+                .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+            println!("  -> Migrating existing reminder offsets...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r#"
+                INSERT INTO todo_reminders
+                    (todo_id, related, offset_minutes, action, last_triggered_at, created_at)
+                SELECT id, 'due', -remind_before_minutes, 'DISPLAY',
+                       NULLIF(reminder_last_triggered_at, ''), created_at
+                FROM todos
+                WHERE remind_before_minutes IS NOT NULL AND purged_at IS NULL;
+                "#
+                .to_string(),
+            ))
+            .await
+            .context("failed to migrate existing reminders")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        } else {
+            println!("  -> Table already exists, skipping...");
+        }
+
+        // 触发器随 todos 表重建而丢失，因此每次都确保存在
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_todo_reminders_todo ON todo_reminders(todo_id);
+
+            CREATE TRIGGER IF NOT EXISTS todo_reminders_ad AFTER DELETE ON todos BEGIN
+                DELETE FROM todo_reminders WHERE todo_id = old.id;
+            END;
+            "#
+            .to_string(),
+        ))
+        .await
+        .context("failed to create todo_reminders index and trigger")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Todo reminder migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "DROP TRIGGER IF EXISTS todo_reminders_ad;".to_string(),
+        ))
+        .await?;
+
+        manager
+            .drop_table(Table::drop().table(todo_reminder::Entity).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+/// 为 Todo 创建 FTS5 全文索引
+///
+/// 使用外部内容表（content='todos'）并通过触发器与 todos 保持同步，
+/// trigram 分词器可以直接对中文做子串匹配。
+#[derive(Debug, Clone, Copy)]
+pub struct TodoSearchMigration;
+
+impl MigrationName for TodoSearchMigration {
+    fn name(&self) -> &str {
+        "m20251201_000001_create_todo_fts"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for TodoSearchMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+
+        println!("Creating full-text search index for todos...");
+
+        match backend {
+            DbBackend::Sqlite => {
+                // 检查 todos_fts 表是否已存在
+                let check_table = db
+                    .query_one(Statement::from_string(
+                        DatabaseBackend::Sqlite,
+                        "SELECT COUNT(*) as count FROM sqlite_master WHERE type='table' AND name='todos_fts';".to_string(),
+                    ))
+                    .await;
+
+                let table_exists = if let Ok(Some(row)) = check_table {
+                    let count: i32 = row.try_get("", "count").unwrap_or(0);
+                    count > 0
+                } else {
+                    false
+                };
+
+                if !table_exists {
+                    println!("  -> Creating todos_fts virtual table...");
+                    db.execute(Statement::from_string(
+                        DatabaseBackend::Sqlite,
+                        r#"
+                        CREATE VIRTUAL TABLE todos_fts USING fts5(
+                            title,
+                            description,
+                            location,
+                            tags,
+                            content='todos',
+                            content_rowid='id',
+                            tokenize='trigram'
+                        );
+                        "#
+                        .to_string(),
+                    ))
+                    .await
+                    .context("failed to create todos_fts table")
+                    .map_err(|e| DbErr::Custom(e.to_string()))?;
+                } else {
+                    println!("  -> Table already exists, skipping...");
+                }
+
+                // 触发器随 todos 表重建而丢失，因此每次都确保存在
+                db.execute(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    r#"
+                    CREATE TRIGGER IF NOT EXISTS todos_fts_ai AFTER INSERT ON todos BEGIN
+                        INSERT INTO todos_fts(rowid, title, description, location, tags)
+                        VALUES (new.id, new.title, new.description, new.location, new.tags);
+                    END;
+
+                    CREATE TRIGGER IF NOT EXISTS todos_fts_ad AFTER DELETE ON todos BEGIN
+                        INSERT INTO todos_fts(todos_fts, rowid, title, description, location, tags)
+                        VALUES ('delete', old.id, old.title, old.description, old.location, old.tags);
+                    END;
+
+                    CREATE TRIGGER IF NOT EXISTS todos_fts_au
+                    AFTER UPDATE OF title, description, location, tags ON todos BEGIN
+                        INSERT INTO todos_fts(todos_fts, rowid, title, description, location, tags)
+                        VALUES ('delete', old.id, old.title, old.description, old.location, old.tags);
+                        INSERT INTO todos_fts(rowid, title, description, location, tags)
+                        VALUES (new.id, new.title, new.description, new.location, new.tags);
+                    END;
+                    "#
+                    .to_string(),
+                ))
+                .await
+                .context("failed to create todos_fts triggers")
+                .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+                if !table_exists {
+                    println!("  -> Indexing existing todos...");
+                    db.execute(Statement::from_string(
+                        DatabaseBackend::Sqlite,
+                        "INSERT INTO todos_fts(todos_fts) VALUES ('rebuild');".to_string(),
+                    ))
+                    .await
+                    .context("failed to build todos_fts index")
+                    .map_err(|e| DbErr::Custom(e.to_string()))?;
+                }
+            }
+            _ => {
+                return Err(DbErr::Custom("Unsupported database backend".to_string()));
+            }
+        }
+
+        println!("Full-text search migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        println!("Removing full-text search index...");
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            DROP TRIGGER IF EXISTS todos_fts_ai;
+            DROP TRIGGER IF EXISTS todos_fts_ad;
+            DROP TRIGGER IF EXISTS todos_fts_au;
+            DROP TABLE IF EXISTS todos_fts;
+            "#
+            .to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+/// 为 todos 添加稍后提醒时间 snoozed_until
+#[derive(Debug, Clone, Copy)]
+pub struct SnoozeMigration;
+
+impl MigrationName for SnoozeMigration {
+    fn name(&self) -> &str {
+        "m20251230_000001_add_todo_snooze"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for SnoozeMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+
+        println!("Adding snooze to todos table...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        // 检查 snoozed_until 列是否已存在
+        let check_column = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM pragma_table_info('todos') WHERE name='snoozed_until';"
+                    .to_string(),
+            ))
+            .await;
+
+        let column_exists = if let Ok(Some(row)) = check_column {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        if !column_exists {
+            println!("  -> Adding snoozed_until column...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "ALTER TABLE todos ADD COLUMN snoozed_until TIMESTAMP_WITH_TIMEZONE_TEXT;"
+                    .to_string(),
+            ))
+            .await
+            .context("failed to add snoozed_until column")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        } else {
+            println!("  -> Column already exists, skipping...");
+        }
+
+        println!("Snooze migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "ALTER TABLE todos DROP COLUMN snoozed_until;".to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+/// 为 todos 添加手动排序位置 sort_order（对应 VTODO 的 X-APPLE-SORT-ORDER）
+#[derive(Debug, Clone, Copy)]
+pub struct SortOrderMigration;
+
+impl MigrationName for SortOrderMigration {
+    fn name(&self) -> &str {
+        "m20251228_000001_add_todo_sort_order"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for SortOrderMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+
+        println!("Adding sort order to todos table...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        // 检查 sort_order 列是否已存在
+        let check_column = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM pragma_table_info('todos') WHERE name='sort_order';"
+                    .to_string(),
+            ))
+            .await;
+
+        let column_exists = if let Ok(Some(row)) = check_column {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        if !column_exists {
+            println!("  -> Adding sort_order column...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "ALTER TABLE todos ADD COLUMN sort_order BIGINT;".to_string(),
+            ))
+            .await
+            .context("failed to add sort_order column")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        } else {
+            println!("  -> Column already exists, skipping...");
+        }
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "CREATE INDEX IF NOT EXISTS idx_todos_parent_sort ON todos(parent_id, sort_order);"
+                .to_string(),
+        ))
+        .await
+        .context("failed to create sort order index")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        println!("Sort order migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            DROP INDEX IF EXISTS idx_todos_parent_sort;
+            ALTER TABLE todos DROP COLUMN sort_order;
+            "#
+            .to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
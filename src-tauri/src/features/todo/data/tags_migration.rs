@@ -0,0 +1,158 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Schema, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::entities::{tag, todo_tag};
+
+/// 创建标签表与待办-标签关联表
+///
+/// todos.tags（JSON）仍是待办标签的来源，CalDAV 同步直接读写它；
+/// 关联表通过触发器随 todos.tags 的变化自动维护，首次创建时从已有数据回填。
+#[derive(Debug, Clone, Copy)]
+pub struct TagsMigration;
+
+impl MigrationName for TagsMigration {
+    fn name(&self) -> &str {
+        "m20251215_000001_create_tags"
+    }
+}
+
+/// 把 new.tags 中的标签写入 tags 表并建立关联（触发器内使用）
+const LINK_NEW_TAGS: &str = r#"
+    INSERT OR IGNORE INTO tags(name, created_at)
+    SELECT DISTINCT trim(value), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END)
+    WHERE trim(value) <> '';
+    INSERT OR IGNORE INTO todo_tags(todo_id, tag_id)
+    SELECT new.id, tags.id
+    FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) AS t
+    JOIN tags ON tags.name = trim(t.value) COLLATE NOCASE;
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for TagsMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+
+        println!("Creating tags tables...");
+
+        if backend != DbBackend::Sqlite {
+            return Err(DbErr::Custom("Unsupported database backend".to_string()));
+        }
+
+        // 检查 tags 表是否已存在
+        let check_table = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) as count FROM sqlite_master WHERE type='table' AND name='tags';"
+                    .to_string(),
+            ))
+            .await;
+
+        let table_exists = if let Ok(Some(row)) = check_table {
+            let count: i32 = row.try_get("", "count").unwrap_or(0);
+            count > 0
+        } else {
+            false
+        };
+
+        let mut create_tags = schema.create_table_from_entity(tag::Entity);
+        create_tags.if_not_exists();
+        db.execute(backend.build(&create_tags))
+            .await
+            .context("failed to create tags table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        let mut create_todo_tags = schema.create_table_from_entity(todo_tag::Entity);
+        create_todo_tags.if_not_exists();
+        db.execute(backend.build(&create_todo_tags))
+            .await
+            .context("failed to create todo_tags table")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_name ON tags(name COLLATE NOCASE);
+            CREATE INDEX IF NOT EXISTS idx_todo_tags_tag ON todo_tags(tag_id);
+            "#
+            .to_string(),
+        ))
+        .await
+        .context("failed to create tags indexes")
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        // 触发器随 todos 表重建而丢失，因此每次都确保存在
+        let triggers = format!(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS todo_tags_ai AFTER INSERT ON todos BEGIN
+                {insert}
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS todo_tags_au AFTER UPDATE OF tags ON todos BEGIN
+                DELETE FROM todo_tags WHERE todo_id = new.id;
+                {insert}
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS todo_tags_ad AFTER DELETE ON todos BEGIN
+                DELETE FROM todo_tags WHERE todo_id = old.id;
+            END;
+            "#,
+            insert = LINK_NEW_TAGS,
+        );
+        db.execute(Statement::from_string(DatabaseBackend::Sqlite, triggers))
+            .await
+            .context("failed to create todo_tags triggers")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        if !table_exists {
+            println!("  -> Importing existing tags...");
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r#"
+                INSERT OR IGNORE INTO tags(name, created_at)
+                SELECT trim(json_each.value), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                FROM todos, json_each(todos.tags)
+                WHERE json_valid(todos.tags) AND trim(json_each.value) <> ''
+                ORDER BY todos.id;
+                INSERT OR IGNORE INTO todo_tags(todo_id, tag_id)
+                SELECT todos.id, tags.id
+                FROM todos, json_each(todos.tags)
+                JOIN tags ON tags.name = trim(json_each.value) COLLATE NOCASE
+                WHERE json_valid(todos.tags);
+                "#
+                .to_string(),
+            ))
+            .await
+            .context("failed to import existing tags")
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        }
+
+        println!("Tags migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            DROP TRIGGER IF EXISTS todo_tags_ai;
+            DROP TRIGGER IF EXISTS todo_tags_au;
+            DROP TRIGGER IF EXISTS todo_tags_ad;
+            "#
+            .to_string(),
+        ))
+        .await?;
+
+        manager
+            .drop_table(Table::drop().table(todo_tag::Entity).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(tag::Entity).to_owned())
+            .await
+    }
+}
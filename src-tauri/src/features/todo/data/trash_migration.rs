@@ -0,0 +1,75 @@
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+/// 为 Todo 添加回收站支持
+/// 添加 purged_at 字段：标记已彻底删除、仅等待同步删除远端的项
+#[derive(Debug, Clone, Copy)]
+pub struct TrashMigration;
+
+impl MigrationName for TrashMigration {
+    fn name(&self) -> &str {
+        "m20251205_000001_add_trash_support"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for TrashMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+
+        println!("Adding trash support to todos table...");
+
+        match backend {
+            DbBackend::Sqlite => {
+                // 检查 purged_at 列是否已存在
+                let check_column = db.query_one(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    "SELECT COUNT(*) as count FROM pragma_table_info('todos') WHERE name='purged_at';".to_string(),
+                ))
+                .await;
+
+                let column_exists = if let Ok(Some(row)) = check_column {
+                    let count: i32 = row.try_get("", "count").unwrap_or(0);
+                    count > 0
+                } else {
+                    false
+                };
+
+                if !column_exists {
+                    println!("  -> Adding purged_at column...");
+                    db.execute(Statement::from_string(
+                        DatabaseBackend::Sqlite,
+                        "ALTER TABLE todos ADD COLUMN purged_at TIMESTAMP_WITH_TIMEZONE_TEXT;"
+                            .to_string(),
+                    ))
+                    .await
+                    .context("failed to add purged_at column")
+                    .map_err(|e| DbErr::Custom(e.to_string()))?;
+                } else {
+                    println!("  -> Column already exists, skipping...");
+                }
+            }
+            _ => {
+                return Err(DbErr::Custom("Unsupported database backend".to_string()));
+            }
+        }
+
+        println!("Trash support migration completed successfully!");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        println!("Removing purged_at column...");
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "ALTER TABLE todos DROP COLUMN purged_at;".to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -8,8 +8,15 @@ use sea_orm_migration::MigrationTrait;
 use crate::core::{AppState, Feature};
 use crate::infrastructure::database::DatabaseRegistry;
 
-use super::core::scheduler::DueNotificationScheduler;
-use super::data::{add_subtask_migration, migration};
+use super::core::{scheduler::DueNotificationScheduler, trash};
+use super::data::{
+    add_subtask_migration, dependency_migration, estimate_migration, journal_migration,
+    list_migration, migration, reminder_migration, search_migration, snooze_migration,
+    sort_order_migration, tags_migration, trash_migration,
+};
+
+/// 回收站过期清理的检查间隔
+const TRASH_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
 
 /// Todo Feature
 ///
@@ -57,17 +64,117 @@ impl Feature for TodoFeature {
             let migration = add_subtask_migration::AddSubtaskMigration;
             Box::pin(async move { migration.up(manager).await })
         });
+
+        // 注册回收站支持迁移
+        registry.register_migration("trash_migration", |manager| {
+            let migration = trash_migration::TrashMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册待办清单迁移
+        registry.register_migration("todo_list_migration", |manager| {
+            let migration = list_migration::TodoListMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册操作日志表迁移
+        registry.register_migration("todo_journal_migration", |manager| {
+            let migration = journal_migration::TodoJournalMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册标签表迁移
+        registry.register_migration("tags_migration", |manager| {
+            let migration = tags_migration::TagsMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册阻塞关系表迁移
+        registry.register_migration("todo_dependency_migration", |manager| {
+            let migration = dependency_migration::TodoDependencyMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册手动排序字段迁移
+        registry.register_migration("todo_sort_order_migration", |manager| {
+            let migration = sort_order_migration::SortOrderMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册稍后提醒字段迁移
+        registry.register_migration("todo_snooze_migration", |manager| {
+            let migration = snooze_migration::SnoozeMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册预估番茄数字段迁移
+        registry.register_migration("todo_estimate_migration", |manager| {
+            let migration = estimate_migration::EstimateMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册多提醒迁移（迁移已有的 remind_before_minutes）
+        registry.register_migration("todo_reminder_migration", |manager| {
+            let migration = reminder_migration::TodoReminderMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
+
+        // 注册全文搜索索引迁移（依赖 todos 表结构，需在子任务迁移之后）
+        registry.register_migration("todo_search_migration", |manager| {
+            let migration = search_migration::TodoSearchMigration;
+            Box::pin(async move { migration.up(manager).await })
+        });
     }
 
     fn command_names(&self) -> Vec<&'static str> {
         vec![
             "list_todos",
+            "query_todos",
+            "search_todos",
             "create_todo",
+            "quick_add_todo",
             "update_todo",
             "delete_todo",
             "update_todo_details",
+            "snooze_todo",
+            "set_todo_estimate",
+            "bulk_todos",
             "get_subtasks",
+            "get_todo_tree",
             "update_todo_parent",
+            "reorder_todo",
+            "get_todo_recurrence_mode",
+            "set_todo_recurrence_mode",
+            "get_todo_subtask_settings",
+            "set_todo_subtask_settings",
+            "list_trashed_todos",
+            "restore_todo",
+            "purge_todo",
+            "empty_trash",
+            "get_trash_retention_days",
+            "set_trash_retention_days",
+            "undo_todo_change",
+            "redo_todo_change",
+            "get_todo_history_status",
+            "list_tags",
+            "rename_tag",
+            "merge_tags",
+            "delete_tag",
+            "set_tag_color",
+            "list_todo_lists",
+            "create_todo_list",
+            "update_todo_list",
+            "delete_todo_list",
+            "move_todo_to_list",
+            "get_todo_dependencies",
+            "add_todo_dependency",
+            "remove_todo_dependency",
+            "get_todo_reminders",
+            "set_todo_reminders",
+            "add_todo_reminder",
+            "remove_todo_reminder",
+            "get_todo_reminder_settings",
+            "set_todo_reminder_settings",
             "get_caldav_status",
             "save_caldav_config",
             "clear_caldav_config",
@@ -98,6 +205,21 @@ impl Feature for TodoFeature {
         // 触发首次调度
         scheduler.reschedule().await;
 
+        // 定期清理超过保留期的回收站项
+        let db = app_state.db().clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match trash::purge_expired(&db).await {
+                    Ok(count) if count > 0 => {
+                        println!("[TodoFeature] Purged {} expired todos from trash", count)
+                    }
+                    Ok(_) => {}
+                    Err(err) => eprintln!("[TodoFeature] Failed to purge expired todos: {err}"),
+                }
+                tokio::time::sleep(TRASH_PURGE_INTERVAL).await;
+            }
+        });
+
         println!("[TodoFeature] Initialized with due notification scheduler");
         Ok(())
     }
@@ -15,8 +15,11 @@ use url::Url;
 use uuid::Uuid;
 
 use super::config::CalDavConfig;
+use crate::features::todo::core::reminders::{ReminderInput, ReminderRelated, ReminderTrigger};
 
 const USER_AGENT: &str = "pet-focus-caldav/0.1";
+/// 本应用生成的 VCALENDAR 的 PRODID
+pub(super) const OWN_PRODID: &str = "-//pet-focus//EN";
 const CALENDAR_QUERY_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
 <cal:calendar-query xmlns:cal="urn:ietf:params:xml:ns:caldav" xmlns:d="DAV:">
     <d:prop>
@@ -45,10 +48,12 @@ pub struct CalDavItem {
     pub due: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub last_modified: Option<DateTime<Utc>>,
-    pub reminder_minutes: Option<i32>,
+    pub alarms: Vec<ReminderInput>, // 所有 VALARM
     pub timezone: Option<String>,
     pub recurrence_rule: Option<String>,
     pub related_to: Option<String>, // 父任务的 UID (用于子任务)
+    pub depends_on: Vec<String>,    // 阻塞该任务的其他任务 UID
+    pub sort_order: Option<i64>,    // X-APPLE-SORT-ORDER 手动排序位置
 }
 
 impl CalDavItem {
@@ -82,7 +87,24 @@ pub struct CalDavClient {
 
 impl CalDavClient {
     pub fn new(config: &CalDavConfig) -> Result<Self> {
-        let normalized_url = format!("{}/", config.url.trim_end_matches('/'));
+        Self::for_calendar(config, &config.url)
+    }
+
+    /// 使用同一账号访问另一个日历集合
+    ///
+    /// calendar 可以是完整 URL，也可以是相对于配置 URL 所在服务器的绝对路径
+    pub fn for_calendar(config: &CalDavConfig, calendar: &str) -> Result<Self> {
+        let calendar = calendar.trim();
+        let resolved = if calendar.starts_with('/') {
+            let base = Url::parse(&config.url).context("invalid CalDAV server URL")?;
+            base.join(calendar)
+                .with_context(|| format!("failed to resolve CalDAV calendar path: {calendar}"))?
+                .to_string()
+        } else {
+            calendar.to_string()
+        };
+
+        let normalized_url = format!("{}/", resolved.trim_end_matches('/'));
         eprintln!("[CalDAV] Creating client with URL: {}", normalized_url);
         eprintln!("[CalDAV] Username: {}", config.username);
 
@@ -753,7 +775,7 @@ enum Field {
     CalendarData,
 }
 
-fn parse_ical_todo(ics: &str) -> Result<CalDavItem> {
+pub(super) fn parse_ical_todo(ics: &str) -> Result<CalDavItem> {
     use ical::parser::ical::IcalParser;
 
     let mut parser = IcalParser::new(ics.as_bytes());
@@ -763,6 +785,10 @@ fn parse_ical_todo(ics: &str) -> Result<CalDavItem> {
         .context("failed to parse VCALENDAR")?
         .ok_or_else(|| anyhow!("missing VCALENDAR component"))?;
 
+    // 本应用以前生成的 TRIGGER 没有 RELATED 参数，实际相对截止时间（现在总是写出 RELATED）
+    let legacy_due_trigger = get_property_value(&calendar.properties, "PRODID")
+        .is_some_and(|prodid| prodid.trim() == OWN_PRODID);
+
     let mut todos_iter = calendar.todos.into_iter();
     let todo = todos_iter
         .next()
@@ -806,15 +832,37 @@ fn parse_ical_todo(ics: &str) -> Result<CalDavItem> {
     let (completed_at, _) = get_datetime_property(&todo.properties, "COMPLETED");
     let (last_modified, _) = get_datetime_property(&todo.properties, "LAST-MODIFIED");
     let recurrence_rule = get_property_value(&todo.properties, "RRULE");
+    // Apple 提醒事项与 Tasks.org 使用的手动排序位置
+    let sort_order = get_property_value(&todo.properties, "X-APPLE-SORT-ORDER")
+        .and_then(|value| value.trim().parse::<i64>().ok());
 
-    let reminder_minutes = todo
+    let alarms = todo
         .alarms
         .iter()
-        .find_map(|alarm| get_property_value(&alarm.properties, "TRIGGER"))
-        .and_then(parse_trigger_offset);
-
-    // 解析 RELATED-TO 字段（用于子任务）
-    let related_to = get_property_value(&todo.properties, "RELATED-TO");
+        .filter_map(|alarm| parse_alarm(&alarm.properties, legacy_due_trigger))
+        .collect();
+
+    // 解析 RELATED-TO 字段：未指定 RELTYPE 或 PARENT 为父任务，DEPENDS-ON 为阻塞关系
+    let mut related_to = None;
+    let mut depends_on = Vec::new();
+    for prop in todo
+        .properties
+        .iter()
+        .filter(|prop| prop.name.eq_ignore_ascii_case("RELATED-TO"))
+    {
+        let Some(value) = prop.value.as_ref().map(|value| value.trim().to_string()) else {
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+        let reltype = get_parameter(prop, "RELTYPE").unwrap_or_else(|| "PARENT".to_string());
+        if reltype.eq_ignore_ascii_case("PARENT") {
+            related_to.get_or_insert(value);
+        } else if reltype.eq_ignore_ascii_case("DEPENDS-ON") && !depends_on.contains(&value) {
+            depends_on.push(value);
+        }
+    }
 
     Ok(CalDavItem {
         uid,
@@ -829,10 +877,12 @@ fn parse_ical_todo(ics: &str) -> Result<CalDavItem> {
         due,
         completed_at,
         last_modified,
-        reminder_minutes,
+        alarms,
         timezone,
         recurrence_rule,
         related_to,
+        depends_on,
+        sort_order,
     })
 }
 
@@ -851,16 +901,19 @@ fn get_property_parameter(
     properties
         .iter()
         .find(|prop| prop.name.eq_ignore_ascii_case(name))
-        .and_then(|prop| prop.params.as_ref())
-        .and_then(|params| {
-            params.iter().find_map(|(param_name, values)| {
-                if param_name.eq_ignore_ascii_case(parameter) {
-                    values.first().cloned()
-                } else {
-                    None
-                }
-            })
+        .and_then(|prop| get_parameter(prop, parameter))
+}
+
+fn get_parameter(prop: &ical::property::Property, parameter: &str) -> Option<String> {
+    prop.params.as_ref().and_then(|params| {
+        params.iter().find_map(|(param_name, values)| {
+            if param_name.eq_ignore_ascii_case(parameter) {
+                values.first().cloned()
+            } else {
+                None
+            }
         })
+    })
 }
 
 fn get_datetime_property(
@@ -920,20 +973,78 @@ fn parse_ical_datetime(value: &str, tzid: Option<&str>) -> Result<DateTime<Utc>>
     Ok(DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc))
 }
 
-fn parse_trigger_offset(value: String) -> Option<i32> {
-    let trimmed = value.trim();
-    if !trimmed.starts_with('-') {
-        return None;
-    }
+/// 解析一个 VALARM：TRIGGER 可以是相对 DTSTART/DUE 的时长，也可以是 `VALUE=DATE-TIME` 的绝对时间
+fn parse_alarm(
+    properties: &[ical::property::Property],
+    legacy_due_trigger: bool,
+) -> Option<ReminderInput> {
+    let trigger_prop = properties
+        .iter()
+        .find(|prop| prop.name.eq_ignore_ascii_case("TRIGGER"))?;
+    let value = trigger_prop.value.as_deref()?.trim();
+
+    let is_absolute = get_parameter(trigger_prop, "VALUE")
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("DATE-TIME"));
+    let trigger = if is_absolute {
+        ReminderTrigger::Absolute {
+            at: parse_ical_datetime(value, None).ok()?,
+        }
+    } else {
+        let related = match get_parameter(trigger_prop, "RELATED") {
+            Some(related) if related.eq_ignore_ascii_case("END") => ReminderRelated::Due,
+            Some(_) => ReminderRelated::Start,
+            // RFC 5545 默认相对开始时间
+            None if legacy_due_trigger => ReminderRelated::Due,
+            None => ReminderRelated::Start,
+        };
+        ReminderTrigger::Relative {
+            related,
+            offset_minutes: parse_duration_minutes(value)?,
+        }
+    };
 
-    let trimmed = trimmed.trim_start_matches('-');
-    if let Some(minutes) = trimmed.strip_prefix("PT").and_then(|v| v.strip_suffix('M')) {
-        return minutes.parse::<i32>().ok();
-    }
+    Some(ReminderInput {
+        trigger,
+        action: get_property_value(properties, "ACTION"),
+        description: get_property_value(properties, "DESCRIPTION"),
+    })
+}
 
-    if let Some(hours) = trimmed.strip_prefix("PT").and_then(|v| v.strip_suffix('H')) {
-        return hours.parse::<i32>().ok().map(|h| h * 60);
+/// 解析 iCalendar 时长（如 `-PT15M`、`-P1D`、`P1DT2H`、`-P1W`），返回分钟数（不足一分钟的秒数舍去）
+fn parse_duration_minutes(value: &str) -> Option<i32> {
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() => in_time = true,
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let unit = match (c, in_time) {
+                    ('W', false) => 7 * 24 * 3600,
+                    ('D', false) => 24 * 3600,
+                    ('H', true) => 3600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+                seconds = seconds.checked_add(n.checked_mul(unit)?)?;
+            }
+            _ => return None,
+        }
+    }
+    if !number.is_empty() {
+        return None;
     }
 
-    None
+    let minutes = i32::try_from(seconds / 60).ok()?;
+    Some(if negative { -minutes } else { minutes })
 }
@@ -5,17 +5,23 @@ use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
 };
 use serde::Serialize;
 use serde_json::json;
 use tauri::{AppHandle, Manager, Wry};
 use tokio::sync::Mutex;
 
-use crate::features::todo::data::entity;
+use crate::features::todo::core::{
+    dependencies,
+    reminders::{self, ReminderInput, ReminderRelated, ReminderTrigger},
+    search, trash,
+};
+use crate::features::todo::data::{entities::todo_list, entity};
 
 use super::{
-    client::{CalDavClient, CalDavItem, RemoteTodo},
-    config::CalDavConfigService,
+    client::{CalDavClient, CalDavItem, RemoteTodo, UploadResult, OWN_PRODID},
+    config::{CalDavConfig, CalDavConfigService},
 };
 
 const SYNC_EVENT: &str = "caldav-sync-event";
@@ -194,8 +200,15 @@ impl CalDavSyncManager {
             }
         };
 
-        let client = CalDavClient::new(&config)?;
-        let summary = synchronize_database(self.db(), &client).await?;
+        let calendars = load_calendars(self.db(), &config).await?;
+        let summary = synchronize_database(self.db(), &calendars).await?;
+
+        // 同步会批量改写待办，完成后整体重建全文索引
+        if summary.created + summary.updated + summary.deleted > 0 {
+            if let Err(err) = search::rebuild_index(self.db()).await {
+                eprintln!("[CalDAV Sync] failed to rebuild search index: {err:#}");
+            }
+        }
         let synced_at = summary.synced_at.to_rfc3339();
 
         CalDavConfigService::set_last_sync(self.db(), Some(summary.synced_at)).await?;
@@ -289,13 +302,82 @@ struct SyncSummary {
     deleted: usize,
 }
 
+/// 参与同步的日历集合
+///
+/// 第一个是 CalDAV 配置中的默认日历，其后是各清单映射的日历（相同 URL 只出现一次）
+struct SyncCalendars {
+    clients: Vec<CalDavClient>,
+    /// 每个日历对应的清单，从该日历拉取的新待办归入此清单
+    list_ids: Vec<Option<i32>>,
+    /// 清单 ID -> 日历下标，未映射日历的清单使用默认日历
+    by_list: HashMap<i32, usize>,
+}
+
+impl SyncCalendars {
+    /// 待办应当所在的日历下标
+    fn index_for(&self, list_id: Option<i32>) -> usize {
+        list_id
+            .and_then(|id| self.by_list.get(&id).copied())
+            .unwrap_or(0)
+    }
+
+    fn client_for(&self, list_id: Option<i32>) -> &CalDavClient {
+        &self.clients[self.index_for(list_id)]
+    }
+
+    fn default_client(&self) -> &CalDavClient {
+        &self.clients[0]
+    }
+}
+
+async fn load_calendars(db: &DatabaseConnection, config: &CalDavConfig) -> Result<SyncCalendars> {
+    let mut calendars = SyncCalendars {
+        clients: vec![CalDavClient::new(config)?],
+        list_ids: vec![None],
+        by_list: HashMap::new(),
+    };
+
+    let lists = todo_list::Entity::find()
+        .order_by_asc(todo_list::Column::Id)
+        .all(db)
+        .await
+        .context("failed to load todo lists")?;
+
+    for list in lists {
+        let Some(calendar_url) = list.calendar_url.as_deref() else {
+            continue;
+        };
+
+        let client = CalDavClient::for_calendar(config, calendar_url)
+            .with_context(|| format!("invalid calendar URL for list \"{}\"", list.name))?;
+        let index = match calendars
+            .clients
+            .iter()
+            .position(|existing| existing.calendar_url() == client.calendar_url())
+        {
+            Some(index) => index,
+            None => {
+                calendars.clients.push(client);
+                calendars.list_ids.push(Some(list.id));
+                calendars.clients.len() - 1
+            }
+        };
+        calendars.by_list.insert(list.id, index);
+    }
+
+    Ok(calendars)
+}
+
 async fn synchronize_database(
     db: &DatabaseConnection,
-    client: &CalDavClient,
+    calendars: &SyncCalendars,
 ) -> Result<SyncSummary> {
     let now = Utc::now();
 
-    let remote_todos = client.fetch_todos().await?;
+    let mut remote_by_calendar = Vec::with_capacity(calendars.clients.len());
+    for client in &calendars.clients {
+        remote_by_calendar.push(client.fetch_todos().await?);
+    }
 
     let mut local_models = entity::Entity::find().all(db).await?;
     let mut by_href: HashMap<String, entity::Model> = HashMap::new();
@@ -310,21 +392,55 @@ async fn synchronize_database(
 
     let mut created = 0usize;
     let mut updated = 0usize;
+    let mut remote_dependencies: Vec<(String, Vec<String>)> = Vec::new();
+
+    for (index, remote_todos) in remote_by_calendar.iter().enumerate() {
+        let client = &calendars.clients[index];
 
-    for remote in &remote_todos {
-        if let Some(existing) = by_href.remove(&remote.href) {
-            by_uid.remove(&existing.uid);
-            update_local_from_remote(db, existing, remote, now, client).await?;
-            updated += 1;
-        } else if let Some(existing) = by_uid.remove(&remote.item.uid) {
-            if let Some(href) = existing.remote_url.clone() {
-                by_href.remove(&href);
+        for remote in remote_todos {
+            remote_dependencies.push((remote.item.uid.clone(), remote.item.depends_on.clone()));
+
+            let existing = if let Some(existing) = by_href.remove(&remote.href) {
+                by_uid.remove(&existing.uid);
+                Some(existing)
+            } else if let Some(existing) = by_uid.remove(&remote.item.uid) {
+                if let Some(href) = existing.remote_url.clone() {
+                    by_href.remove(&href);
+                }
+                Some(existing)
+            } else {
+                None
+            };
+
+            match existing {
+                Some(existing) => {
+                    // 仍在它所属清单对应的日历中时保留清单，否则归入该日历映射的清单
+                    let list_id = if calendars.index_for(existing.list_id) == index {
+                        existing.list_id
+                    } else {
+                        calendars.list_ids[index]
+                    };
+                    update_local_from_remote(db, existing, remote, now, client, list_id).await?;
+                    updated += 1;
+                }
+                None => {
+                    create_local_from_remote(db, remote, now, client, calendars.list_ids[index])
+                        .await?;
+                    created += 1;
+                }
             }
-            update_local_from_remote(db, existing, remote, now, client).await?;
-            updated += 1;
-        } else {
-            create_local_from_remote(db, remote, now, client).await?;
-            created += 1;
+        }
+    }
+
+    // 所有远端待办都已拉取后再解析 DEPENDS-ON，前置待办可能在其后才创建；
+    // 本地有未推送修改的待办保留本地关系
+    for (uid, depends_on) in remote_dependencies {
+        let local = entity::Entity::find()
+            .filter(entity::Column::Uid.eq(&uid))
+            .one(db)
+            .await?;
+        if let Some(local) = local.filter(|model| !model.dirty && model.deleted_at.is_none()) {
+            dependencies::replace_from_remote(db, local.id, &depends_on).await?;
         }
     }
 
@@ -339,32 +455,55 @@ async fn synchronize_database(
     for model in dirty_locals {
         // 如果是待删除的项（deleted_at 不为空）
         if model.deleted_at.is_some() {
-            delete_remote_todo(db, client, model).await?;
+            delete_remote_todo(db, calendars.default_client(), model, now).await?;
             deleted += 1;
         } else {
+            let client = calendars.client_for(model.list_id);
             push_local_to_remote(db, client, model, now).await?;
             pushed += 1;
         }
     }
 
+    // 远端已删除的项处理完毕后，移除回收站中已彻底删除的项
+    trash::finalize_purged(db).await?;
+
     // 检测远端已删除但本地仍存在的 todo（不在 remote_todos 中但有 remote_url 的本地项）
-    // 这些应该从本地删除
-    let remote_hrefs: std::collections::HashSet<String> =
-        remote_todos.iter().map(|r| r.href.clone()).collect();
+    // 这些移入本地回收站；本地有未推送修改的项已在上面重新上传，不做处理
+    let remote_hrefs: std::collections::HashSet<String> = remote_by_calendar
+        .iter()
+        .flatten()
+        .map(|r| r.href.clone())
+        .collect();
+    let synced_calendars: std::collections::HashSet<String> = calendars
+        .clients
+        .iter()
+        .map(|client| client.calendar_url().to_string())
+        .collect();
 
     for (href, local_model) in by_href {
+        // 所在日历本次没有拉取（例如清单映射已更改）时无法判断远端是否删除，跳过
+        let calendar_synced = local_model
+            .remote_calendar_url
+            .as_ref()
+            .is_none_or(|calendar| synced_calendars.contains(calendar));
+
         // 如果本地有 remote_url 但远端已不存在，且未被标记删除
-        if local_model.deleted_at.is_none() && !remote_hrefs.contains(&href) {
-            // 远端已删除，删除本地记录
-            entity::Entity::delete_by_id(local_model.id)
-                .exec(db)
-                .await
-                .with_context(|| {
-                    format!(
-                        "failed to delete local todo {} (removed from remote)",
-                        local_model.id
-                    )
-                })?;
+        if local_model.deleted_at.is_none()
+            && !local_model.dirty
+            && calendar_synced
+            && !remote_hrefs.contains(&href)
+        {
+            let id = local_model.id;
+            let mut active: entity::ActiveModel = local_model.into();
+            active.deleted_at = Set(Some(now));
+            active.remote_url = Set(None);
+            active.remote_etag = Set(None);
+            active.dirty = Set(false);
+            active.updated_at = Set(now);
+
+            active.update(db).await.with_context(|| {
+                format!("failed to move local todo {id} to trash (removed from remote)")
+            })?;
             deleted += 1;
         }
     }
@@ -384,6 +523,7 @@ async fn update_local_from_remote(
     remote: &RemoteTodo,
     now: DateTime<Utc>,
     client: &CalDavClient,
+    list_id: Option<i32>,
 ) -> Result<()> {
     // 如果本地已标记删除，跳过（等待同步删除到远端）
     if existing.deleted_at.is_some() {
@@ -418,12 +558,19 @@ async fn update_local_from_remote(
 
     let mut active: entity::ActiveModel = existing.clone().into();
     apply_remote_to_active(db, &mut active, &remote.item, remote, now, client).await;
+    active.list_id = Set(list_id);
 
-    active
+    let updated = active
         .update(db)
         .await
         .with_context(|| format!("failed to update local todo {} from CalDAV", existing.id))?;
 
+    reminders::replace_from_remote(db, updated.id, &remote.item.alarms).await?;
+    // 开始/截止时间变化后提醒时间随之变化，重新允许触发
+    if updated.due_date != existing.due_date || updated.start_at != existing.start_at {
+        reminders::reset_in(db, updated.id).await?;
+    }
+
     Ok(())
 }
 
@@ -432,33 +579,40 @@ async fn create_local_from_remote(
     remote: &RemoteTodo,
     now: DateTime<Utc>,
     client: &CalDavClient,
+    list_id: Option<i32>,
 ) -> Result<()> {
     use sea_orm::ActiveValue::NotSet;
 
     let mut active = entity::ActiveModel {
         id: NotSet,
         parent_id: Set(None), // 稍后会通过 RELATED-TO 设置
+        list_id: Set(list_id),
         ..Default::default()
     };
 
     apply_remote_to_active(db, &mut active, &remote.item, remote, now, client).await;
     active.created_at = Set(now);
 
-    active.insert(db).await.with_context(|| {
+    let inserted = active.insert(db).await.with_context(|| {
         format!(
             "failed to insert local todo from CalDAV resource {}",
             remote.href
         )
     })?;
 
+    reminders::replace_from_remote(db, inserted.id, &remote.item.alarms).await?;
+
     Ok(())
 }
 
-/// 删除远端 CalDAV todo 资源，并从本地数据库彻底移除
+/// 删除远端 CalDAV todo 资源，本地记录保留在回收站中
+///
+/// 解除与远端的关联后，从回收站恢复时会重新创建远端资源
 async fn delete_remote_todo(
     db: &DatabaseConnection,
     client: &CalDavClient,
     model: entity::Model,
+    now: DateTime<Utc>,
 ) -> Result<()> {
     // 如果有远端URL，则尝试删除远端资源
     if let Some(href) = &model.remote_url {
@@ -514,13 +668,19 @@ async fn delete_remote_todo(
         }
     }
 
-    // 从本地数据库彻底删除
-    entity::Entity::delete_by_id(model.id)
-        .exec(db)
+    let id = model.id;
+    let mut active: entity::ActiveModel = model.into();
+    active.remote_url = Set(None);
+    active.remote_etag = Set(None);
+    active.dirty = Set(false);
+    active.last_synced_at = Set(Some(now));
+
+    active
+        .update(db)
         .await
-        .with_context(|| format!("failed to delete todo {} from local database", model.id))?;
+        .with_context(|| format!("failed to detach trashed todo {id} from remote"))?;
 
-    eprintln!("Successfully deleted local todo {}", model.id);
+    eprintln!("Trashed todo {} detached from remote", id);
     Ok(())
 }
 
@@ -531,6 +691,27 @@ async fn push_local_to_remote(
     model: entity::Model,
     now: DateTime<Utc>,
 ) -> Result<()> {
+    let mut model = model;
+
+    // 待办已移到映射其他日历的清单：先从原日历删除，再在新日历中创建
+    if let (Some(href), Some(calendar)) = (&model.remote_url, &model.remote_calendar_url) {
+        if calendar.as_str() != client.calendar_url().as_str() {
+            eprintln!(
+                "Todo {} moved from {} to {}, deleting old resource {}",
+                model.id,
+                calendar,
+                client.calendar_url(),
+                href
+            );
+            client
+                .delete_todo(href, None)
+                .await
+                .with_context(|| format!("failed to remove todo {} from {calendar}", model.id))?;
+            model.remote_url = None;
+            model.remote_etag = None;
+        }
+    }
+
     let body = build_ical_from_model(db, &model).await;
 
     let upload = if let Some(href) = &model.remote_url {
@@ -543,9 +724,22 @@ async fn push_local_to_remote(
             Ok(upload) => upload,
             Err(err) => {
                 let err_msg = err.to_string();
+                let is_404 = err_msg.contains("404") || err_msg.contains("Not Found");
                 let is_412 = err_msg.contains("412") || err_msg.contains("Precondition Failed");
 
-                if is_412 {
+                if is_404 {
+                    // 远端已被删除（例如从回收站恢复的项），重新创建
+                    eprintln!(
+                        "Remote todo {} not found (404), re-creating on CalDAV",
+                        model.id
+                    );
+                    client
+                        .create_todo(&model.uid, &body)
+                        .await
+                        .with_context(|| {
+                            format!("failed to re-create todo {} on CalDAV", model.id)
+                        })?
+                } else if is_412 {
                     // 遇到 412 冲突，应用 Last-Write-Wins 策略
                     eprintln!(
                         "⚠️  412 Conflict detected for todo {}, applying Last-Write-Wins strategy",
@@ -553,10 +747,27 @@ async fn push_local_to_remote(
                     );
 
                     // 获取远端最新版本
-                    let remote_todo = client
-                        .get_todo(href)
-                        .await
-                        .context("failed to fetch remote todo after 412 conflict")?;
+                    let remote_todo = match client.get_todo(href).await {
+                        Ok(remote_todo) => remote_todo,
+                        Err(fetch_err)
+                            if fetch_err.to_string().contains("404")
+                                || fetch_err.to_string().contains("Not Found") =>
+                        {
+                            // 远端已被删除，重新创建后结束
+                            let upload =
+                                client
+                                    .create_todo(&model.uid, &body)
+                                    .await
+                                    .with_context(|| {
+                                        format!("failed to re-create todo {} on CalDAV", model.id)
+                                    })?;
+                            return persist_upload(db, client, model, upload, now).await;
+                        }
+                        Err(fetch_err) => {
+                            return Err(fetch_err)
+                                .context("failed to fetch remote todo after 412 conflict");
+                        }
+                    };
 
                     let local_modified = model.last_modified_at;
                     let remote_modified = remote_todo.item.last_modified.unwrap_or(now);
@@ -614,6 +825,17 @@ async fn push_local_to_remote(
             .with_context(|| format!("failed to create todo {} on CalDAV", model.id))?
     };
 
+    persist_upload(db, client, model, upload, now).await
+}
+
+/// 上传成功后记录远端位置与 ETag
+async fn persist_upload(
+    db: &DatabaseConnection,
+    client: &CalDavClient,
+    model: entity::Model,
+    upload: UploadResult,
+    now: DateTime<Utc>,
+) -> Result<()> {
     let mut active: entity::ActiveModel = model.into();
     active.dirty = Set(false);
     active.remote_url = Set(Some(upload.href.clone()));
@@ -663,6 +885,9 @@ async fn apply_remote_to_active(
         _ => None,
     };
 
+    // 更新已有待办时为 Unchanged，新建时为 NotSet
+    let existing_sort_order = active.sort_order.try_as_ref().copied().flatten();
+
     let existing_method = match &active.reminder_method {
         Set(Some(value)) => Some(value.clone()),
         _ => None,
@@ -674,7 +899,19 @@ async fn apply_remote_to_active(
         item.percent_complete
     };
 
-    let reminder_minutes = item.reminder_minutes.unwrap_or(existing_reminder).max(0);
+    // reminder_offset_minutes 跟随第一个相对截止时间的提醒
+    let reminder_minutes = item
+        .alarms
+        .iter()
+        .find_map(|alarm| match alarm.trigger {
+            ReminderTrigger::Relative {
+                related: ReminderRelated::Due,
+                offset_minutes,
+            } => Some(-offset_minutes),
+            _ => None,
+        })
+        .unwrap_or(existing_reminder)
+        .max(0);
 
     active.uid = Set(item.uid.clone());
     active.title = Set(item.summary.clone());
@@ -689,6 +926,8 @@ async fn apply_remote_to_active(
     active.last_modified_at = Set(item.last_modified.unwrap_or(now));
     active.due_date = Set(item.due);
     active.recurrence_rule = Set(item.recurrence_rule.clone());
+    // 远端没有排序位置时保留本地的
+    active.sort_order = Set(item.sort_order.or(existing_sort_order));
     active.reminder_offset_minutes = Set(reminder_minutes);
     active.timezone = Set(item.timezone.clone().or(existing_timezone));
     let reminder_method = existing_method.unwrap_or_else(|| "display".to_string());
@@ -757,7 +996,7 @@ async fn build_ical_from_model(db: &DatabaseConnection, model: &entity::Model) -
 
     lines.push("BEGIN:VCALENDAR".to_string());
     lines.push("VERSION:2.0".to_string());
-    lines.push("PRODID:-//pet-focus//EN".to_string());
+    lines.push(format!("PRODID:{OWN_PRODID}"));
 
     // 如果有时区，添加 VTIMEZONE 组件
     if let Some(ref tz) = model.timezone {
@@ -851,12 +1090,37 @@ async fn build_ical_from_model(db: &DatabaseConnection, model: &entity::Model) -
         }
     }
 
-    if model.reminder_offset_minutes > 0 {
-        lines.push("BEGIN:VALARM".to_string());
-        lines.push(format!("TRIGGER:-PT{}M", model.reminder_offset_minutes));
-        lines.push("ACTION:DISPLAY".to_string());
-        lines.push("DESCRIPTION:Reminder".to_string());
-        lines.push("END:VALARM".to_string());
+    if let Some(sort_order) = model.sort_order {
+        lines.push(format!("X-APPLE-SORT-ORDER:{}", sort_order));
+    }
+
+    // 处理阻塞关系 (RELATED-TO;RELTYPE=DEPENDS-ON)
+    match dependencies::dependency_uids(db, model.id).await {
+        Ok(uids) => {
+            for uid in uids {
+                lines.push(format!(
+                    "RELATED-TO;RELTYPE=DEPENDS-ON:{}",
+                    escape_ical_value(&uid)
+                ));
+            }
+        }
+        Err(err) => eprintln!(
+            "  -> Warning: Failed to load dependencies for UID={}: {err:?}",
+            model.uid
+        ),
+    }
+
+    // 每个提醒生成一个 VALARM
+    match reminders::remote_alarms(db, model.id).await {
+        Ok(alarms) => {
+            for alarm in alarms {
+                add_valarm(&mut lines, &alarm);
+            }
+        }
+        Err(err) => eprintln!(
+            "  -> Warning: Failed to load reminders for UID={}: {err:?}",
+            model.uid
+        ),
     }
 
     lines.push("END:VTODO".to_string());
@@ -866,6 +1130,58 @@ async fn build_ical_from_model(db: &DatabaseConnection, model: &entity::Model) -
     lines.join("\r\n")
 }
 
+/// 添加 VALARM 组件
+///
+/// 相对触发总是写出 RELATED，读取时只对旧数据按相对截止时间处理
+fn add_valarm(lines: &mut Vec<String>, alarm: &ReminderInput) {
+    lines.push("BEGIN:VALARM".to_string());
+    match alarm.trigger {
+        ReminderTrigger::Relative {
+            related: ReminderRelated::Due,
+            offset_minutes,
+        } => lines.push(format!(
+            "TRIGGER;RELATED=END:{}",
+            format_duration(offset_minutes)
+        )),
+        ReminderTrigger::Relative {
+            related: ReminderRelated::Start,
+            offset_minutes,
+        } => lines.push(format!(
+            "TRIGGER;RELATED=START:{}",
+            format_duration(offset_minutes)
+        )),
+        ReminderTrigger::Absolute { at } => {
+            lines.push(format!("TRIGGER;VALUE=DATE-TIME:{}", format_datetime(&at)))
+        }
+    }
+    let action = alarm.action.as_deref().unwrap_or("DISPLAY");
+    lines.push(format!("ACTION:{}", escape_ical_value(action)));
+    // DISPLAY 提醒必须带 DESCRIPTION
+    match &alarm.description {
+        Some(description) => lines.push(format!("DESCRIPTION:{}", escape_ical_value(description))),
+        None if action.eq_ignore_ascii_case("DISPLAY") => {
+            lines.push("DESCRIPTION:Reminder".to_string())
+        }
+        None => {}
+    }
+    lines.push("END:VALARM".to_string());
+}
+
+/// 格式化分钟数为 iCalendar 时长（如 `-PT15M`、`-P1D`）
+fn format_duration(minutes: i32) -> String {
+    let sign = if minutes < 0 { "-" } else { "" };
+    let minutes = minutes.unsigned_abs();
+    if minutes == 0 {
+        "PT0M".to_string()
+    } else if minutes.is_multiple_of(24 * 60) {
+        format!("{sign}P{}D", minutes / (24 * 60))
+    } else if minutes.is_multiple_of(60) {
+        format!("{sign}PT{}H", minutes / 60)
+    } else {
+        format!("{sign}PT{minutes}M")
+    }
+}
+
 /// 格式化 UTC 时间为 iCalendar 格式（带 Z 后缀表示 UTC）
 fn format_datetime(value: &DateTime<Utc>) -> String {
     value.format("%Y%m%dT%H%M%SZ").to_string()
@@ -915,3 +1231,80 @@ fn escape_ical_value(value: &str) -> String {
         .replace(',', "\\,")
         .replace(';', "\\;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::client::parse_ical_todo;
+    use super::*;
+
+    fn calendar_with_alarms(alarms: &[ReminderInput]) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{OWN_PRODID}"),
+            "BEGIN:VTODO".to_string(),
+            "UID:reminder-round-trip".to_string(),
+            "SUMMARY:Write report".to_string(),
+            "DTSTART:20250120T080000Z".to_string(),
+            "DUE:20250120T170000Z".to_string(),
+        ];
+        for alarm in alarms {
+            add_valarm(&mut lines, alarm);
+        }
+        lines.push("END:VTODO".to_string());
+        lines.push("END:VCALENDAR".to_string());
+        lines.join("\r\n")
+    }
+
+    fn alarm(trigger: ReminderTrigger) -> ReminderInput {
+        ReminderInput {
+            trigger,
+            action: Some("DISPLAY".to_string()),
+            description: Some("Reminder".to_string()),
+        }
+    }
+
+    #[test]
+    fn reminders_survive_round_trip() {
+        let alarms = vec![
+            alarm(ReminderTrigger::Relative {
+                related: ReminderRelated::Start,
+                offset_minutes: -15,
+            }),
+            alarm(ReminderTrigger::Relative {
+                related: ReminderRelated::Start,
+                offset_minutes: 0,
+            }),
+            alarm(ReminderTrigger::Relative {
+                related: ReminderRelated::Due,
+                offset_minutes: -24 * 60,
+            }),
+            alarm(ReminderTrigger::Absolute {
+                at: "2025-01-19T21:30:00Z".parse().unwrap(),
+            }),
+        ];
+
+        let ics = calendar_with_alarms(&alarms);
+        assert!(ics.contains("TRIGGER;RELATED=START:-PT15M"));
+
+        let parsed = parse_ical_todo(&ics).unwrap();
+        assert_eq!(parsed.alarms, alarms);
+    }
+
+    #[test]
+    fn legacy_trigger_without_related_is_due_relative() {
+        let ics = calendar_with_alarms(&[]).replace(
+            "END:VTODO",
+            "BEGIN:VALARM\r\nTRIGGER:-PT30M\r\nACTION:DISPLAY\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VTODO",
+        );
+
+        let parsed = parse_ical_todo(&ics).unwrap();
+        assert_eq!(
+            parsed.alarms,
+            vec![alarm(ReminderTrigger::Relative {
+                related: ReminderRelated::Due,
+                offset_minutes: -30,
+            })]
+        );
+    }
+}
@@ -1,13 +1,19 @@
 import { invoke } from "@tauri-apps/api/core"
 
-export type PomodoroMode = "focus" | "short_break" | "long_break" | "idle"
+export type PomodoroMode = "focus" | "short_break" | "long_break" | "idle" | "flow"
 
 export type PomodoroStatus = {
   running: boolean
   paused: boolean
   mode: PomodoroMode
   remainingSeconds: number
+  elapsedSeconds?: number
   round: number
+  flow?: boolean
+  todoId?: number | null
+  presetId?: number | null
+  adjustedSeconds?: number
+  awaitingConfirmation?: boolean
 }
 
 export type PomodoroConfig = {
@@ -15,6 +21,15 @@ export type PomodoroConfig = {
   shortBreakMinutes: number
   longBreakMinutes: number
   longBreakInterval: number
+  resumeOnRestart?: boolean
+  suspendPolicy?: "pause" | "finish"
+  autoCompleteTodo?: boolean
+  flowBreakRatio?: number
+  flowMinBreakMinutes?: number
+  flowMaxBreakMinutes?: number
+  autoStartBreaks?: boolean
+  autoStartFocus?: boolean
+  pauseTimeoutMinutes?: number
 }
 
 export type PomodoroSession = {
@@ -26,17 +41,117 @@ export type PomodoroSession = {
   end_at: string
   elapsed_seconds: number
   related_todo_id?: number | null
+  preset_id?: number | null
+  planned_seconds?: number | null
+  adjusted_seconds?: number
   created_at: string
   updated_at: string
 }
 
+export type PomodoroPreset = {
+  id: number
+  name: string
+  focus_minutes: number
+  short_break_minutes: number
+  long_break_minutes: number
+  long_break_interval: number
+  created_at: string
+  updated_at: string
+}
+
+export type PomodoroPresetInput = Pick<
+  PomodoroPreset,
+  "name" | "focus_minutes" | "short_break_minutes" | "long_break_minutes" | "long_break_interval"
+>
+
 export type PomodoroStats = {
   totalFocusSeconds: number
   sessionCount: number
 }
 
-export async function pomodoroStart(): Promise<PomodoroStatus> {
-  return await invoke<PomodoroStatus>("pomodoro_start")
+export type StatsBucket = "day" | "week" | "month"
+
+export type FocusAnalyticsRange = {
+  from: string
+  to: string
+  timezone?: string | null
+  presetId?: number | null
+}
+
+export type FocusBucket = {
+  start: string
+  focusSeconds: number
+  pomodoroCount: number
+}
+
+export type FocusStreaks = {
+  currentDays: number
+  longestDays: number
+  lastFocusDate: string | null
+}
+
+export type FocusHeatmap = {
+  seconds: number[][]
+}
+
+export type FocusBreakdown = {
+  outcomes: {
+    total: number
+    completed: number
+    stopped: number
+    skipped: number
+    completionRatio: number
+    stopRatio: number
+    skipRatio: number
+  }
+  todos: { todoId: number; title: string | null; focusSeconds: number; pomodoroCount: number }[]
+  tags: { tagId: number; name: string; focusSeconds: number; pomodoroCount: number }[]
+}
+
+export type GoalMetric = "focus_minutes" | "pomodoros"
+
+export type PomodoroGoal = {
+  metric: GoalMetric
+  dailyTarget: number
+  weekdayTargets: (number | null)[]
+}
+
+export type GoalProgress = {
+  date: string
+  metric: GoalMetric
+  target: number
+  progress: number
+  percent: number
+  reached: boolean
+  reachedAt: string | null
+}
+
+export type GoalMilestone = GoalProgress & {
+  milestone: 50 | 100
+}
+
+export async function pomodoroStart(
+  todoId?: number | null,
+  presetId?: number | null,
+  flow?: boolean,
+): Promise<PomodoroStatus> {
+  return await invoke<PomodoroStatus>("pomodoro_start", { todoId, presetId, flow })
+}
+
+export async function pomodoroFinish(): Promise<PomodoroStatus> {
+  return await invoke<PomodoroStatus>("pomodoro_finish")
+}
+
+export async function pomodoroConfirm(): Promise<PomodoroStatus> {
+  return await invoke<PomodoroStatus>("pomodoro_confirm")
+}
+
+export async function pomodoroAdjust(deltaSeconds: number): Promise<PomodoroStatus> {
+  return await invoke<PomodoroStatus>("pomodoro_adjust", { deltaSeconds })
+}
+
+export async function pomodoroSetRemaining(seconds: number): Promise<PomodoroStatus> {
+  return await invoke<PomodoroStatus>("pomodoro_set_remaining", { seconds })
 }
 
 export async function pomodoroPause(): Promise<PomodoroStatus> {
@@ -75,6 +190,87 @@ export async function deletePomodoroSession(sessionId: number): Promise<void> {
   await invoke("pomodoro_delete_session", { sessionId })
 }
 
-export async function getPomodoroStats(from: string, to: string): Promise<PomodoroStats> {
-  return await invoke<PomodoroStats>("pomodoro_stats", { payload: { from, to } })
+export async function getPomodoroStats(
+  from: string,
+  to: string,
+  presetId?: number | null,
+): Promise<PomodoroStats> {
+  return await invoke<PomodoroStats>("pomodoro_stats", {
+    payload: { from, to, preset_id: presetId ?? null },
+  })
+}
+
+function analyticsPayload(range: FocusAnalyticsRange) {
+  return {
+    from: range.from,
+    to: range.to,
+    timezone: range.timezone ?? Intl.DateTimeFormat().resolvedOptions().timeZone,
+    preset_id: range.presetId ?? null,
+  }
+}
+
+export async function getFocusSeries(
+  range: FocusAnalyticsRange,
+  bucket: StatsBucket = "day",
+): Promise<FocusBucket[]> {
+  return await invoke<FocusBucket[]>("pomodoro_stats_series", {
+    payload: { ...analyticsPayload(range), bucket },
+  })
+}
+
+export async function getFocusStreaks(timezone?: string | null): Promise<FocusStreaks> {
+  return await invoke<FocusStreaks>("pomodoro_stats_streaks", {
+    timezone: timezone ?? Intl.DateTimeFormat().resolvedOptions().timeZone,
+  })
+}
+
+export async function getFocusHeatmap(range: FocusAnalyticsRange): Promise<FocusHeatmap> {
+  return await invoke<FocusHeatmap>("pomodoro_stats_heatmap", { payload: analyticsPayload(range) })
+}
+
+export async function getFocusBreakdown(range: FocusAnalyticsRange): Promise<FocusBreakdown> {
+  return await invoke<FocusBreakdown>("pomodoro_stats_breakdown", { payload: analyticsPayload(range) })
+}
+
+export async function getPomodoroGoal(): Promise<PomodoroGoal | null> {
+  return await invoke<PomodoroGoal | null>("pomodoro_get_goal")
+}
+
+export async function setPomodoroGoal(goal: PomodoroGoal | null): Promise<void> {
+  await invoke("pomodoro_set_goal", { goal })
+}
+
+export async function getGoalProgress(): Promise<GoalProgress | null> {
+  return await invoke<GoalProgress | null>("pomodoro_goal_progress")
+}
+
+export async function getGoalHistory(from: string, to: string): Promise<GoalProgress[]> {
+  return await invoke<GoalProgress[]>("pomodoro_goal_history", { from, to })
+}
+
+export async function listPomodoroPresets(): Promise<PomodoroPreset[]> {
+  return await invoke<PomodoroPreset[]>("pomodoro_list_presets")
+}
+
+export async function createPomodoroPreset(payload: PomodoroPresetInput): Promise<PomodoroPreset> {
+  return await invoke<PomodoroPreset>("pomodoro_create_preset", { payload })
+}
+
+export async function updatePomodoroPreset(
+  id: number,
+  payload: PomodoroPresetInput,
+): Promise<PomodoroPreset> {
+  return await invoke<PomodoroPreset>("pomodoro_update_preset", { id, payload })
+}
+
+export async function deletePomodoroPreset(id: number): Promise<void> {
+  await invoke("pomodoro_delete_preset", { id })
+}
+
+export async function getActivePomodoroPreset(): Promise<PomodoroPreset | null> {
+  return await invoke<PomodoroPreset | null>("pomodoro_get_active_preset")
+}
+
+export async function setActivePomodoroPreset(id: number | null): Promise<void> {
+  await invoke("pomodoro_set_active_preset", { id })
 }
@@ -16,10 +16,11 @@ import {
   useAdjustedTimes,
   useSaveAdjustedTimes,
 } from "@/features/pomodoro/hooks"
+import { setActivePomodoroPreset } from "@/features/pomodoro/api/pomodoro.api"
 import { TimeAdjustmentDialog } from "./time-adjustment-dialog"
 
 export function PomodoroTimer() {
-  const { status, isBusy, start, pause, resume, skip, stop, display } = usePomodoro()
+  const { status, isBusy, start, pause, resume, confirm, skip, stop, display } = usePomodoro()
   const { data: activeSession, isLoading: sessionLoading } = useActiveSession()
   const { data: sessionRecords } = useSessionRecords(activeSession?.id ?? 0)
   const { data: sessionTitle } = useSessionTitle(activeSession?.id ?? 0)
@@ -49,6 +50,7 @@ export function PomodoroTimer() {
 
   const isRunning = status?.running ?? false
   const isPaused = status?.paused ?? false
+  const isAwaiting = status?.awaitingConfirmation ?? false
 
   const handleAutoConfirm = async () => {
     // 清理倒计时定时器
@@ -70,6 +72,12 @@ export function PomodoroTimer() {
       return
     }
 
+    // 关闭自动开始时由用户确认开始下一阶段，不再弹出自动继续的对话框
+    if (isAwaiting) {
+      previousModeRef.current = status.mode
+      return
+    }
+
     const currentMode = status.mode
     const previousMode = previousModeRef.current
 
@@ -104,7 +112,7 @@ export function PomodoroTimer() {
     }
 
     previousModeRef.current = currentMode
-  }, [status?.mode, isRunning, isPaused, pause, resume])
+  }, [status?.mode, isRunning, isPaused, isAwaiting, pause, resume])
 
   // 清理倒计时定时器
   useEffect(() => {
@@ -189,6 +197,8 @@ export function PomodoroTimer() {
             focusMinutes: minutes,
           },
         })
+        // 调整后的时长写在默认配置中，需要改回默认配置，否则开始计时时会被当前预设的时长覆盖
+        await setActivePomodoroPreset(null)
         
         // 如果是自动切换，恢复计时；否则开始新计时
         if (isAutoTransition) {
@@ -213,6 +223,7 @@ export function PomodoroTimer() {
             shortBreakMinutes: minutes,
           },
         })
+        await setActivePomodoroPreset(null)
         
         // 如果是自动切换，恢复计时；否则开始新计时
         if (isAutoTransition) {
@@ -323,7 +334,8 @@ export function PomodoroTimer() {
         <CardHeader>
           <CardTitle className="text-2xl font-semibold">番茄钟</CardTitle>
           <CardDescription>
-            {display.modeLabel} {isRunning ? (isPaused ? "(已暂停)" : "进行中") : "(空闲)"}
+            {display.modeLabel}{" "}
+            {isRunning ? (isAwaiting ? "(等待开始)" : isPaused ? "(已暂停)" : "进行中") : "(空闲)"}
           </CardDescription>
         </CardHeader>
         <CardContent className="space-y-6">
@@ -344,6 +356,10 @@ export function PomodoroTimer() {
               >
                 <Play className="size-4" /> 开始专注
               </Button>
+            ) : isAwaiting ? (
+              <Button size="lg" className="min-w-32 gap-2" onClick={() => confirm()} disabled={isBusy}>
+                <Play className="size-4" /> 开始{display.modeLabel}
+              </Button>
             ) : isPaused ? (
               <Button size="lg" className="min-w-32 gap-2" onClick={() => resume()} disabled={isBusy}>
                 <Play className="size-4" /> 继续
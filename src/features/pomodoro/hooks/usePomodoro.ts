@@ -4,6 +4,7 @@ import {
   pomodoroStart,
   pomodoroPause,
   pomodoroResume,
+  pomodoroConfirm,
   pomodoroSkip,
   pomodoroStop,
   pomodoroStatus,
@@ -16,6 +17,7 @@ type UsePomodoroReturn = {
   start: () => Promise<void>
   pause: () => Promise<void>
   resume: () => Promise<void>
+  confirm: () => Promise<void>
   skip: () => Promise<void>
   stop: () => Promise<void>
   display: {
@@ -39,6 +41,8 @@ function modeToLabel(mode: PomodoroStatus["mode"] | undefined): string {
       return "短休"
     case "long_break":
       return "长休"
+    case "flow":
+      return "心流"
     case "idle":
     default:
       return "空闲"
@@ -106,6 +110,16 @@ export function usePomodoro(): UsePomodoroReturn {
     }
   }, [])
 
+  const confirm = useCallback(async () => {
+    setIsBusy(true)
+    try {
+      const s = await pomodoroConfirm()
+      setStatus(s)
+    } finally {
+      setIsBusy(false)
+    }
+  }, [])
+
   const skip = useCallback(async () => {
     setIsBusy(true)
     try {
@@ -133,6 +147,6 @@ export function usePomodoro(): UsePomodoroReturn {
     }
   }, [status])
 
-  return { status, isBusy, start, pause, resume, skip, stop, display }
+  return { status, isBusy, start, pause, resume, confirm, skip, stop, display }
 }
 